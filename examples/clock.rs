@@ -1,6 +1,5 @@
 #[macro_use]
 extern crate limn;
-extern crate euclid;
 
 extern crate chrono;
 
@@ -11,64 +10,40 @@ use std::{thread, time};
 
 use chrono::{Local, Timelike};
 
-use limn::webrender::api::*;
 use limn::prelude::*;
 use limn::draw::ellipse::{EllipseState, EllipseStyle};
 
-type Radians = euclid::Radians<f32>;
-
 struct ClockTick;
 
+/// A clock hand pointing down from the center of its bounds, rotated by the widget's transform
 pub struct ClockHand {
     color: Color,
     width: f32,
     length: f32,
-    rotation: Radians,
 }
 impl ClockHand {
-    pub fn new(color: Color, width: f32, length: f32, rotation: Radians) -> Self {
+    pub fn new(color: Color, width: f32, length: f32) -> Self {
         ClockHand {
             color: color,
             width: width,
             length: length,
-            rotation: rotation,
         }
     }
 }
 
 impl Draw for ClockHand {
     fn draw(&mut self, bounds: Rect, _: Rect, renderer: &mut RenderBuilder) {
-        let transform = rotation_transform(&bounds.center(),
-            self.rotation + Radians::new(f32::consts::PI));
-        renderer.builder.push_stacking_context(
-            &PrimitiveInfo::new(Rect::zero()),
-            ScrollPolicy::Fixed,
-            Some(PropertyBinding::Value(transform)),
-            TransformStyle::Flat,
-            None,
-            MixBlendMode::Normal,
-            Vec::new(),
-        );
         let rect = Rect::new(
             bounds.center() + Size::new(-self.width / 2.0, 0.0),
             Size::new(self.width, self.length)
         );
-        renderer.builder.push_rect(
-            &PrimitiveInfo::new(rect),
-            self.color.into());
-        renderer.builder.pop_stacking_context();
+        renderer.push_rect(rect, self.color);
     }
 }
 
-fn rotation_transform(origin: &LayoutPoint, rotation: Radians) -> LayoutTransform {
-    let pre_transform = LayoutTransform::create_translation(origin.x, origin.y, 0.0);
-    let post_transform = LayoutTransform::create_translation(-origin.x, -origin.y, -0.0);
-    let transform = LayoutTransform::identity().pre_rotate(0.0, 0.0, 1.0, -rotation);
-    pre_transform.pre_mul(&transform).pre_mul(&post_transform)
-}
-
-fn rotation(fraction: f32) -> Radians {
-    Radians::new(2.0 * f32::consts::PI * fraction)
+/// Rotate a hand pointing down to point at a fraction of a turn from 12 o'clock
+fn rotation(fraction: f32) -> WidgetTransform {
+    WidgetTransform::new().rotate(2.0 * f32::consts::PI * fraction + f32::consts::PI)
 }
 
 struct ClockBuilder {
@@ -89,22 +64,25 @@ impl ClockBuilder {
         let second_angle = || rotation(Local::now().second() as f32 / 60.0);
         let mut hour_widget = WidgetBuilder::new("hours");
         hour_widget
-            .set_draw_state(ClockHand::new(BLACK, 4.0, 60.0, hour_angle()))
-            .add_handler(DrawEventHandler::new(ClockTick, move |state: &mut ClockHand| {
-                state.rotation = hour_angle()
-            }));
+            .set_draw_state(ClockHand::new(BLACK, 4.0, 60.0))
+            .set_transform(hour_angle())
+            .add_handler(move |_: &ClockTick, mut args: EventArgs| {
+                args.widget.set_transform(hour_angle());
+            });
         let mut minute_widget = WidgetBuilder::new("minutes");
         minute_widget
-            .set_draw_state(ClockHand::new(BLACK, 3.0, 90.0, minute_angle()))
-            .add_handler(DrawEventHandler::new(ClockTick, move |state: &mut ClockHand| {
-                state.rotation = minute_angle()
-            }));
+            .set_draw_state(ClockHand::new(BLACK, 3.0, 90.0))
+            .set_transform(minute_angle())
+            .add_handler(move |_: &ClockTick, mut args: EventArgs| {
+                args.widget.set_transform(minute_angle());
+            });
         let mut second_widget = WidgetBuilder::new("seconds");
         second_widget
-            .set_draw_state(ClockHand::new(RED, 2.0, 80.0, second_angle()))
-            .add_handler(DrawEventHandler::new(ClockTick, move |state: &mut ClockHand| {
-                state.rotation = second_angle()
-            }));
+            .set_draw_state(ClockHand::new(RED, 2.0, 80.0))
+            .set_transform(second_angle())
+            .add_handler(move |_: &ClockTick, mut args: EventArgs| {
+                args.widget.set_transform(second_angle());
+            });
 
         widget
             .add_child(hour_widget)
@@ -0,0 +1,148 @@
+#[macro_use]
+extern crate limn;
+
+mod util;
+
+use limn::prelude::*;
+
+use limn::widgets::drag_drop::{DragSource, DropTarget};
+use limn::widgets::text::TextBuilder;
+use limn::draw::rect::{RectState, RectStyle};
+
+/// Payload of a dragged item, its position in the panels
+#[derive(Debug, Copy, Clone)]
+struct Item {
+    panel: usize,
+    index: usize,
+}
+
+/// Move the item to a panel, before the item at `index`, or at the end of the panel
+#[derive(Debug, Copy, Clone)]
+struct MoveItem {
+    item: Item,
+    panel: usize,
+    index: Option<usize>,
+}
+
+struct PanelsHandler {
+    panels: Vec<WidgetRef>,
+    items: Vec<Vec<String>>,
+}
+
+impl PanelsHandler {
+    fn build_item(&self, panel: usize, index: usize) -> WidgetBuilder {
+        let name = &self.items[panel][index];
+        let mut item_widget = WidgetBuilder::new("item");
+        item_widget
+            .set_draw_state_with_style(RectState::new(), style!(
+                RectStyle::BackgroundColor: selector!(GRAY_80, DRAG_OVER: BLUE_HIGHLIGHT),
+                RectStyle::CornerRadius: Some(3.0)))
+            .set_drag_source(DragSource::new(Item { panel: panel, index: index }).set_preview({
+                let name = name.clone();
+                move || {
+                    let mut preview = WidgetBuilder::new("item_preview");
+                    preview.set_draw_state_with_style(RectState::new(), style!(
+                        RectStyle::BackgroundColor: GRAY_70,
+                        RectStyle::Border: Some((1.0, GRAY_30))));
+                    let mut text = TextBuilder::new(&name);
+                    text.layout().add(bound_by(&preview).padding(5.0));
+                    preview.add_child(text);
+                    preview
+                }
+            }))
+            .set_drop_target(DropTarget::new().accept::<Item>())
+            .on_drop(move |item: &Item, _, args| {
+                args.ui.event(MoveItem { item: *item, panel: panel, index: Some(index) });
+            });
+        item_widget.layout().add(match_width(&self.panels[panel]).padding(10.0));
+        let mut text = TextBuilder::new(name);
+        text.layout().add(bound_by(&item_widget).padding(5.0));
+        item_widget.add_child(text);
+        item_widget
+    }
+    /// Recreate the item widgets in a panel, so their payloads match their new positions
+    fn update_panel(&mut self, panel: usize) {
+        let mut panel_widget = self.panels[panel].clone();
+        for child in panel_widget.children() {
+            panel_widget.remove_child(child);
+        }
+        for index in 0..self.items[panel].len() {
+            let item_widget = self.build_item(panel, index);
+            panel_widget.add_child(item_widget);
+        }
+    }
+}
+
+impl EventHandler<MoveItem> for PanelsHandler {
+    fn handle(&mut self, event: &MoveItem, _: EventArgs) {
+        let MoveItem { item, panel, index } = *event;
+        let name = self.items[item.panel].remove(item.index);
+        let len = self.items[panel].len();
+        let mut index = index.unwrap_or(len);
+        // removing the item shifts the items after it in the same panel
+        if item.panel == panel && item.index < index {
+            index -= 1;
+        }
+        self.items[panel].insert(index.min(len), name);
+        self.update_panel(item.panel);
+        if item.panel != panel {
+            self.update_panel(panel);
+        }
+    }
+}
+
+fn main() {
+    let window_builder = glutin::WindowBuilder::new()
+        .with_title("Limn drag and drop demo")
+        .with_min_dimensions(400, 300);
+    let mut app = util::init(window_builder);
+    let mut root = WidgetBuilder::new("root");
+    root.layout().add(min_size(Size::new(400.0, 300.0)));
+
+    let mut panels = Vec::new();
+    for panel in 0..2 {
+        let mut settings = LinearLayoutSettings::new(Orientation::Vertical);
+        settings.item_align = ItemAlignment::Center;
+        settings.padding = 10.0;
+        let mut panel_widget = WidgetBuilder::new("panel");
+        panel_widget
+            .set_draw_state_with_style(RectState::new(), style!(
+                RectStyle::BackgroundColor: WHITE,
+                RectStyle::Border: selector!(Some((1.0, GRAY_50)), DRAG_OVER: Some((2.0, BLUE_HIGHLIGHT)))))
+            .linear_layout(settings)
+            .set_drop_target(DropTarget::new().accept::<Item>())
+            // drops on items are handled by the items, the innermost drop target
+            .on_drop(move |item: &Item, _, args| {
+                args.ui.event(MoveItem { item: *item, panel: panel, index: None });
+            });
+        panel_widget.layout().add(constraints![
+            align_top(&root).padding(20.0),
+            align_bottom(&root).padding(20.0),
+            min_width(150.0),
+        ]);
+        panels.push(panel_widget);
+    }
+    panels[0].layout().add(align_left(&root).padding(20.0));
+    let left_panel = panels[0].widget_ref();
+    panels[1].layout().add(constraints![
+        to_right_of(&left_panel).padding(20.0),
+        align_right(&root).padding(20.0),
+        match_width(&left_panel),
+    ]);
+
+    let mut handler = PanelsHandler {
+        panels: panels.iter().map(|panel| panel.widget_ref()).collect(),
+        items: vec![
+            vec!["Apples".to_owned(), "Bananas".to_owned(), "Cherries".to_owned(), "Dates".to_owned()],
+            vec!["Eggplant".to_owned(), "Fennel".to_owned()],
+        ],
+    };
+    for panel in panels {
+        root.add_child(panel);
+    }
+    handler.update_panel(0);
+    handler.update_panel(1);
+    app.add_handler(handler);
+
+    app.main_loop(root);
+}
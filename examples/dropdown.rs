@@ -0,0 +1,48 @@
+extern crate limn;
+
+mod util;
+
+use limn::prelude::*;
+use limn::widgets::dropdown::DropdownBuilder;
+use limn::widgets::text::TextBuilder;
+use limn::draw::text::TextState;
+
+fn main() {
+    let window_builder = glutin::WindowBuilder::new()
+        .with_title("Limn dropdown demo")
+        .with_min_dimensions(100, 100);
+    let app = util::init(window_builder);
+    let mut root = WidgetBuilder::new("root");
+    let mut layout_settings = LinearLayoutSettings::new(Orientation::Vertical);
+    layout_settings.padding = 10.0;
+    root.linear_layout(layout_settings);
+    root.layout().add(min_size(Size::new(300.0, 300.0)));
+
+    let status = TextBuilder::new("");
+    let status_ref = status.widget_ref();
+
+    let mut dropdown = DropdownBuilder::new();
+    let color_status = status_ref.clone();
+    dropdown
+        .set_items(vec!["Red", "Green", "Blue"])
+        .set_selected(0)
+        .on_selected(move |event, _| {
+            color_status.clone().update(|state: &mut TextState| state.text = format!("Color: {}", event.value));
+        });
+
+    let mut filtered = DropdownBuilder::new();
+    let fruit_status = status_ref.clone();
+    filtered
+        .set_items(vec!["Apple", "Apricot", "Banana", "Blackberry", "Cherry", "Grape", "Lemon"])
+        .enable_filter()
+        .on_selected(move |event, _| {
+            fruit_status.clone().update(|state: &mut TextState| state.text = format!("Fruit: {}", event.value));
+        });
+
+    root
+        .add_child(dropdown)
+        .add_child(filtered)
+        .add_child(status);
+
+    app.main_loop(root);
+}
@@ -0,0 +1,95 @@
+extern crate limn;
+
+mod util;
+
+use limn::prelude::*;
+
+use limn::widgets::button::PushButtonBuilder;
+use limn::widgets::edit_text::EditTextBuilder;
+use limn::widgets::form::FormBuilder;
+use limn::widgets::text::TextBuilder;
+use limn::widgets::validation::{Validator, InputFilter};
+use limn::draw::text::TextState;
+
+fn main() {
+    let window_builder = glutin::WindowBuilder::new()
+        .with_title("Limn form demo")
+        .with_min_dimensions(100, 100);
+    let app = util::init(window_builder);
+    let mut root = WidgetBuilder::new("root");
+    root.layout().add(min_size(Size::new(400.0, 300.0)));
+
+    let mut form = FormBuilder::new();
+    form.layout().add(match_layout(&root).padding(20.0));
+
+    let mut name_title = TextBuilder::new("Username");
+    name_title.layout().add(constraints![
+        align_top(&form),
+        align_left(&form),
+    ]);
+    let mut name = EditTextBuilder::new();
+    name.add_filter(InputFilter::max_length(16))
+        .add_validator(Validator::required())
+        .add_validator(Validator::regex("[a-z][a-z0-9_]*")
+            .with_message("Lowercase letters, digits and underscores only"));
+    let mut name_error = name.error_label();
+    name.layout().add(constraints![
+        below(&name_title).padding(5.0),
+        align_left(&form),
+        align_right(&form),
+    ]);
+    name_error.layout().add(constraints![
+        below(&name).padding(5.0),
+        align_left(&form),
+    ]);
+
+    let mut age_title = TextBuilder::new("Age");
+    age_title.layout().add(constraints![
+        below(&name_error).padding(10.0),
+        align_left(&form),
+    ]);
+    let mut age = EditTextBuilder::new();
+    age.add_filter(InputFilter::integer())
+        .add_validator(Validator::required())
+        .add_validator(Validator::range(0.0..150.0))
+        .show_errors_in_tooltip();
+    age.layout().add(constraints![
+        below(&age_title).padding(5.0),
+        align_left(&form),
+        align_right(&form),
+    ]);
+
+    let mut status = TextBuilder::new("");
+    let status_ref = status.widget_ref();
+
+    let mut submit = PushButtonBuilder::new();
+    let submit_status = status_ref.clone();
+    submit
+        .set_text("Submit")
+        .on_click(move |_, _| {
+            submit_status.clone().update(|state: &mut TextState| state.text = "Submitted".to_owned());
+        });
+    submit.layout().add(constraints![
+        below(&age).padding(20.0),
+        align_right(&form),
+    ]);
+    status.layout().add(constraints![
+        center_vertical(&submit),
+        align_left(&form),
+    ]);
+    form.add_submit_button(&submit)
+        .on_validity_changed(move |valid, _| {
+            let text = if valid { "" } else { "Fix the errors above to submit" };
+            status_ref.clone().update(|state: &mut TextState| state.text = text.to_owned());
+        });
+
+    form.add_child(name_title)
+        .add_child(name)
+        .add_child(name_error)
+        .add_child(age_title)
+        .add_child(age)
+        .add_child(submit)
+        .add_child(status);
+    root.add_child(form);
+    app.main_loop(root);
+}
@@ -4,6 +4,7 @@ mod util;
 
 use limn::prelude::*;
 use limn::widgets::image::ImageBuilder;
+use limn::widgets::icon::IconBuilder;
 
 fn main() {
     let window_builder = glutin::WindowBuilder::new()
@@ -17,7 +18,14 @@ fn main() {
         center(&root),
         bound_by(&root).padding(50.0),
     ]);
+    let mut icon_widget = IconBuilder::new("star.svg");
+    icon_widget.layout().add(constraints![
+        size(Size::new(48.0, 48.0)),
+        align_top(&root).padding(10.0),
+        align_right(&root).padding(10.0),
+    ]);
     root.add_child(image_widget);
+    root.add_child(icon_widget);
 
     app.main_loop(root);
 }
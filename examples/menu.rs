@@ -0,0 +1,125 @@
+extern crate limn;
+
+mod util;
+
+use limn::prelude::*;
+use limn::widgets::menu::{Menu, MenuItem, MenuBarBuilder};
+use limn::widgets::text::TextBuilder;
+use limn::draw::rect::RectState;
+use limn::draw::text::TextState;
+
+#[derive(Debug, Clone)]
+enum AppCommand {
+    Open,
+    Save,
+    Quit,
+}
+
+fn file_menu() -> Menu {
+    let mut open = MenuItem::new("Open");
+    open.set_accelerator("Ctrl+O").set_event(AppCommand::Open);
+    let mut save = MenuItem::new("Save");
+    save.set_accelerator("Ctrl+S").set_event(AppCommand::Save);
+    let mut save_as = MenuItem::new("Save As");
+    save_as.set_enabled(false);
+    let mut quit = MenuItem::new("Quit");
+    quit.set_accelerator("Ctrl+Q").set_event(AppCommand::Quit);
+
+    let mut recent = Menu::new();
+    recent
+        .add_item(MenuItem::new("notes.txt"))
+        .add_item(MenuItem::new("todo.txt"));
+
+    let mut menu = Menu::new();
+    menu
+        .add_item(open)
+        .add_submenu("Open Recent", recent)
+        .add_item(save)
+        .add_item(save_as)
+        .add_separator()
+        .add_item(quit);
+    menu
+}
+
+fn set_status(status: &WidgetRef, text: String) {
+    status.clone().update(|state: &mut TextState| state.text = text);
+}
+
+fn view_menu(status: &WidgetRef) -> Menu {
+    let status_ref = status.clone();
+    let mut toolbar = MenuItem::new("Show Toolbar");
+    toolbar
+        .set_checkable(true)
+        .on_activate(move |event, _| {
+            set_status(&status_ref, format!("Show toolbar: {:?}", event.checked));
+        });
+    let status_ref = status.clone();
+    let mut status_bar = MenuItem::new("Show Status Bar");
+    status_bar
+        .set_checkable(false)
+        .on_activate(move |event, _| {
+            set_status(&status_ref, format!("Show status bar: {:?}", event.checked));
+        });
+    let mut menu = Menu::new();
+    menu.add_item(toolbar).add_item(status_bar);
+    menu
+}
+
+fn context_menu(status: &WidgetRef) -> Menu {
+    let mut menu = Menu::new();
+    for text in vec!["Cut", "Copy", "Paste"] {
+        let mut item = MenuItem::new(text);
+        let status = status.clone();
+        item.on_activate(move |_, _| {
+            set_status(&status, text.to_owned());
+        });
+        menu.add_item(item);
+    }
+    menu
+}
+
+fn main() {
+    let window_builder = glutin::WindowBuilder::new()
+        .with_title("Limn menu demo")
+        .with_min_dimensions(100, 100);
+    let mut app = util::init(window_builder);
+    let mut root = WidgetBuilder::new("root");
+    root.layout().add(min_size(Size::new(400.0, 300.0)));
+
+    let mut status = TextBuilder::new("Right click for the context menu");
+    let status_ref = status.widget_ref();
+
+    let mut menu_bar = MenuBarBuilder::new();
+    menu_bar
+        .add_menu("File", file_menu())
+        .add_menu("View", view_menu(&status_ref));
+    menu_bar.layout().add(constraints![
+        align_top(&root),
+        align_left(&root),
+        align_right(&root),
+    ]);
+
+    let mut content = WidgetBuilder::new("content");
+    content
+        .set_draw_state(RectState::new())
+        .set_context_menu(context_menu(&status_ref));
+    content.layout().add(constraints![
+        below(&menu_bar),
+        align_left(&root),
+        align_right(&root),
+        align_bottom(&root),
+    ]);
+
+    status.layout().add(center(&content));
+    content.add_child(status);
+
+    root.add_child(menu_bar).add_child(content);
+
+    app.add_handler(move |event: &AppCommand, args: EventArgs| {
+        set_status(&status_ref, format!("{:?}", event));
+        if let AppCommand::Quit = *event {
+            args.ui.close();
+        }
+    });
+    app.main_loop(root);
+}
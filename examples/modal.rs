@@ -0,0 +1,55 @@
+extern crate limn;
+
+mod util;
+
+use limn::prelude::*;
+use limn::widgets::button::PushButtonBuilder;
+use limn::widgets::edit_text::EditTextBuilder;
+use limn::widgets::text::TextBuilder;
+use limn::draw::rect::RectState;
+
+fn build_dialog() -> WidgetBuilder {
+    let mut dialog = WidgetBuilder::new("dialog");
+    dialog.set_draw_state(RectState::new());
+    let mut layout_settings = LinearLayoutSettings::new(Orientation::Vertical);
+    layout_settings.padding = 20.0;
+    layout_settings.spacing = Spacing::Around;
+    dialog.linear_layout(layout_settings);
+    dialog.layout().add(min_size(Size::new(250.0, 180.0)));
+
+    let title = TextBuilder::new("Enter your name");
+    let edit_text = EditTextBuilder::new();
+    let mut close_button = PushButtonBuilder::new();
+    close_button
+        .set_text("Close")
+        .on_click(|_, args| {
+            let dialog = args.widget.parent().unwrap();
+            args.ui.close_popup(&dialog);
+        });
+
+    dialog
+        .add_child(title)
+        .add_child(edit_text)
+        .add_child(close_button);
+    dialog
+}
+
+fn main() {
+    let window_builder = glutin::WindowBuilder::new()
+        .with_title("Limn modal demo")
+        .with_min_dimensions(100, 100);
+    let app = util::init(window_builder);
+    let mut root = WidgetBuilder::new("root");
+    root.layout().add(min_size(Size::new(400.0, 300.0)));
+
+    let mut button = PushButtonBuilder::new();
+    button
+        .set_text("Open dialog")
+        .on_click(|_, args| {
+            args.ui.open_modal(build_dialog());
+        });
+    button.layout().add(center(&root));
+    root.add_child(button);
+
+    app.main_loop(root);
+}
@@ -0,0 +1,65 @@
+extern crate limn;
+
+mod util;
+
+use std::{thread, time};
+
+use limn::prelude::*;
+
+use limn::widgets::button::PushButtonBuilder;
+use limn::widgets::progress::{ProgressBarBuilder, SpinnerBuilder, SetProgressValue, SetSpinnerRunning};
+
+/// Sent from the worker thread as the work progresses
+struct WorkProgress(f32);
+
+fn main() {
+    let window_builder = glutin::WindowBuilder::new()
+        .with_title("Limn progress demo")
+        .with_min_dimensions(100, 100);
+    let mut app = util::init(window_builder);
+    let mut root = WidgetBuilder::new("root");
+    root.layout().add(min_size(Size::new(400.0, 200.0)));
+
+    let mut progress_bar = ProgressBarBuilder::new();
+    progress_bar.set_range(0.0..100.0);
+    progress_bar.layout().add(constraints![
+        align_top(&root).padding(40.0),
+        align_left(&root).padding(40.0),
+    ]);
+
+    let mut spinner = SpinnerBuilder::new();
+    spinner.set_running(false);
+    spinner.layout().add(constraints![
+        to_right_of(&progress_bar).padding(20.0),
+        align_right(&root).padding(40.0),
+        center_vertical(&progress_bar),
+    ]);
+
+    let mut start_button = PushButtonBuilder::new();
+    start_button
+        .set_text("Start")
+        .on_click(|_, _| {
+            thread::spawn(|| {
+                for progress in 0..101 {
+                    event_global(WorkProgress(progress as f32));
+                    thread::sleep(time::Duration::from_millis(50));
+                }
+            });
+        });
+    start_button.layout().add(constraints![
+        below(&progress_bar).padding(40.0),
+        center_horizontal(&root),
+        align_bottom(&root).padding(40.0),
+    ]);
+
+    let (progress_bar_ref, spinner_ref) = (progress_bar.widget_ref(), spinner.widget_ref());
+    app.add_handler(move |event: &WorkProgress, _: EventArgs| {
+        progress_bar_ref.event(SetProgressValue(event.0));
+        spinner_ref.event(SetSpinnerRunning(event.0 < 100.0));
+    });
+
+    root.add_child(progress_bar);
+    root.add_child(spinner);
+    root.add_child(start_button);
+    app.main_loop(root);
+}
@@ -5,7 +5,8 @@ mod util;
 
 use limn::prelude::*;
 
-use limn::widgets::scroll::ScrollBuilder;
+use limn::widgets::scroll::{ScrollBuilder, ScrollToWidget};
+use limn::input::mouse::ClickEvent;
 use limn::draw::rect::{RectState, RectStyle};
 
 fn main() {
@@ -28,6 +29,10 @@ fn main() {
             let mut rect = WidgetBuilder::new(format!("rect_{:?}", color));
             rect.set_draw_state_with_style(RectState::new(),
                 style!(RectStyle::BackgroundColor: color));
+            // clicking a partly hidden rect scrolls it into view
+            rect.add_handler(|_: &ClickEvent, args: EventArgs| {
+                args.widget.event_bubble_up(ScrollToWidget(args.widget.clone()));
+            });
             rect_container.add_child(rect);
         };
         add_rect(GREEN);
@@ -0,0 +1,56 @@
+extern crate limn;
+
+mod util;
+
+use limn::prelude::*;
+
+use limn::widgets::slider::SliderBuilder;
+use limn::widgets::spin_box::SpinBoxBuilder;
+use limn::widgets::text::TextBuilder;
+use limn::draw::text::TextState;
+
+fn main() {
+    let window_builder = glutin::WindowBuilder::new()
+        .with_title("Limn spin box demo")
+        .with_min_dimensions(100, 100);
+    let app = util::init(window_builder);
+    let mut root = WidgetBuilder::new("root");
+    root.layout().add(min_size(Size::new(400.0, 200.0)));
+
+    let mut slider = SliderBuilder::new();
+
+    let mut status = TextBuilder::new("Value: 2.5");
+    let status_ref = status.widget_ref();
+
+    let mut spin_box = SpinBoxBuilder::new();
+    spin_box
+        .set_range(0.0..10.0)
+        .set_step(0.5)
+        .set_precision(1)
+        .set_value(2.5)
+        .bind_slider(&mut slider)
+        .on_value_changed(move |value, _| {
+            status_ref.clone().update(|state: &mut TextState| state.text = format!("Value: {}", value));
+        });
+
+    spin_box.layout().add(constraints![
+        align_top(&root).padding(40.0),
+        align_left(&root).padding(40.0),
+        align_right(&root).padding(40.0),
+    ]);
+    slider.layout().add(constraints![
+        below(&spin_box).padding(40.0),
+        align_left(&root).padding(40.0),
+        align_right(&root).padding(40.0),
+    ]);
+    status.layout().add(constraints![
+        below(&slider).padding(20.0),
+        align_left(&root).padding(40.0),
+        align_bottom(&root).padding(20.0),
+    ]);
+
+    root.add_child(spin_box);
+    root.add_child(slider);
+    root.add_child(status);
+    app.main_loop(root);
+}
@@ -0,0 +1,50 @@
+#[macro_use]
+extern crate limn;
+
+mod util;
+
+use limn::prelude::*;
+use limn::draw::rect::{RectState, RectStyle};
+use limn::widgets::split::SplitPaneBuilder;
+use limn::widgets::text::TextBuilder;
+use limn::draw::text::TextState;
+
+fn pane(mut text: WidgetBuilder, color: Color) -> WidgetBuilder {
+    let mut pane = WidgetBuilder::new("pane");
+    pane.set_draw_state_with_style(RectState::new(), style!(RectStyle::BackgroundColor: color));
+    text.layout().add(center(&pane));
+    pane.add_child(text);
+    pane
+}
+
+fn main() {
+    let window_builder = glutin::WindowBuilder::new()
+        .with_title("Limn split pane demo")
+        .with_min_dimensions(100, 100);
+    let app = util::init(window_builder);
+    let mut root = WidgetBuilder::new("root");
+    root.layout().add(min_size(Size::new(600.0, 400.0)));
+
+    let mut editor = SplitPaneBuilder::new(Orientation::Vertical);
+    editor
+        .add_pane(pane(TextBuilder::new("Editor"), WHITE))
+        .add_pane_with_limits(pane(TextBuilder::new("Console"), GRAY_90), 50.0, None)
+        .set_ratios(vec![0.75, 0.25]);
+
+    let sidebar_text = TextBuilder::new("Sidebar");
+    let sidebar_ref = sidebar_text.widget_ref();
+
+    let mut split = SplitPaneBuilder::new(Orientation::Horizontal);
+    split
+        .add_pane_with_limits(pane(sidebar_text, GRAY_80), 100.0, Some(300.0))
+        .add_pane(editor)
+        .set_ratios(vec![0.3, 0.7])
+        .on_ratios_changed(move |ratios, _| {
+            let text = format!("Sidebar {:.0}%", ratios[0] * 100.0);
+            sidebar_ref.clone().update(|state: &mut TextState| state.text = text);
+        });
+    split.layout().add(match_layout(&root));
+    root.add_child(split);
+
+    app.main_loop(root);
+}
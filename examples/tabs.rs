@@ -0,0 +1,51 @@
+extern crate limn;
+
+mod util;
+
+use limn::prelude::*;
+use limn::widgets::tabs::TabViewBuilder;
+use limn::widgets::text::TextBuilder;
+use limn::draw::text::TextState;
+
+fn panel(text: &str) -> WidgetBuilder {
+    let mut panel = WidgetBuilder::new("panel");
+    let mut text = TextBuilder::new(text);
+    text.layout().add(center(&panel));
+    panel.add_child(text);
+    panel
+}
+
+fn main() {
+    let window_builder = glutin::WindowBuilder::new()
+        .with_title("Limn tabs demo")
+        .with_min_dimensions(100, 100);
+    let app = util::init(window_builder);
+    let mut root = WidgetBuilder::new("root");
+    root.layout().add(min_size(Size::new(500.0, 300.0)));
+
+    let mut status = TextBuilder::new("");
+    let status_ref = status.widget_ref();
+
+    let mut tabs = TabViewBuilder::new();
+    tabs
+        .add_tab("General", panel("General settings"))
+        .add_tab("Appearance", panel("Appearance settings"))
+        .add_tab("Advanced", panel("Advanced settings"))
+        .set_closable(true)
+        .on_tab_event(move |event, _| {
+            status_ref.clone().update(|state: &mut TextState| state.text = format!("{:?}", event));
+        });
+    tabs.layout().add(constraints![
+        align_top(&root),
+        align_left(&root),
+        align_right(&root),
+    ]);
+    status.layout().add(constraints![
+        below(&tabs).padding(5.0),
+        align_left(&root).padding(5.0),
+        align_bottom(&root).padding(5.0),
+    ]);
+    root.add_child(tabs).add_child(status);
+
+    app.main_loop(root);
+}
@@ -0,0 +1,69 @@
+extern crate limn;
+
+mod util;
+
+use limn::prelude::*;
+use limn::widgets::checkbox::{CheckBoxBuilder, CheckState};
+use limn::widgets::radio::RadioGroupBuilder;
+use limn::widgets::switch::SwitchBuilder;
+use limn::widgets::text::TextBuilder;
+use limn::draw::text::TextState;
+
+fn main() {
+    let window_builder = glutin::WindowBuilder::new()
+        .with_title("Limn toggles demo")
+        .with_min_dimensions(100, 100);
+    let app = util::init(window_builder);
+    let mut root = WidgetBuilder::new("root");
+    let mut layout_settings = LinearLayoutSettings::new(Orientation::Vertical);
+    layout_settings.padding = 10.0;
+    root.linear_layout(layout_settings);
+    root.layout().add(min_size(Size::new(300.0, 300.0)));
+
+    let status = TextBuilder::new("");
+    let status_ref = status.widget_ref();
+
+    let mut checkbox = CheckBoxBuilder::new();
+    let checkbox_status = status_ref.clone();
+    checkbox
+        .set_text("Checked")
+        .set_state(CheckState::Checked)
+        .on_change(move |event, _| {
+            checkbox_status.clone().update(|state: &mut TextState| state.text = format!("Check box: {:?}", event.0));
+        });
+    let mut indeterminate = CheckBoxBuilder::new();
+    indeterminate
+        .set_text("Indeterminate")
+        .set_state(CheckState::Indeterminate);
+
+    let mut radio_group = RadioGroupBuilder::new();
+    let radio_status = status_ref.clone();
+    radio_group
+        .add_option("Small")
+        .add_option("Medium")
+        .add_option("Large")
+        .on_selected(move |widget, _| {
+            let sizes = ["Small", "Medium", "Large"];
+            let index = widget.parent().and_then(|group| group.children().iter().position(|button| *button == widget));
+            if let Some(index) = index {
+                radio_status.clone().update(|state: &mut TextState| state.text = format!("Size: {}", sizes[index]));
+            }
+        });
+
+    let mut switch = SwitchBuilder::new();
+    let switch_status = status_ref.clone();
+    switch
+        .on_toggle(move |event, _| {
+            switch_status.clone().update(|state: &mut TextState| state.text = format!("Switch: {:?}", event));
+        })
+        .set_tooltip("Turn the switch on or off");
+
+    root
+        .add_child(checkbox)
+        .add_child(indeterminate)
+        .add_child(radio_group)
+        .add_child(switch)
+        .add_child(status);
+
+    app.main_loop(root);
+}
@@ -9,8 +9,8 @@ use glutin;
 use window::Window;
 use ui::Ui;
 use input::InputEvent;
-use widget::WidgetBuilder;
-use event::{self, EventHandler};
+use widget::{WidgetBuilder, WidgetRef};
+use event::{self, EventHandler, EventArgs};
 use geometry::Size;
 
 /// The `App` type is just a thin wrapper around a `Ui` containing
@@ -39,7 +39,9 @@ impl App {
     /// desktop app:
     ///
     /// `ui_handlers`, `layout_handlers`, `input_handlers`,
-    /// `mouse_handlers`, `keyboard_handlers` and `drag_handlers`
+    /// `mouse_handlers`, `keyboard_handlers`, `window_handlers`, `drag_handlers`,
+    /// `drag_drop_handlers`, `overlay_handlers`, `tooltip_handlers`,
+    /// `context_menu_handlers`, `spinner_handlers` and `frame_handlers`
     pub fn new(window: Window, events_loop: glutin::EventsLoop) -> Self {
         event::queue_set_events_loop(&events_loop);
         let ui = Ui::new(window, &events_loop);
@@ -62,7 +64,14 @@ impl App {
 
         self.add_mouse_handlers();
         self.add_keyboard_handlers();
+        self.add_window_handlers();
         self.add_drag_handlers();
+        self.add_drag_drop_handlers();
+        self.add_overlay_handlers();
+        self.add_tooltip_handlers();
+        self.add_context_menu_handlers();
+        self.add_spinner_handlers();
+        self.add_frame_handlers();
     }
 
     fn handle_window_event(&mut self, event: glutin::Event) {
@@ -143,11 +152,62 @@ impl App {
     pub fn window(&self) -> ::std::cell::Ref<Window> {
         self.ui.window.borrow()
     }
+
+    /// Add the handler that sends `FrameEvent`s to widgets that called `WidgetRef::request_frame`
+    pub fn add_frame_handlers(&mut self) {
+        self.add_handler(|event: &RequestFrame, args: EventArgs| {
+            args.widget.event(FrameRequestEvent::Request(event.0.clone()));
+        });
+        self.add_handler(|_: &FrameEvent, args: EventArgs| {
+            args.widget.event(FrameRequestEvent::Frame);
+        });
+        self.add_handler(FrameRequestHandler::default());
+    }
 }
 
-/// Event emitted after every frame is rendered.
+/// Event emitted to the root widget after every frame is rendered.
 ///
 /// To implement animation, add a handler for this event that calls
-/// [`args.ui.redraw()`](../ui/struct.Ui.html#method.redraw) to draw a new frame.
+/// [`args.ui.redraw()`](../ui/struct.Ui.html#method.redraw) to draw a new frame,
+/// or have a widget receive it by calling
+/// [`WidgetRef::request_frame`](../widget/struct.WidgetRef.html#method.request_frame).
 #[derive(Debug, Copy, Clone)]
 pub struct FrameEvent;
+
+/// The length of a duration in seconds, for animating by the time between frames
+pub(crate) fn seconds(duration: Duration) -> f32 {
+    duration.as_secs() as f32 + duration.subsec_nanos() as f32 / 1_000_000_000.0
+}
+
+/// Sent to the root widget by `WidgetRef::request_frame`
+pub(crate) struct RequestFrame(pub WidgetRef);
+
+enum FrameRequestEvent {
+    Request(WidgetRef),
+    Frame,
+}
+
+/// Forwards the next `FrameEvent` to the widgets that requested it
+#[derive(Default)]
+struct FrameRequestHandler {
+    requested: Vec<WidgetRef>,
+}
+
+impl EventHandler<FrameRequestEvent> for FrameRequestHandler {
+    fn handle(&mut self, event: &FrameRequestEvent, args: EventArgs) {
+        match *event {
+            FrameRequestEvent::Request(ref widget) => {
+                if !self.requested.contains(widget) {
+                    self.requested.push(widget.clone());
+                }
+                // make sure there is a next frame, even if nothing else needs to be drawn
+                args.ui.redraw();
+            }
+            FrameRequestEvent::Frame => {
+                for widget in self.requested.drain(..) {
+                    widget.event(FrameEvent);
+                }
+            }
+        }
+    }
+}
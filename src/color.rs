@@ -11,7 +11,7 @@ fn rgba(color: u32) -> ColorF {
 }
 
 // needed to declare const val colors, when const fns are stable, can use rgba to construct ColorF directly
-#[derive(Clone, Copy, PartialEq, Eq)]
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
 pub struct Color(u32);
 
 impl ::std::fmt::Debug for Color {
@@ -26,8 +26,25 @@ impl Into<ColorF> for Color {
     }
 }
 
+impl From<ColorF> for Color {
+    fn from(color: ColorF) -> Self {
+        let write = |val: f32, offset: u32| { ((val.max(0.0).min(1.0) * 255.0).round() as u32) << offset };
+        Color(write(color.r, 24) | write(color.g, 16) | write(color.b, 8) | write(color.a, 0))
+    }
+}
+
+impl Color {
+    /// The same color, with its alpha multiplied by `alpha`
+    pub fn scale_alpha(self, alpha: f32) -> Color {
+        let mut color: ColorF = self.into();
+        color.a *= alpha;
+        color.into()
+    }
+}
+
 pub const TRANSPARENT: Color = Color(0x00000000);
 pub const BLACK: Color = Color(0x000000FF);
+pub const BLACK_TRANSLUCENT: Color = Color(0x00000066);
 pub const WHITE: Color = Color(0xFFFFFFFF);
 
 pub const GRAY_10: Color = Color(0x191919FF);
@@ -1,6 +1,4 @@
-use webrender::api::{ComplexClipRegion, BorderRadius, LocalClip, PrimitiveInfo};
-
-use render::RenderBuilder;
+use render::{RenderBuilder, CornerRadii};
 use widget::draw::Draw;
 use widget::property::PropSet;
 use widget::style::{self, Style, Value};
@@ -28,15 +26,8 @@ impl EllipseState {
     }
 }
 
-fn clip_ellipse(rect: Rect) -> LocalClip {
-    let clip_region = ComplexClipRegion::new(rect, BorderRadius::uniform_size(rect.size / 2.0));
-    LocalClip::RoundedRect(rect, clip_region)
-}
-
-fn push_ellipse(renderer: &mut RenderBuilder, rect: Rect, clip_rect: Rect, color: Color) {
-    let clip = clip_ellipse(clip_rect);
-    let info = PrimitiveInfo::with_clip(rect, clip);
-    renderer.builder.push_rect(&info, color.into());
+fn push_ellipse(renderer: &mut RenderBuilder, rect: Rect, color: Color) {
+    renderer.push_rounded_rect(rect, CornerRadii::ellipse(rect), color);
 }
 
 impl Draw for EllipseState {
@@ -45,10 +36,10 @@ impl Draw for EllipseState {
         let bounds = bounds.round();
         if let Some((width, color)) = self.border {
             let width = if width < 2.0 { 2.0 } else { width };
-            push_ellipse(renderer, bounds, bounds, color);
-            push_ellipse(renderer, bounds, bounds.shrink_bounds(width), self.background_color);
+            push_ellipse(renderer, bounds, color);
+            push_ellipse(renderer, bounds.shrink_bounds(width), self.background_color);
         } else {
-            push_ellipse(renderer, bounds, bounds, self.background_color);
+            push_ellipse(renderer, bounds, self.background_color);
         };
     }
     fn is_under_cursor(&self, bounds: Rect, cursor: Point) -> bool {
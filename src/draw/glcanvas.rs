@@ -34,23 +34,18 @@ impl GLCanvasState {
 
 impl Draw for GLCanvasState {
     fn draw(&mut self, bounds: Rect, _: Rect, renderer: &mut RenderBuilder) {
-        let mut res = resources();
-        let image_info = *res.get_image(&self.name);
-        if bounds.width() as u32 != image_info.info.width ||
-            bounds.height() as u32 != image_info.info.height {
-            let descriptor = ImageDescriptor::new(bounds.width() as u32, bounds.height() as u32, ImageFormat::RGB8, true);
-            res.update_image(&self.name, self.data.clone(), descriptor);
-            if let ImageData::External(ExternalImageData { id: ExternalImageId(texture_id), .. }) = self.data {
-                res.texture_descriptors.insert(texture_id, descriptor);
+        {
+            let mut res = resources();
+            let image_info = *res.get_image(&self.name);
+            if bounds.width() as u32 != image_info.info.width ||
+                bounds.height() as u32 != image_info.info.height {
+                let descriptor = ImageDescriptor::new(bounds.width() as u32, bounds.height() as u32, ImageFormat::RGB8, true);
+                res.update_image(&self.name, self.data.clone(), descriptor);
+                if let ImageData::External(ExternalImageData { id: ExternalImageId(texture_id), .. }) = self.data {
+                    res.texture_descriptors.insert(texture_id, descriptor);
+                }
             }
         }
-        let info = PrimitiveInfo::new(bounds);
-        renderer.builder.push_image(
-            &info,
-            bounds.size,
-            LayoutSize::zero(),
-            ImageRendering::Auto,
-            image_info.key,
-        );
+        renderer.push_image(bounds, &self.name);
     }
 }
@@ -0,0 +1,70 @@
+use webrender::api::ColorF;
+
+use render::{RenderBuilder, StackingContext};
+use widget::draw::Draw;
+use widget::property::PropSet;
+use widget::style::{self, Style, Value};
+use resources::resources;
+use geometry::{Rect, Size, Transform};
+use color::*;
+
+/// Draws an SVG document from the resources, scaled to fill the bounds
+#[derive(Debug, Clone)]
+pub struct IconState {
+    pub icon: String,
+    /// Color that the shapes of the icon are drawn with, keeping their opacity, instead of their own colors
+    pub tint: Option<Color>,
+}
+
+impl IconState {
+    pub fn new(icon: &str) -> Self {
+        IconState {
+            icon: icon.to_owned(),
+            tint: None,
+        }
+    }
+    /// The size of the SVG document, or zero if it can't be loaded
+    pub fn measure(&self) -> Size {
+        resources().get_svg(&self.icon).map(|svg| svg.size).unwrap_or_else(Size::zero)
+    }
+}
+
+impl Draw for IconState {
+    fn draw(&mut self, bounds: Rect, _: Rect, renderer: &mut RenderBuilder) {
+        let layers = resources().get_svg_layers(&self.icon, bounds.size);
+        // the layers are tessellated for a size in device pixels, and placed on
+        // device pixels too, so icons rasterized by the renderer stay crisp
+        let hidpi_factor = resources().hidpi_factor;
+        let snap = |value: f32| (value * hidpi_factor).round() / hidpi_factor;
+        renderer.push_stacking_context(StackingContext {
+            transform: Some(Transform::create_translation(snap(bounds.origin.x), snap(bounds.origin.y))),
+            ..StackingContext::default()
+        });
+        for layer in layers.iter() {
+            let color = match self.tint {
+                Some(tint) => {
+                    let color: ColorF = layer.color.into();
+                    tint.scale_alpha(color.a)
+                }
+                None => layer.color,
+            };
+            renderer.push_polygons(&layer.polygons, layer.fill_rule, color);
+        }
+        renderer.pop_stacking_context();
+    }
+}
+
+#[derive(Clone, Debug)]
+pub enum IconStyle {
+    Icon(Value<String>),
+    Tint(Value<Option<Color>>),
+}
+
+impl Style<IconState> for IconStyle {
+    fn apply(&self, state: &mut IconState, props: &PropSet) -> bool {
+        match *self {
+            IconStyle::Icon(ref val) => style::update(&mut state.icon, val.get(props)),
+            IconStyle::Tint(ref val) => style::update(&mut state.tint, val.get(props)),
+        }
+    }
+}
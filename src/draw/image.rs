@@ -1,40 +1,201 @@
-use webrender::api::*;
-
-use render::RenderBuilder;
+use render::{RenderBuilder, Clip, ImageFilter};
 use widget::draw::Draw;
+use widget::property::PropSet;
+use widget::style::{self, Style, Value};
 use resources::resources;
-use geometry::{Rect, Size};
+use geometry::{Rect, RectExt, Point, Size};
+
+/// How an image is sized within the bounds, like CSS `object-fit`
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum ImageFit {
+    /// Stretch the image to fill the bounds
+    Fill,
+    /// Scale the image to fit within the bounds, keeping its aspect ratio
+    Contain,
+    /// Scale the image to cover the bounds, keeping its aspect ratio, the parts outside the bounds are clipped
+    Cover,
+    /// Draw the image at its own size
+    None,
+    /// The smaller of `None` and `Contain`
+    ScaleDown,
+}
+
+/// The directions an image is repeated in to fill the bounds
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum ImageRepeat {
+    NoRepeat,
+    Repeat,
+    RepeatX,
+    RepeatY,
+}
+
+/// Insets dividing an image into a grid of nine parts. The corners are drawn at their own size, the edges
+/// are stretched along the edges of the bounds, and the center is stretched to fill the rest.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct NinePatch {
+    pub left: f32,
+    pub top: f32,
+    pub right: f32,
+    pub bottom: f32,
+}
+
+impl NinePatch {
+    pub fn new(left: f32, top: f32, right: f32, bottom: f32) -> Self {
+        NinePatch { left: left, top: top, right: right, bottom: bottom }
+    }
+    pub fn uniform(inset: f32) -> Self {
+        NinePatch::new(inset, inset, inset, inset)
+    }
+}
 
 pub struct ImageState {
     pub image: String,
+    /// Scale of the image's own size, used for measuring, and when drawing with `ImageFit::None`
+    /// or `ImageFit::ScaleDown`, or as a nine-patch
     pub scale: Size,
+    pub fit: ImageFit,
+    /// Position of the image in the bounds, as a fraction of the space left around it,
+    /// (0.5, 0.5), centered, by default
+    pub align: Point,
+    pub repeat: ImageRepeat,
+    /// Draw the image as a nine-patch, with insets in density independent pixels of the image,
+    /// the fit, alignment and repeat are ignored if set
+    pub nine_patch: Option<NinePatch>,
+    pub filter: ImageFilter,
 }
 impl ImageState {
     pub fn new(image: &str) -> Self {
         ImageState {
             image: image.to_owned(),
             scale: Size::new(1.0, 1.0),
+            fit: ImageFit::Fill,
+            align: Point::new(0.5, 0.5),
+            repeat: ImageRepeat::NoRepeat,
+            nine_patch: None,
+            filter: ImageFilter::Smooth,
         }
     }
+    /// The size of the image, multiplied by its scale
     pub fn measure(&self) -> Size {
         let mut res = resources();
-        let info = res.get_image(&self.image).info;
-        Size::new(info.width as f32, info.height as f32)
+        let size = res.get_image(&self.image).size();
+        Size::new(size.width * self.scale.width, size.height * self.scale.height)
     }
     pub fn scale(&mut self, scale: Size) {
         self.scale = scale;
     }
+    /// The size of the image drawn in bounds of the given size
+    fn fitted_size(&self, bounds: Size) -> Size {
+        let size = self.measure();
+        let contain = (bounds.width / size.width).min(bounds.height / size.height);
+        let scale = match self.fit {
+            ImageFit::Fill => return bounds,
+            ImageFit::Contain => contain,
+            ImageFit::Cover => (bounds.width / size.width).max(bounds.height / size.height),
+            ImageFit::None => 1.0,
+            ImageFit::ScaleDown => contain.min(1.0),
+        };
+        Size::new(size.width * scale, size.height * scale)
+    }
+    fn draw_fitted(&self, bounds: Rect, renderer: &mut RenderBuilder) {
+        let size = self.fitted_size(bounds.size);
+        if !(size.width > 0.0 && size.height > 0.0) {
+            return;
+        }
+        let mut rect = Rect::new(Point::new(
+            bounds.left() + (bounds.width() - size.width) * self.align.x,
+            bounds.top() + (bounds.height() - size.height) * self.align.y), size);
+        // repeated images are extended to the edges of the bounds, with a tile at the aligned position
+        let (repeat_x, repeat_y) = match self.repeat {
+            ImageRepeat::NoRepeat => (false, false),
+            ImageRepeat::Repeat => (true, true),
+            ImageRepeat::RepeatX => (true, false),
+            ImageRepeat::RepeatY => (false, true),
+        };
+        if repeat_x {
+            let left = rect.left() - ((rect.left() - bounds.left()) / size.width).ceil() * size.width;
+            rect = Rect::new(Point::new(left, rect.top()), Size::new(bounds.right() - left, rect.height()));
+        }
+        if repeat_y {
+            let top = rect.top() - ((rect.top() - bounds.top()) / size.height).ceil() * size.height;
+            rect = Rect::new(Point::new(rect.left(), top), Size::new(rect.width(), bounds.bottom() - top));
+        }
+        let clipped = !bounds.contains_rect(&rect);
+        if clipped {
+            renderer.push_clip(Clip::Rect(bounds));
+        }
+        renderer.push_tiled_image(rect, &self.image, size, Size::zero(), self.filter);
+        if clipped {
+            renderer.pop_clip();
+        }
+    }
+    fn draw_nine_patch(&self, bounds: Rect, nine_patch: NinePatch, renderer: &mut RenderBuilder) {
+        let image_size = resources().get_image(&self.image).size();
+        // the corners are scaled down if they don't fit in the bounds
+        let fit = |start: f32, end: f32, available: f32| {
+            let total = start + end;
+            if total > available && total > 0.0 { available / total } else { 1.0 }
+        };
+        let scale_x = self.scale.width * fit(nine_patch.left * self.scale.width, nine_patch.right * self.scale.width, bounds.width());
+        let scale_y = self.scale.height * fit(nine_patch.top * self.scale.height, nine_patch.bottom * self.scale.height, bounds.height());
+        // edges of the columns and rows, in the image and in the bounds
+        let image_columns = [0.0, nine_patch.left, image_size.width - nine_patch.right, image_size.width];
+        let image_rows = [0.0, nine_patch.top, image_size.height - nine_patch.bottom, image_size.height];
+        let columns = [bounds.left(), bounds.left() + nine_patch.left * scale_x,
+                       bounds.right() - nine_patch.right * scale_x, bounds.right()];
+        let rows = [bounds.top(), bounds.top() + nine_patch.top * scale_y,
+                    bounds.bottom() - nine_patch.bottom * scale_y, bounds.bottom()];
+        for row in 0..3 {
+            for column in 0..3 {
+                let source = Rect::new(Point::new(image_columns[column], image_rows[row]), Size::new(
+                    image_columns[column + 1] - image_columns[column], image_rows[row + 1] - image_rows[row]));
+                let target = Rect::new(Point::new(columns[column], rows[row]), Size::new(
+                    columns[column + 1] - columns[column], rows[row + 1] - rows[row]));
+                if source.width() <= 0.0 || source.height() <= 0.0 || target.width() <= 0.0 || target.height() <= 0.0 {
+                    continue;
+                }
+                // the whole image is drawn scaled so the source part covers the target, clipped to the target
+                let (part_scale_x, part_scale_y) = (target.width() / source.width(), target.height() / source.height());
+                let image_rect = Rect::new(
+                    Point::new(target.left() - source.left() * part_scale_x, target.top() - source.top() * part_scale_y),
+                    Size::new(image_size.width * part_scale_x, image_size.height * part_scale_y));
+                renderer.push_clip(Clip::Rect(target));
+                renderer.push_tiled_image(image_rect, &self.image, image_rect.size, Size::zero(), self.filter);
+                renderer.pop_clip();
+            }
+        }
+    }
 }
 impl Draw for ImageState {
     fn draw(&mut self, bounds: Rect, _: Rect, renderer: &mut RenderBuilder) {
-        let key = resources().get_image(&self.image).key;
-        let info = PrimitiveInfo::new(bounds);
-        renderer.builder.push_image(
-            &info,
-            bounds.size,
-            LayoutSize::zero(),
-            ImageRendering::Auto,
-            key,
-        );
+        match self.nine_patch {
+            Some(nine_patch) => self.draw_nine_patch(bounds, nine_patch, renderer),
+            None => self.draw_fitted(bounds, renderer),
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub enum ImageStyle {
+    Image(Value<String>),
+    Scale(Value<Size>),
+    Fit(Value<ImageFit>),
+    Align(Value<Point>),
+    Repeat(Value<ImageRepeat>),
+    NinePatch(Value<Option<NinePatch>>),
+    Filter(Value<ImageFilter>),
+}
+
+impl Style<ImageState> for ImageStyle {
+    fn apply(&self, state: &mut ImageState, props: &PropSet) -> bool {
+        match *self {
+            ImageStyle::Image(ref val) => style::update(&mut state.image, val.get(props)),
+            ImageStyle::Scale(ref val) => style::update(&mut state.scale, val.get(props)),
+            ImageStyle::Fit(ref val) => style::update(&mut state.fit, val.get(props)),
+            ImageStyle::Align(ref val) => style::update(&mut state.align, val.get(props)),
+            ImageStyle::Repeat(ref val) => style::update(&mut state.repeat, val.get(props)),
+            ImageStyle::NinePatch(ref val) => style::update(&mut state.nine_patch, val.get(props)),
+            ImageStyle::Filter(ref val) => style::update(&mut state.filter, val.get(props)),
+        }
     }
 }
@@ -2,6 +2,9 @@
 
 pub mod rect;
 pub mod ellipse;
+pub mod path;
+pub mod spinner;
 pub mod text;
 pub mod image;
+pub mod icon;
 pub mod glcanvas;
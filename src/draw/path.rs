@@ -0,0 +1,792 @@
+//! Vector paths made of lines, curves and arcs, that can be filled and stroked.
+//!
+//! Paths are flattened to polygons, and strokes are tessellated to polygons covering the stroked
+//! area, which the renderer fills with `RenderBuilder::push_polygons`.
+
+use std::mem;
+use std::cell::RefCell;
+use std::f32::consts::PI;
+
+use render::{RenderBuilder, FillRule, polygons_contain, polygons_bounds};
+use widget::draw::Draw;
+use widget::property::PropSet;
+use widget::style::{self, Style, Value};
+use geometry::{Rect, Point, Size, Vector};
+use color::*;
+
+/// Maximum distance between a flattened curve and the real curve, in pixels
+const TOLERANCE: f32 = 0.1;
+/// Limit on the number of lines a single curve is flattened to
+const MAX_CURVE_LINES: f32 = 1000.0;
+/// Ratio of the length of a miter join to the stroke width, above which a bevel join is used instead,
+/// the default of SVG's `stroke-miterlimit`
+const MITER_LIMIT: f32 = 4.0;
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum PathSegment {
+    /// Start a new subpath at a point
+    MoveTo(Point),
+    LineTo(Point),
+    /// A quadratic bezier curve, with a control point and end point
+    QuadTo(Point, Point),
+    /// A cubic bezier curve, with two control points and an end point
+    CubicTo(Point, Point, Point),
+    /// An elliptical arc to a point, with the parameters of SVG path arcs,
+    /// the rotation of the ellipse's x axis is in radians
+    ArcTo { radii: Size, x_rotation: f32, large_arc: bool, sweep: bool, to: Point },
+    /// Close the current subpath with a line back to its start
+    Close,
+}
+
+impl PathSegment {
+    fn end_point(&self) -> Option<Point> {
+        match *self {
+            PathSegment::MoveTo(to) |
+            PathSegment::LineTo(to) |
+            PathSegment::QuadTo(_, to) |
+            PathSegment::CubicTo(_, _, to) |
+            PathSegment::ArcTo { to, .. } => Some(to),
+            PathSegment::Close => None,
+        }
+    }
+}
+
+/// A subpath flattened to a list of points
+#[derive(Debug, Clone, PartialEq)]
+pub struct Contour {
+    pub points: Vec<Point>,
+    /// If the last point is connected back to the first
+    pub closed: bool,
+}
+
+/// A sequence of path segments. Positions are in pixels, with y pointing down,
+/// so positive angles and arcs with the sweep flag set go clockwise.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Path {
+    segments: Vec<PathSegment>,
+}
+
+impl Path {
+    pub fn new() -> Self {
+        Path::default()
+    }
+    pub fn segment(mut self, segment: PathSegment) -> Self {
+        self.segments.push(segment);
+        self
+    }
+    pub fn move_to(self, point: Point) -> Self {
+        self.segment(PathSegment::MoveTo(point))
+    }
+    pub fn line_to(self, point: Point) -> Self {
+        self.segment(PathSegment::LineTo(point))
+    }
+    pub fn quad_to(self, control: Point, to: Point) -> Self {
+        self.segment(PathSegment::QuadTo(control, to))
+    }
+    pub fn cubic_to(self, control_1: Point, control_2: Point, to: Point) -> Self {
+        self.segment(PathSegment::CubicTo(control_1, control_2, to))
+    }
+    pub fn arc_to(self, radii: Size, x_rotation: f32, large_arc: bool, sweep: bool, to: Point) -> Self {
+        self.segment(PathSegment::ArcTo {
+            radii: radii,
+            x_rotation: x_rotation,
+            large_arc: large_arc,
+            sweep: sweep,
+            to: to,
+        })
+    }
+    /// Add an arc of the ellipse with `center` and `radius`, starting at `start_angle` and going
+    /// through `sweep_angle`, in radians. The start of the arc is connected to the current point
+    /// with a line, if there is one.
+    pub fn arc(mut self, center: Point, radius: Size, start_angle: f32, sweep_angle: f32) -> Self {
+        let point_at = |angle: f32| Point::new(center.x + radius.width * angle.cos(), center.y + radius.height * angle.sin());
+        self = if self.segments.is_empty() {
+            self.move_to(point_at(start_angle))
+        } else {
+            self.line_to(point_at(start_angle))
+        };
+        // arcs of at most half a turn are never the large arc between their end points
+        let pieces = (sweep_angle.abs() / PI).ceil().max(1.0) as usize;
+        for i in 1..pieces + 1 {
+            let angle = start_angle + sweep_angle * i as f32 / pieces as f32;
+            self = self.arc_to(radius, 0.0, false, sweep_angle > 0.0, point_at(angle));
+        }
+        self
+    }
+    pub fn close(self) -> Self {
+        self.segment(PathSegment::Close)
+    }
+    pub fn segments(&self) -> &[PathSegment] {
+        &self.segments
+    }
+    /// Approximate the path with lines, curves stay within `tolerance` of the flattened contours
+    pub fn flatten(&self, tolerance: f32) -> Vec<Contour> {
+        let mut contours = Vec::new();
+        let mut points = Vec::new();
+        let mut position = Point::zero();
+        let mut start = Point::zero();
+        for segment in &self.segments {
+            if let PathSegment::MoveTo(to) = *segment {
+                end_contour(&mut contours, &mut points, false);
+                start = to;
+            } else if points.is_empty() && *segment != PathSegment::Close {
+                start = position;
+                points.push(position);
+            }
+            match *segment {
+                PathSegment::MoveTo(to) | PathSegment::LineTo(to) => points.push(to),
+                PathSegment::QuadTo(control, to) => flatten_quad(position, control, to, tolerance, &mut points),
+                PathSegment::CubicTo(control_1, control_2, to) => {
+                    flatten_cubic(position, control_1, control_2, to, tolerance, &mut points)
+                }
+                PathSegment::ArcTo { radii, x_rotation, large_arc, sweep, to } => {
+                    flatten_arc(position, radii, x_rotation, large_arc, sweep, to, tolerance, &mut points)
+                }
+                PathSegment::Close => end_contour(&mut contours, &mut points, true),
+            }
+            position = segment.end_point().unwrap_or(start);
+        }
+        end_contour(&mut contours, &mut points, false);
+        contours
+    }
+}
+
+fn end_contour(contours: &mut Vec<Contour>, points: &mut Vec<Point>, closed: bool) {
+    let mut points = mem::replace(points, Vec::new());
+    if closed && points.len() > 1 && points.first() == points.last() {
+        points.pop();
+    }
+    // a lone point is only kept if it's closed, so it can be drawn as a dot
+    if points.len() > 1 || (closed && !points.is_empty()) {
+        contours.push(Contour {
+            points: points,
+            closed: closed,
+        });
+    }
+}
+
+fn curve_lines(error_factor: f32, tolerance: f32) -> usize {
+    (error_factor / tolerance).sqrt().ceil().max(1.0).min(MAX_CURVE_LINES) as usize
+}
+
+fn flatten_quad(from: Point, control: Point, to: Point, tolerance: f32, points: &mut Vec<Point>) {
+    let dx = from.x - 2.0 * control.x + to.x;
+    let dy = from.y - 2.0 * control.y + to.y;
+    let lines = curve_lines((dx * dx + dy * dy).sqrt() / 4.0, tolerance);
+    for i in 1..lines + 1 {
+        let t = i as f32 / lines as f32;
+        let (a, b, c) = ((1.0 - t) * (1.0 - t), 2.0 * (1.0 - t) * t, t * t);
+        points.push(Point::new(
+            a * from.x + b * control.x + c * to.x,
+            a * from.y + b * control.y + c * to.y));
+    }
+}
+
+fn flatten_cubic(from: Point, control_1: Point, control_2: Point, to: Point, tolerance: f32, points: &mut Vec<Point>) {
+    let length = |x: f32, y: f32| (x * x + y * y).sqrt();
+    let dd = length(from.x - 2.0 * control_1.x + control_2.x, from.y - 2.0 * control_1.y + control_2.y)
+        .max(length(control_1.x - 2.0 * control_2.x + to.x, control_1.y - 2.0 * control_2.y + to.y));
+    let lines = curve_lines(dd * 3.0 / 4.0, tolerance);
+    for i in 1..lines + 1 {
+        let t = i as f32 / lines as f32;
+        let mt = 1.0 - t;
+        let (a, b, c, d) = (mt * mt * mt, 3.0 * mt * mt * t, 3.0 * mt * t * t, t * t * t);
+        points.push(Point::new(
+            a * from.x + b * control_1.x + c * control_2.x + d * to.x,
+            a * from.y + b * control_1.y + c * control_2.y + d * to.y));
+    }
+}
+
+/// The angle between points on a circle, for lines between them to stay within the tolerance
+fn arc_step(radius: f32, tolerance: f32) -> f32 {
+    (2.0 * (1.0 - (tolerance / radius).min(1.0)).acos()).max(0.001)
+}
+
+// Converts the arc to center parameterization, as described in the SVG implementation notes
+#[cfg_attr(feature = "cargo-clippy", allow(too_many_arguments))]
+fn flatten_arc(from: Point, radii: Size, x_rotation: f32, large_arc: bool, sweep: bool, to: Point,
+               tolerance: f32, points: &mut Vec<Point>)
+{
+    if from == to {
+        return;
+    }
+    let (mut rx, mut ry) = (radii.width.abs(), radii.height.abs());
+    if rx == 0.0 || ry == 0.0 {
+        points.push(to);
+        return;
+    }
+    let (sin, cos) = x_rotation.sin_cos();
+    let (dx, dy) = ((from.x - to.x) / 2.0, (from.y - to.y) / 2.0);
+    let (x1, y1) = (cos * dx + sin * dy, -sin * dx + cos * dy);
+    // radii too small to reach the end point are scaled up
+    let lambda = x1 * x1 / (rx * rx) + y1 * y1 / (ry * ry);
+    if lambda > 1.0 {
+        rx *= lambda.sqrt();
+        ry *= lambda.sqrt();
+    }
+    let numerator = rx * rx * ry * ry - rx * rx * y1 * y1 - ry * ry * x1 * x1;
+    let denominator = rx * rx * y1 * y1 + ry * ry * x1 * x1;
+    let mut factor = (numerator / denominator).max(0.0).sqrt();
+    if large_arc == sweep {
+        factor = -factor;
+    }
+    let (cx1, cy1) = (factor * rx * y1 / ry, -factor * ry * x1 / rx);
+    let center = Point::new(
+        cos * cx1 - sin * cy1 + (from.x + to.x) / 2.0,
+        sin * cx1 + cos * cy1 + (from.y + to.y) / 2.0);
+    let start_angle = ((y1 - cy1) / ry).atan2((x1 - cx1) / rx);
+    let end_angle = ((-y1 - cy1) / ry).atan2((-x1 - cx1) / rx);
+    let mut sweep_angle = end_angle - start_angle;
+    if sweep && sweep_angle < 0.0 {
+        sweep_angle += 2.0 * PI;
+    } else if !sweep && sweep_angle > 0.0 {
+        sweep_angle -= 2.0 * PI;
+    }
+    let lines = (sweep_angle.abs() / arc_step(rx.max(ry), tolerance)).ceil().max(1.0).min(MAX_CURVE_LINES) as usize;
+    for i in 1..lines {
+        let (angle_sin, angle_cos) = (start_angle + sweep_angle * i as f32 / lines as f32).sin_cos();
+        let (x, y) = (rx * angle_cos, ry * angle_sin);
+        points.push(Point::new(cos * x - sin * y + center.x, sin * x + cos * y + center.y));
+    }
+    points.push(to);
+}
+
+/// The shape used to join connected lines of a stroke
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum LineJoin {
+    Miter,
+    Round,
+    Bevel,
+}
+
+/// The shape of the ends of a stroke
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum LineCap {
+    Butt,
+    Round,
+    /// Extends the stroke past its ends by half its width
+    Square,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct StrokeStyle {
+    pub width: f32,
+    pub join: LineJoin,
+    pub cap: LineCap,
+    /// Lengths of alternating dashes and gaps, a solid line is drawn if empty
+    pub dashes: Vec<f32>,
+    /// Distance into the dash pattern that the stroke starts at
+    pub dash_offset: f32,
+}
+
+impl Default for StrokeStyle {
+    fn default() -> Self {
+        StrokeStyle {
+            width: 1.0,
+            join: LineJoin::Miter,
+            cap: LineCap::Butt,
+            dashes: Vec::new(),
+            dash_offset: 0.0,
+        }
+    }
+}
+
+impl StrokeStyle {
+    /// Tessellate the stroke of flattened contours to polygons that cover the stroked area. The polygons
+    /// all wind in the same direction, so they can be filled together using `FillRule::NonZero`.
+    pub fn tessellate(&self, contours: &[Contour], tolerance: f32) -> Vec<Vec<Point>> {
+        let mut polygons = Vec::new();
+        if self.width <= 0.0 {
+            return polygons;
+        }
+        for contour in contours {
+            for (points, closed) in self.dash(contour) {
+                self.stroke_polyline(points, closed, tolerance, &mut polygons);
+            }
+        }
+        polygons
+    }
+
+    /// Split a contour into the polylines of its dashes
+    fn dash(&self, contour: &Contour) -> Vec<(Vec<Point>, bool)> {
+        let total: f32 = self.dashes.iter().sum();
+        if self.dashes.iter().any(|&dash| dash < 0.0) || total <= 0.0 {
+            return vec![(contour.points.clone(), contour.closed)];
+        }
+        let mut points = contour.points.clone();
+        if contour.closed {
+            points.push(contour.points[0]);
+        }
+        // as in SVG, a pattern with an odd number of lengths is repeated to get an even number
+        let mut pattern = self.dashes.clone();
+        if pattern.len() % 2 == 1 {
+            pattern.extend_from_slice(&self.dashes);
+        }
+        let total = total * (pattern.len() / self.dashes.len()) as f32;
+        let mut offset = self.dash_offset % total;
+        if offset < 0.0 {
+            offset += total;
+        }
+        let mut index = 0;
+        while offset >= pattern[index] {
+            offset -= pattern[index];
+            index = (index + 1) % pattern.len();
+        }
+        let mut remaining = pattern[index] - offset;
+        let mut dashes = Vec::new();
+        let mut dash = if index % 2 == 0 { vec![points[0]] } else { Vec::new() };
+        for line in points.windows(2) {
+            let (mut from, to) = (line[0], line[1]);
+            let mut length = distance(from, to);
+            while length > remaining {
+                let split = Point::new(
+                    from.x + (to.x - from.x) * remaining / length,
+                    from.y + (to.y - from.y) * remaining / length);
+                // the split ends the current dash, or starts the next one
+                dash.push(split);
+                if index % 2 == 0 {
+                    dashes.push((mem::replace(&mut dash, Vec::new()), false));
+                }
+                length -= remaining;
+                from = split;
+                index = (index + 1) % pattern.len();
+                remaining = pattern[index];
+            }
+            remaining -= length;
+            if index % 2 == 0 {
+                dash.push(to);
+            }
+        }
+        if index % 2 == 0 && !dash.is_empty() {
+            dashes.push((dash, false));
+        }
+        dashes
+    }
+
+    fn stroke_polyline(&self, mut points: Vec<Point>, closed: bool, tolerance: f32, polygons: &mut Vec<Vec<Point>>) {
+        let half_width = self.width / 2.0;
+        points.dedup_by(|a, b| distance(*a, *b) < 1e-4);
+        if closed && points.len() > 1 && distance(points[0], points[points.len() - 1]) < 1e-4 {
+            points.pop();
+        }
+        if points.len() < 2 {
+            // zero length lines are only drawn with round caps, as dots
+            if let (Some(&point), LineCap::Round) = (points.first(), self.cap) {
+                polygons.push(circle(point, half_width, tolerance));
+            }
+            return;
+        }
+        let count = points.len();
+        let lines = if closed { count } else { count - 1 };
+        for i in 0..lines {
+            let (mut start, mut end) = (points[i], points[(i + 1) % count]);
+            let direction = unit_vector(start, end);
+            if !closed && self.cap == LineCap::Square {
+                if i == 0 {
+                    start = start - direction * half_width;
+                }
+                if i == lines - 1 {
+                    end = end + direction * half_width;
+                }
+            }
+            let normal = Vector::new(-direction.y, direction.x) * half_width;
+            polygons.push(oriented(vec![start + normal, end + normal, end - normal, start - normal]));
+        }
+        let joins = if closed { 0..count } else { 1..count - 1 };
+        for i in joins {
+            let (previous, point, next) = (points[(i + count - 1) % count], points[i], points[(i + 1) % count]);
+            self.join(previous, point, next, tolerance, polygons);
+        }
+        if !closed && self.cap == LineCap::Round {
+            polygons.push(circle(points[0], half_width, tolerance));
+            polygons.push(circle(points[count - 1], half_width, tolerance));
+        }
+    }
+
+    /// Fill the gap on the outside of the corner where two lines meet
+    fn join(&self, previous: Point, point: Point, next: Point, tolerance: f32, polygons: &mut Vec<Vec<Point>>) {
+        let half_width = self.width / 2.0;
+        let (d0, d1) = (unit_vector(previous, point), unit_vector(point, next));
+        let cross = d0.x * d1.y - d0.y * d1.x;
+        if cross.abs() < 1e-6 && d0.x * d1.x + d0.y * d1.y > 0.0 {
+            return;
+        }
+        if self.join == LineJoin::Round {
+            polygons.push(circle(point, half_width, tolerance));
+            return;
+        }
+        // the lines turn towards the side of their normals if the cross product is positive
+        let side = if cross > 0.0 { -1.0 } else { 1.0 };
+        let (n0, n1) = (Vector::new(-d0.y, d0.x) * side, Vector::new(-d1.y, d1.x) * side);
+        let (outer_0, outer_1) = (point + n0 * half_width, point + n1 * half_width);
+        let bisector = n0 + n1;
+        let bisector_length = (bisector.x * bisector.x + bisector.y * bisector.y).sqrt();
+        // cosine of the angle between the bisector and the normals, the miter length is inversely proportional
+        let cos_half = bisector_length / 2.0;
+        let polygon = if self.join == LineJoin::Miter && cos_half > 1.0 / MITER_LIMIT {
+            let tip = point + bisector * (half_width / cos_half / bisector_length);
+            vec![point, outer_0, tip, outer_1]
+        } else {
+            vec![point, outer_0, outer_1]
+        };
+        polygons.push(oriented(polygon));
+    }
+}
+
+fn distance(a: Point, b: Point) -> f32 {
+    ((b.x - a.x).powi(2) + (b.y - a.y).powi(2)).sqrt()
+}
+
+fn unit_vector(from: Point, to: Point) -> Vector {
+    let length = distance(from, to);
+    Vector::new((to.x - from.x) / length, (to.y - from.y) / length)
+}
+
+fn circle(center: Point, radius: f32, tolerance: f32) -> Vec<Point> {
+    let count = (2.0 * PI / arc_step(radius, tolerance)).ceil().max(8.0).min(MAX_CURVE_LINES) as usize;
+    (0..count).map(|i| {
+        let (sin, cos) = (2.0 * PI * i as f32 / count as f32).sin_cos();
+        Point::new(center.x + radius * cos, center.y + radius * sin)
+    }).collect()
+}
+
+/// Reverse a polygon if needed so that its signed area is positive
+fn oriented(mut polygon: Vec<Point>) -> Vec<Point> {
+    let mut area = 0.0;
+    for (i, a) in polygon.iter().enumerate() {
+        let b = polygon[(i + 1) % polygon.len()];
+        area += a.x * b.y - b.x * a.y;
+    }
+    if area < 0.0 {
+        polygon.reverse();
+    }
+    polygon
+}
+
+/// The fill and stroke polygons of a path, with what they were tessellated from
+#[derive(Debug, Clone)]
+struct Tessellation {
+    bounds: Rect,
+    path: Path,
+    view_box: Option<Rect>,
+    filled: bool,
+    stroke: Option<StrokeStyle>,
+    fill_polygons: Vec<Vec<Point>>,
+    stroke_polygons: Vec<Vec<Point>>,
+}
+
+impl Tessellation {
+    fn is_valid(&self, bounds: Rect, state: &PathState) -> bool {
+        self.bounds == bounds && self.path == state.path && self.view_box == state.view_box &&
+            self.filled == state.fill_color.is_some() && self.stroke.as_ref() == state.stroke_if_drawn()
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct PathState {
+    pub path: Path,
+    /// The region of the path's coordinates that's scaled to fill the widget's bounds,
+    /// if not set, path coordinates are relative to the top left of the widget
+    pub view_box: Option<Rect>,
+    pub fill_color: Option<Color>,
+    pub fill_rule: FillRule,
+    pub stroke_color: Option<Color>,
+    pub stroke: StrokeStyle,
+    /// The last tessellation, reused for drawing and hit testing until the bounds or the path change
+    tessellation: RefCell<Option<Tessellation>>,
+}
+
+impl Default for PathState {
+    fn default() -> Self {
+        PathState {
+            path: Path::new(),
+            view_box: None,
+            fill_color: Some(BLACK),
+            fill_rule: FillRule::NonZero,
+            stroke_color: None,
+            stroke: StrokeStyle::default(),
+            tessellation: RefCell::new(None),
+        }
+    }
+}
+
+impl PathState {
+    pub fn new(path: Path) -> Self {
+        PathState {
+            path: path,
+            ..PathState::default()
+        }
+    }
+    /// The polygons of the fill and the stroke of the path, positioned within `bounds`.
+    /// Empty if the path isn't filled or stroked.
+    pub fn tessellate(&self, bounds: Rect) -> (Vec<Vec<Point>>, Vec<Vec<Point>>) {
+        let view_box = self.view_box.unwrap_or_else(|| Rect::new(Point::zero(), bounds.size));
+        if view_box.size.width <= 0.0 || view_box.size.height <= 0.0 {
+            return (Vec::new(), Vec::new());
+        }
+        let scale = Vector::new(bounds.size.width / view_box.size.width, bounds.size.height / view_box.size.height);
+        let tolerance = TOLERANCE / scale.x.max(scale.y).max(0.001);
+        let contours = self.path.flatten(tolerance);
+        let fill = match self.fill_color {
+            Some(_) => contours.iter().map(|contour| contour.points.clone()).collect(),
+            None => Vec::new(),
+        };
+        let stroke = match self.stroke_color {
+            Some(_) => self.stroke.tessellate(&contours, tolerance),
+            None => Vec::new(),
+        };
+        let position = |polygons: Vec<Vec<Point>>| -> Vec<Vec<Point>> {
+            polygons.into_iter().map(|polygon| {
+                polygon.into_iter().map(|point| Point::new(
+                    bounds.origin.x + (point.x - view_box.origin.x) * scale.x,
+                    bounds.origin.y + (point.y - view_box.origin.y) * scale.y)).collect()
+            }).collect()
+        };
+        (position(fill), position(stroke))
+    }
+    fn stroke_if_drawn(&self) -> Option<&StrokeStyle> {
+        match self.stroke_color {
+            Some(_) => Some(&self.stroke),
+            None => None,
+        }
+    }
+    /// Call `f` with the fill and stroke polygons for `bounds`, tessellating the path only if it changed
+    fn with_polygons<F, T>(&self, bounds: Rect, f: F) -> T
+        where F: FnOnce(&[Vec<Point>], &[Vec<Point>]) -> T
+    {
+        let mut tessellation = self.tessellation.borrow_mut();
+        let is_valid = match *tessellation {
+            Some(ref tessellation) => tessellation.is_valid(bounds, self),
+            None => false,
+        };
+        if !is_valid {
+            let (fill, stroke) = self.tessellate(bounds);
+            *tessellation = Some(Tessellation {
+                bounds: bounds,
+                path: self.path.clone(),
+                view_box: self.view_box,
+                filled: self.fill_color.is_some(),
+                stroke: self.stroke_if_drawn().cloned(),
+                fill_polygons: fill,
+                stroke_polygons: stroke,
+            });
+        }
+        let tessellation = tessellation.as_ref().unwrap();
+        f(&tessellation.fill_polygons, &tessellation.stroke_polygons)
+    }
+}
+
+impl Draw for PathState {
+    fn draw(&mut self, bounds: Rect, _: Rect, renderer: &mut RenderBuilder) {
+        self.with_polygons(bounds, |fill, stroke| {
+            if let Some(color) = self.fill_color {
+                renderer.push_polygons(fill, self.fill_rule, color);
+            }
+            if let Some(color) = self.stroke_color {
+                renderer.push_polygons(stroke, FillRule::NonZero, color);
+            }
+        });
+    }
+    fn visual_bounds(&self, bounds: Rect) -> Rect {
+        self.with_polygons(bounds, |fill, stroke| {
+            let mut visual_bounds = bounds;
+            for polygon_bounds in polygons_bounds(fill).into_iter().chain(polygons_bounds(stroke)) {
+                visual_bounds = visual_bounds.union(&polygon_bounds);
+            }
+            visual_bounds
+        })
+    }
+    fn is_under_cursor(&self, bounds: Rect, cursor: Point) -> bool {
+        self.with_polygons(bounds, |fill, stroke| {
+            polygons_contain(fill, self.fill_rule, cursor) || polygons_contain(stroke, FillRule::NonZero, cursor)
+        })
+    }
+}
+
+#[derive(Clone, Debug)]
+pub enum PathStyle {
+    Path(Value<Path>),
+    ViewBox(Value<Option<Rect>>),
+    FillColor(Value<Option<Color>>),
+    FillRule(Value<FillRule>),
+    StrokeColor(Value<Option<Color>>),
+    StrokeWidth(Value<f32>),
+    LineJoin(Value<LineJoin>),
+    LineCap(Value<LineCap>),
+    Dashes(Value<Vec<f32>>),
+    DashOffset(Value<f32>),
+}
+
+impl Style<PathState> for PathStyle {
+    fn apply(&self, state: &mut PathState, props: &PropSet) -> bool {
+        match *self {
+            PathStyle::Path(ref val) => style::update(&mut state.path, val.get(props)),
+            PathStyle::ViewBox(ref val) => style::update(&mut state.view_box, val.get(props)),
+            PathStyle::FillColor(ref val) => style::update(&mut state.fill_color, val.get(props)),
+            PathStyle::FillRule(ref val) => style::update(&mut state.fill_rule, val.get(props)),
+            PathStyle::StrokeColor(ref val) => style::update(&mut state.stroke_color, val.get(props)),
+            PathStyle::StrokeWidth(ref val) => style::update(&mut state.stroke.width, val.get(props)),
+            PathStyle::LineJoin(ref val) => style::update(&mut state.stroke.join, val.get(props)),
+            PathStyle::LineCap(ref val) => style::update(&mut state.stroke.cap, val.get(props)),
+            PathStyle::Dashes(ref val) => style::update(&mut state.stroke.dashes, val.get(props)),
+            PathStyle::DashOffset(ref val) => style::update(&mut state.stroke.dash_offset, val.get(props)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn stroke_contains(stroke: &StrokeStyle, path: &Path, point: Point) -> bool {
+        let polygons = stroke.tessellate(&path.flatten(TOLERANCE), TOLERANCE);
+        polygons_contain(&polygons, FillRule::NonZero, point)
+    }
+
+    fn corner() -> Path {
+        Path::new().move_to(Point::new(0.0, 0.0)).line_to(Point::new(10.0, 0.0)).line_to(Point::new(10.0, 10.0))
+    }
+
+    fn line() -> Path {
+        Path::new().move_to(Point::new(0.0, 0.0)).line_to(Point::new(10.0, 0.0))
+    }
+
+    fn stroke(join: LineJoin, cap: LineCap) -> StrokeStyle {
+        StrokeStyle {
+            width: 2.0,
+            join: join,
+            cap: cap,
+            ..StrokeStyle::default()
+        }
+    }
+
+    fn dash_ends(stroke: &StrokeStyle) -> Vec<(f32, f32)> {
+        let contour = Contour { points: vec![Point::new(0.0, 0.0), Point::new(10.0, 0.0)], closed: false };
+        stroke.dash(&contour).into_iter().map(|(points, _)| (points[0].x, points[points.len() - 1].x)).collect()
+    }
+
+    #[test]
+    fn arc_stays_on_circle() {
+        let (from, to) = (Point::new(0.0, 0.0), Point::new(20.0, 0.0));
+        let mut points = Vec::new();
+        flatten_arc(from, Size::new(10.0, 10.0), 0.0, false, true, to, TOLERANCE, &mut points);
+        assert!(points.len() > 2);
+        assert_eq!(*points.last().unwrap(), to);
+        for point in &points {
+            assert!((distance(*point, Point::new(10.0, 0.0)) - 10.0).abs() < 1e-3);
+            // clockwise from the left of the circle, with y pointing down, goes over the top
+            assert!(point.y <= 1e-3);
+        }
+    }
+
+    #[test]
+    fn arc_sweep_flag_chooses_side() {
+        let (from, to) = (Point::new(0.0, 0.0), Point::new(20.0, 0.0));
+        let mut points = Vec::new();
+        flatten_arc(from, Size::new(10.0, 10.0), 0.0, false, false, to, TOLERANCE, &mut points);
+        assert!(points.iter().all(|point| point.y >= -1e-3));
+        assert!(points.iter().any(|point| point.y > 9.0));
+    }
+
+    #[test]
+    fn arc_radii_scaled_to_reach_end() {
+        let (from, to) = (Point::new(0.0, 0.0), Point::new(20.0, 0.0));
+        let mut points = Vec::new();
+        flatten_arc(from, Size::new(1.0, 1.0), 0.0, false, true, to, TOLERANCE, &mut points);
+        for point in &points {
+            assert!((distance(*point, Point::new(10.0, 0.0)) - 10.0).abs() < 1e-3);
+        }
+    }
+
+    #[test]
+    fn arc_with_zero_radius_is_line() {
+        let mut points = Vec::new();
+        flatten_arc(Point::zero(), Size::new(0.0, 5.0), 0.0, false, true, Point::new(5.0, 5.0), TOLERANCE, &mut points);
+        assert_eq!(points, vec![Point::new(5.0, 5.0)]);
+    }
+
+    #[test]
+    fn dashes() {
+        let mut stroke = StrokeStyle::default();
+        stroke.dashes = vec![2.0, 3.0];
+        assert_eq!(dash_ends(&stroke), vec![(0.0, 2.0), (5.0, 7.0)]);
+    }
+
+    #[test]
+    fn odd_dash_pattern_repeats() {
+        let mut stroke = StrokeStyle::default();
+        stroke.dashes = vec![2.0];
+        assert_eq!(dash_ends(&stroke), vec![(0.0, 2.0), (4.0, 6.0), (8.0, 10.0)]);
+    }
+
+    #[test]
+    fn dash_offset() {
+        let mut stroke = StrokeStyle::default();
+        stroke.dashes = vec![2.0, 3.0];
+        stroke.dash_offset = 1.0;
+        assert_eq!(dash_ends(&stroke), vec![(0.0, 1.0), (4.0, 6.0), (9.0, 10.0)]);
+    }
+
+    #[test]
+    fn invalid_dashes_draw_solid_line() {
+        let mut stroke = StrokeStyle::default();
+        stroke.dashes = vec![2.0, -1.0];
+        assert_eq!(dash_ends(&stroke), vec![(0.0, 10.0)]);
+    }
+
+    #[test]
+    fn miter_join() {
+        let stroke = stroke(LineJoin::Miter, LineCap::Butt);
+        assert!(stroke_contains(&stroke, &corner(), Point::new(10.9, -0.9)));
+    }
+
+    #[test]
+    fn bevel_join() {
+        let stroke = stroke(LineJoin::Bevel, LineCap::Butt);
+        assert!(stroke_contains(&stroke, &corner(), Point::new(10.4, -0.4)));
+        assert!(!stroke_contains(&stroke, &corner(), Point::new(10.9, -0.9)));
+    }
+
+    #[test]
+    fn round_join() {
+        let stroke = stroke(LineJoin::Round, LineCap::Butt);
+        assert!(stroke_contains(&stroke, &corner(), Point::new(10.6, -0.6)));
+        assert!(!stroke_contains(&stroke, &corner(), Point::new(10.9, -0.9)));
+    }
+
+    #[test]
+    fn sharp_miter_falls_back_to_bevel() {
+        let path = Path::new().move_to(Point::new(0.0, 0.0)).line_to(Point::new(10.0, 0.0)).line_to(Point::new(0.0, 1.0));
+        let stroke = stroke(LineJoin::Miter, LineCap::Butt);
+        let polygons = stroke.tessellate(&path.flatten(TOLERANCE), TOLERANCE);
+        assert!(polygons_bounds(&polygons).unwrap().max_x() < 12.0);
+    }
+
+    #[test]
+    fn caps() {
+        let butt = stroke(LineJoin::Miter, LineCap::Butt);
+        assert!(!stroke_contains(&butt, &line(), Point::new(-0.5, 0.0)));
+        assert!(stroke_contains(&butt, &line(), Point::new(0.5, 0.9)));
+
+        let square = stroke(LineJoin::Miter, LineCap::Square);
+        assert!(stroke_contains(&square, &line(), Point::new(-0.5, 0.9)));
+        assert!(!stroke_contains(&square, &line(), Point::new(-1.5, 0.0)));
+
+        let round = stroke(LineJoin::Miter, LineCap::Round);
+        assert!(stroke_contains(&round, &line(), Point::new(-0.9, 0.0)));
+        assert!(!stroke_contains(&round, &line(), Point::new(-0.8, -0.8)));
+    }
+
+    #[test]
+    fn path_state_tessellates_again_when_changed() {
+        let bounds = Rect::new(Point::zero(), Size::new(20.0, 20.0));
+        let square = |size: f32| Path::new().move_to(Point::zero()).line_to(Point::new(size, 0.0))
+            .line_to(Point::new(size, size)).line_to(Point::new(0.0, size)).close();
+        let mut state = PathState::new(square(5.0));
+        assert!(!state.is_under_cursor(bounds, Point::new(8.0, 8.0)));
+        state.path = square(10.0);
+        assert!(state.is_under_cursor(bounds, Point::new(8.0, 8.0)));
+        state.fill_color = None;
+        assert!(!state.is_under_cursor(bounds, Point::new(8.0, 8.0)));
+    }
+}
@@ -1,25 +1,35 @@
-use webrender::api::{LocalClip, BorderRadius, ComplexClipRegion, PrimitiveInfo};
-
-use render::RenderBuilder;
+use render::{RenderBuilder, CornerRadii, BorderStyle, Gradient, BoxShadow};
 use widget::draw::Draw;
 use widget::property::PropSet;
 use widget::style::{self, Style, Value};
-use geometry::{Rect, RectExt};
+use geometry::{Rect, RectExt, Point};
 use color::*;
 
-#[derive(Debug, Copy, Clone)]
+/// Not `Copy` since gradients were added, as a background gradient owns its color stops,
+/// use `clone` to copy a `RectState`.
+#[derive(Debug, Clone)]
 pub struct RectState {
     pub background_color: Color,
+    /// Drawn over the background color
+    pub background_gradient: Option<Gradient>,
     pub corner_radius: Option<f32>,
+    /// Radii of each corner, used instead of `corner_radius` if set
+    pub corner_radii: Option<CornerRadii>,
     pub border: Option<(f32, Color)>,
+    pub border_style: BorderStyle,
+    pub box_shadow: Option<BoxShadow>,
 }
 
 impl Default for RectState {
     fn default() -> Self {
         RectState {
             background_color: WHITE,
+            background_gradient: None,
             corner_radius: None,
+            corner_radii: None,
             border: None,
+            border_style: BorderStyle::Solid,
+            box_shadow: None,
         }
     }
 }
@@ -28,41 +38,67 @@ impl RectState {
     pub fn new() -> Self {
         RectState::default()
     }
+    fn radii(&self) -> CornerRadii {
+        self.corner_radii.unwrap_or_else(|| CornerRadii::uniform(self.corner_radius.unwrap_or(0.0)))
+    }
 }
 
-fn clip_rounded(rect: Rect, radius: f32) -> LocalClip {
-    let clip_region = ComplexClipRegion::new(rect, BorderRadius::uniform(radius));
-    LocalClip::RoundedRect(rect, clip_region)
-}
-
-fn push_rect(renderer: &mut RenderBuilder, rect: Rect, color: Color, clip_rect: Rect, radius: Option<f32>) {
-    let info = if let Some(radius) = radius {
-        PrimitiveInfo::with_clip(rect, clip_rounded(clip_rect, radius))
+fn push_rect(renderer: &mut RenderBuilder, rect: Rect, radii: CornerRadii, color: Color) {
+    if radii == CornerRadii::zero() {
+        renderer.push_rect(rect, color);
     } else {
-        PrimitiveInfo::new(rect)
-    };
-    renderer.builder.push_rect(&info, color.into());
+        renderer.push_rounded_rect(rect, radii, color);
+    }
 }
 
 impl Draw for RectState {
     fn draw(&mut self, bounds: Rect, _: Rect, renderer: &mut RenderBuilder) {
         // rounding is a hack to prevent bug in webrender that produces artifacts around the corners
         let bounds = bounds.round();
-        if let Some((width, color)) = self.border {
-            let width = if width < 2.0 { 2.0 } else { width };
-            push_rect(renderer, bounds, color, bounds, self.corner_radius);
-            push_rect(renderer, bounds, self.background_color, bounds.shrink_bounds(width), self.corner_radius);
-        } else {
-            push_rect(renderer, bounds, self.background_color, bounds, self.corner_radius);
+        let radii = self.radii();
+        let (outset_shadow, inset_shadow) = match self.box_shadow {
+            Some(shadow) if shadow.inset => (None, Some(shadow)),
+            shadow => (shadow, None),
         };
+        if let Some(shadow) = outset_shadow {
+            renderer.push_box_shadow(bounds, radii, shadow);
+        }
+        push_rect(renderer, bounds, radii, self.background_color);
+        if let Some(ref gradient) = self.background_gradient {
+            renderer.push_gradient(bounds, radii, gradient);
+        }
+        if let Some(shadow) = inset_shadow {
+            // inset shadows are cast inside the border
+            let (shadow_bounds, shadow_radii) = match self.border {
+                Some((width, _)) => (bounds.shrink_bounds(width * 2.0), radii.shrink(width)),
+                None => (bounds, radii),
+            };
+            renderer.push_box_shadow(shadow_bounds, shadow_radii, shadow);
+        }
+        if let Some((width, color)) = self.border {
+            renderer.push_border(bounds, width, color, radii, self.border_style);
+        }
+    }
+    fn visual_bounds(&self, bounds: Rect) -> Rect {
+        match self.box_shadow {
+            Some(shadow) => shadow.bounds(bounds.round()),
+            None => bounds,
+        }
+    }
+    fn is_under_cursor(&self, bounds: Rect, cursor: Point) -> bool {
+        self.radii().contains(bounds, cursor)
     }
 }
 
 #[derive(Clone, Debug)]
 pub enum RectStyle {
     BackgroundColor(Value<Color>),
+    BackgroundGradient(Value<Option<Gradient>>),
     CornerRadius(Value<Option<f32>>),
+    CornerRadii(Value<Option<CornerRadii>>),
     Border(Value<Option<(f32, Color)>>),
+    BorderStyle(Value<BorderStyle>),
+    BoxShadow(Value<Option<BoxShadow>>),
 }
 
 impl Style<RectState> for RectStyle {
@@ -71,8 +107,12 @@ impl Style<RectState> for RectStyle {
             RectStyle::BackgroundColor(ref val) => {
                 style::update(&mut state.background_color, val.get(props))
             }
+            RectStyle::BackgroundGradient(ref val) => style::update(&mut state.background_gradient, val.get(props)),
             RectStyle::CornerRadius(ref val) => style::update(&mut state.corner_radius, val.get(props)),
+            RectStyle::CornerRadii(ref val) => style::update(&mut state.corner_radii, val.get(props)),
             RectStyle::Border(ref val) => style::update(&mut state.border, val.get(props)),
+            RectStyle::BorderStyle(ref val) => style::update(&mut state.border_style, val.get(props)),
+            RectStyle::BoxShadow(ref val) => style::update(&mut state.box_shadow, val.get(props)),
         }
     }
 }
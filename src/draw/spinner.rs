@@ -0,0 +1,69 @@
+use std::f32::consts::PI;
+
+use render::{RenderBuilder, CornerRadii};
+use widget::draw::Draw;
+use widget::property::PropSet;
+use widget::style::{self, Style, Value};
+use geometry::{Rect, RectExt, Point, Size};
+use color::*;
+
+/// A ring of dots, with a highlight that moves around the ring as `phase` goes from 0 to 1
+#[derive(Debug, Copy, Clone)]
+pub struct SpinnerState {
+    pub color: Color,
+    pub dots: usize,
+    pub phase: f32,
+}
+
+impl Default for SpinnerState {
+    fn default() -> Self {
+        SpinnerState {
+            color: GRAY_30,
+            dots: 8,
+            phase: 0.0,
+        }
+    }
+}
+
+impl SpinnerState {
+    pub fn new() -> Self {
+        SpinnerState::default()
+    }
+}
+
+impl Draw for SpinnerState {
+    fn draw(&mut self, bounds: Rect, _: Rect, renderer: &mut RenderBuilder) {
+        let radius = bounds.width().min(bounds.height()) / 2.0;
+        let dot_radius = (radius * 0.2).round().max(1.0);
+        let ring_radius = radius - dot_radius;
+        let center = bounds.center();
+        for dot in 0..self.dots {
+            let fraction = dot as f32 / self.dots as f32;
+            // dots fade out behind the highlight as it moves clockwise
+            let distance = (self.phase - fraction + 1.0) % 1.0;
+            let color = self.color.scale_alpha(1.0 - distance * 0.8);
+
+            let angle = fraction * 2.0 * PI - PI / 2.0;
+            let dot_center = Point::new(center.x + angle.cos() * ring_radius, center.y + angle.sin() * ring_radius);
+            let rect = Rect::new(
+                Point::new(dot_center.x - dot_radius, dot_center.y - dot_radius),
+                Size::new(dot_radius * 2.0, dot_radius * 2.0)).round();
+            renderer.push_rounded_rect(rect, CornerRadii::uniform(dot_radius), color);
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub enum SpinnerStyle {
+    Color(Value<Color>),
+    Dots(Value<usize>),
+}
+
+impl Style<SpinnerState> for SpinnerStyle {
+    fn apply(&self, state: &mut SpinnerState, props: &PropSet) -> bool {
+        match *self {
+            SpinnerStyle::Color(ref val) => style::update(&mut state.color, val.get(props)),
+            SpinnerStyle::Dots(ref val) => style::update(&mut state.dots, val.get(props)),
+        }
+    }
+}
@@ -1,11 +1,9 @@
-use webrender::api::{LayoutPoint, GlyphInstance, PrimitiveInfo, FontInstanceKey};
 use rusttype::{Scale, GlyphId, VMetrics};
 
-use render::RenderBuilder;
+use render::{self, RenderBuilder, Glyph};
 use text_layout::{self, Wrap, Align};
 use resources::resources;
-use geometry::{Size, Rect, RectExt, Vector};
-use render;
+use geometry::{Size, Rect, RectExt, Point, Vector};
 use widget::draw::Draw;
 use widget::property::PropSet;
 use widget::style::{self, Value, Style};
@@ -85,7 +83,7 @@ impl TextState {
             self.wrap,
             self.align).iter().map(|rect| Rect::from_untyped(rect)).collect()
     }
-    fn position_glyphs(&self, bounds: Rect) -> Vec<GlyphInstance> {
+    fn position_glyphs(&self, bounds: Rect) -> Vec<Glyph> {
         let line_height = self.line_height();
         let descent = self.v_metrics().descent;
         let mut resources = resources();
@@ -99,15 +97,12 @@ impl TextState {
             self.wrap,
             self.align).iter().map(|glyph| {
                 let position = glyph.position();
-                GlyphInstance {
+                Glyph {
                     index: glyph.id().0,
-                    point: LayoutPoint::new(position.x, position.y + descent),
+                    point: Point::new(position.x, position.y + descent),
                 }
             }).collect()
     }
-    fn font_instance_key(&self) -> FontInstanceKey {
-        *resources().get_font_instance(&self.font, self.font_size)
-    }
     fn v_metrics(&self) -> VMetrics {
         let mut resources = resources();
         let font = resources.get_font(&self.font);
@@ -139,15 +134,7 @@ impl Draw for TextState {
                 }
             }
         }
-        let key = self.font_instance_key();
-        let info = PrimitiveInfo::new(bounds);
-        renderer.builder.push_text(
-            &info,
-            &glyphs,
-            key,
-            self.text_color.into(),
-            None,
-        );
+        renderer.push_text(bounds, &glyphs, &self.font, self.font_size, self.text_color);
     }
 }
 
@@ -34,13 +34,22 @@
 //! modify the UI are not thread safe, so can't currently be referenced from other threads, so if any specific widgets need
 //! to be notified from another thread, it's necessary to add a handler to the root widget to forward events.
 //!
+//! Events can also be delayed, using `WidgetRef::event_delayed` or `Ui::event_delayed`, to be delivered after a given
+//! duration, which is useful for timers, like showing a tooltip after the mouse has hovered over a widget for some time.
+//! A delayed event can't be cancelled, so handlers should ignore delayed events that are no longer relevant when they
+//! arrive, for example by including a counter in the event that is incremented whenever the timer should be reset.
+//!
 //! For further explanation of the single threaded event architecture see
 //! https://github.com/christolliday/limn/pull/20#discussion_r145373568
 
 use std::any::{Any, TypeId};
 use std::cell::{Cell, RefCell};
 use std::sync::Mutex;
-use std::collections::VecDeque;
+use std::sync::mpsc::{self, Sender, RecvTimeoutError};
+use std::collections::{VecDeque, BinaryHeap};
+use std::cmp::Reverse;
+use std::time::{Instant, Duration};
+use std::thread;
 
 use glutin::{EventsLoop, EventsLoopProxy};
 
@@ -65,6 +74,9 @@ pub(crate) enum Target {
 
 struct Queue {
     queue: VecDeque<(Target, TypeId, Box<Any>)>,
+    /// Events that will be moved to the queue once their deadline has passed, ordered by deadline
+    delayed: VecDeque<(Instant, Target, TypeId, Box<Any>)>,
+    delayed_wakeup: Option<DelayedWakeup>,
     events_loop_proxy: Option<EventsLoopProxy>,
 }
 
@@ -72,11 +84,14 @@ impl Queue {
     fn new() -> Self {
         Queue {
             queue: VecDeque::new(),
+            delayed: VecDeque::new(),
+            delayed_wakeup: None,
             events_loop_proxy: None,
         }
     }
-    fn set_events_loop(&mut self, events_loop: EventsLoopProxy) {
-        self.events_loop_proxy = Some(events_loop);
+    fn set_events_loop(&mut self, events_loop: &EventsLoop) {
+        self.delayed_wakeup = Some(DelayedWakeup::new(events_loop.create_proxy()));
+        self.events_loop_proxy = Some(events_loop.create_proxy());
     }
     /// Push a new event on the queue and wake the window up if it is asleep
     fn push<T: 'static>(&mut self, address: Target, data: T) {
@@ -86,11 +101,74 @@ impl Queue {
             events_loop_proxy.wakeup().unwrap();
         }
     }
+    /// Push an event that will be added to the queue after `delay` has passed.
+    /// The window is woken up by the `DelayedWakeup` thread when the event is due.
+    fn push_delayed<T: 'static>(&mut self, address: Target, data: T, delay: Duration) {
+        let type_id = TypeId::of::<T>();
+        let deadline = Instant::now() + delay;
+        let index = self.delayed.iter().position(|entry| entry.0 > deadline).unwrap_or(self.delayed.len());
+        self.delayed.insert(index, (deadline, address, type_id, Box::new(data)));
+        if let Some(ref delayed_wakeup) = self.delayed_wakeup {
+            delayed_wakeup.wakeup_at(deadline);
+        }
+    }
+    /// Move delayed events whose deadline has passed on to the queue
+    fn release_delayed(&mut self) {
+        let now = Instant::now();
+        while self.delayed.front().map_or(false, |entry| entry.0 <= now) {
+            let (_, address, type_id, data) = self.delayed.pop_front().unwrap();
+            self.queue.push_back((address, type_id, data));
+        }
+    }
+}
+/// A single thread shared by every delayed event, that sleeps until the earliest deadline
+/// it's been sent and then wakes the window up, so the event can be moved on to the queue.
+struct DelayedWakeup {
+    deadlines: Sender<Instant>,
+}
+
+impl DelayedWakeup {
+    fn new(events_loop_proxy: EventsLoopProxy) -> Self {
+        let (sender, receiver) = mpsc::channel();
+        thread::spawn(move || {
+            let mut deadlines = BinaryHeap::new();
+            loop {
+                let now = Instant::now();
+                let mut due = false;
+                while deadlines.peek().map_or(false, |&Reverse(deadline)| deadline <= now) {
+                    deadlines.pop();
+                    due = true;
+                }
+                // the window may have already closed
+                if due && events_loop_proxy.wakeup().is_err() {
+                    return;
+                }
+                let received = match deadlines.peek() {
+                    Some(&Reverse(deadline)) => receiver.recv_timeout(deadline - now),
+                    None => receiver.recv().map_err(|_| RecvTimeoutError::Disconnected),
+                };
+                match received {
+                    Ok(deadline) => deadlines.push(Reverse(deadline)),
+                    Err(RecvTimeoutError::Timeout) => (),
+                    Err(RecvTimeoutError::Disconnected) => return,
+                }
+            }
+        });
+        DelayedWakeup {
+            deadlines: sender,
+        }
+    }
+    fn wakeup_at(&self, deadline: Instant) {
+        // the thread only stops once the window has closed
+        self.deadlines.send(deadline).ok();
+    }
 }
+
 impl Iterator for Queue {
     type Item = (Target, TypeId, Box<Any>);
     /// Take the next event off the Queue, should only be called by App
     fn next(&mut self) -> Option<(Target, TypeId, Box<Any>)> {
+        self.release_delayed();
         self.queue.pop_front()
     }
 }
@@ -185,7 +263,7 @@ pub(super) fn queue_next() -> Option<(Target, TypeId, Box<Any>)> {
 
 pub(super) fn queue_set_events_loop(events_loop: &EventsLoop) {
     GLOBAL_QUEUE.lock().unwrap().set_events_loop(events_loop.create_proxy());
-    LOCAL_QUEUE.with(|queue| queue.as_ref().unwrap().borrow_mut().set_events_loop(events_loop.create_proxy()));
+    LOCAL_QUEUE.with(|queue| queue.as_ref().unwrap().borrow_mut().set_events_loop(events_loop));
 }
 
 /// Send message to target address, must be sent from main UI thread.
@@ -200,6 +278,18 @@ pub(crate) fn event<T: 'static>(address: Target, data: T) {
     });
 }
 
+/// Send message to target address after a delay, must be sent from main UI thread.
+pub(crate) fn event_delayed<T: 'static>(address: Target, data: T, delay: Duration) {
+    LOCAL_QUEUE.with(|queue| {
+        if let Some(queue) = queue.as_ref() {
+            debug!("push delayed event {}", ::type_name::<T>());
+            queue.borrow_mut().push_delayed(address, data, delay);
+        } else {
+            eprintln!("Tried to send event off the main thread, use event_global");
+        }
+    });
+}
+
 /// Send message to UI from any thread.
 pub fn event_global<T: 'static + Send>(data: T) {
     GLOBAL_QUEUE.lock().unwrap().push(data);
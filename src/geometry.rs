@@ -20,6 +20,8 @@ pub type Size = euclid::TypedSize2D<f32, DensityIndependentPixel>;
 pub type Point = euclid::TypedPoint2D<f32, DensityIndependentPixel>;
 pub type Vector = euclid::TypedVector2D<f32, DensityIndependentPixel>;
 pub type Rect = euclid::TypedRect<f32, DensityIndependentPixel>;
+/// A 2D affine transform, used to translate, rotate or scale what's drawn by a widget
+pub type Transform = euclid::TypedTransform2D<f32, DensityIndependentPixel, DensityIndependentPixel>;
 
 /// This is the unit of actual pixels in the framebuffer.
 /// Multiply by the windows hidpi factor to get `DensityIndependentPixel`s
@@ -0,0 +1,128 @@
+//! Helpers for testing drawing against golden images.
+//!
+//! Frames are rendered with the CPU renderer, so the output doesn't depend on the GPU or driver,
+//! and compared with a previously saved image, allowing a small per channel tolerance for
+//! antialiasing differences. Golden images are only created or rewritten from the current output
+//! when the `LIMN_UPDATE_GOLDEN` environment variable is set, otherwise a missing golden image is an error.
+
+use std::fmt;
+use std::env;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use image::{self, Rgba, RgbaImage};
+
+use widget::draw::Draw;
+use render::CpuRenderBuilder;
+use geometry::{Rect, Point, Size};
+use color::WHITE;
+
+/// Environment variable that causes `check_golden` to write golden images instead of comparing
+pub const UPDATE_GOLDEN_VAR: &'static str = "LIMN_UPDATE_GOLDEN";
+
+#[derive(Debug)]
+pub enum GoldenError {
+    Image(image::ImageError),
+    Io(io::Error),
+    /// There is no golden image at the path, set `LIMN_UPDATE_GOLDEN` to create it
+    Missing(PathBuf),
+    /// The image doesn't have the same dimensions as the golden image
+    SizeMismatch { actual: (u32, u32), expected: (u32, u32) },
+    /// Some pixels differ from the golden image by more than the tolerance,
+    /// an image highlighting them is written to `diff_path`
+    Mismatch { differing_pixels: usize, diff_path: PathBuf },
+}
+
+impl fmt::Display for GoldenError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            GoldenError::Image(ref err) => write!(f, "{}", err),
+            GoldenError::Io(ref err) => write!(f, "{}", err),
+            GoldenError::Missing(ref path) => {
+                write!(f, "golden image {} doesn't exist, run with {}=1 to create it", path.display(), UPDATE_GOLDEN_VAR)
+            }
+            GoldenError::SizeMismatch { actual, expected } => {
+                write!(f, "image is {}x{}, golden image is {}x{}", actual.0, actual.1, expected.0, expected.1)
+            }
+            GoldenError::Mismatch { differing_pixels, ref diff_path } => {
+                write!(f, "{} pixels differ from the golden image, see {}", differing_pixels, diff_path.display())
+            }
+        }
+    }
+}
+
+impl From<image::ImageError> for GoldenError {
+    fn from(err: image::ImageError) -> Self {
+        GoldenError::Image(err)
+    }
+}
+
+impl From<io::Error> for GoldenError {
+    fn from(err: io::Error) -> Self {
+        GoldenError::Io(err)
+    }
+}
+
+/// Draw a single draw state with bounds of the given size, on a white background
+pub fn render_draw_state<T: Draw>(state: &mut T, size: Size) -> RgbaImage {
+    let mut renderer = CpuRenderBuilder::new(size, 1.0);
+    renderer.fill_background(WHITE);
+    let bounds = Rect::new(Point::zero(), size);
+    state.draw(bounds, bounds, &mut renderer);
+    renderer.into_image()
+}
+
+/// Compare two images of the same size, returns the number of pixels with a channel differing
+/// by more than `tolerance`, and an image with the differing pixels in red over a faded copy of `expected`
+pub fn compare_images(actual: &RgbaImage, expected: &RgbaImage, tolerance: u8) -> (usize, RgbaImage) {
+    let mut differing_pixels = 0;
+    let diff = RgbaImage::from_fn(expected.width(), expected.height(), |x, y| {
+        let actual = actual.get_pixel(x, y);
+        let expected = expected.get_pixel(x, y);
+        let differs = actual.data.iter().zip(expected.data.iter()).any(|(a, e)| {
+            (*a as i16 - *e as i16).abs() > tolerance as i16
+        });
+        if differs {
+            differing_pixels += 1;
+            Rgba([255, 0, 0, 255])
+        } else {
+            let fade = |c: u8| 191 + c / 4;
+            Rgba([fade(expected[0]), fade(expected[1]), fade(expected[2]), 255])
+        }
+    });
+    (differing_pixels, diff)
+}
+
+/// Compare `frame` with the golden image at `path`. On a mismatch, the image is written next to the
+/// golden image with the extension `.actual.png`, and the differences with the extension `.diff.png`.
+/// If `LIMN_UPDATE_GOLDEN` is set, `frame` is saved as the golden image instead.
+pub fn check_golden<P: AsRef<Path>>(frame: &RgbaImage, path: P, tolerance: u8) -> Result<(), GoldenError> {
+    let path = path.as_ref();
+    if env::var_os(UPDATE_GOLDEN_VAR).is_some() {
+        frame.save(path)?;
+        return Ok(());
+    }
+    if !path.exists() {
+        frame.save(path.with_extension("actual.png"))?;
+        return Err(GoldenError::Missing(path.to_owned()));
+    }
+    let expected = image::open(path)?.to_rgba();
+    if frame.dimensions() != expected.dimensions() {
+        frame.save(path.with_extension("actual.png"))?;
+        return Err(GoldenError::SizeMismatch {
+            actual: frame.dimensions(),
+            expected: expected.dimensions(),
+        });
+    }
+    let (differing_pixels, diff) = compare_images(frame, &expected, tolerance);
+    if differing_pixels > 0 {
+        let diff_path = path.with_extension("diff.png");
+        frame.save(path.with_extension("actual.png"))?;
+        diff.save(&diff_path)?;
+        return Err(GoldenError::Mismatch {
+            differing_pixels: differing_pixels,
+            diff_path: diff_path,
+        });
+    }
+    Ok(())
+}
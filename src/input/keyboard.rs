@@ -9,6 +9,10 @@ use widget::{WidgetRef, WidgetBuilder};
 use widget::property::Property;
 use input::mouse::ClickEvent;
 use event::{EventHandler, EventArgs};
+use geometry::RectExt;
+use ui::{WidgetAttachedEvent, WidgetDetachedEvent};
+use overlay::is_descendant;
+use widgets::scroll::ScrollToWidget;
 use app::App;
 
 use glutin;
@@ -29,6 +33,10 @@ pub struct WidgetReceivedCharacter(pub char);
 /// are registered as focusable.
 /// Later on maybe it should be based on the relative positioning of widgets (could get
 /// ugly updating the treemap as widgets change position), or some user defined ordering.
+///
+/// Focus scopes are pushed when an overlay is opened, remembering what was focused before,
+/// so that focus can be restored when the overlay is closed. A scope that traps focus, like that
+/// of a modal, restricts focus to the widgets within it while it's on top of the stack.
 #[derive(Default)]
 pub struct FocusHandler {
     focusable_map: HashMap<WidgetRef, usize>,
@@ -36,11 +44,29 @@ pub struct FocusHandler {
     focusable: TreeMap<usize, WidgetRef>,
     focused: Option<WidgetRef>,
     focus_index_max: usize,
+    scopes: Vec<FocusScope>,
 }
+
+struct FocusScope {
+    root: WidgetRef,
+    trap_focus: bool,
+    previous_focus: Option<WidgetRef>,
+}
+
 impl FocusHandler {
     pub fn new() -> Self {
         Self::default()
     }
+    /// Returns true if the widget can receive focus, given the innermost scope that traps focus
+    fn in_scope(&self, widget: &WidgetRef) -> bool {
+        match self.scopes.iter().rev().find(|scope| scope.trap_focus) {
+            Some(scope) => is_descendant(widget, &scope.root),
+            None => true,
+        }
+    }
+    fn first_in_scope(&self) -> Option<WidgetRef> {
+        self.focusable.iter().map(|(_, v)| v).find(|widget| self.in_scope(widget)).cloned()
+    }
     fn set_focus(&mut self, new_focus: Option<WidgetRef>) {
         if new_focus != self.focused {
             if let Some(ref mut focused) = self.focused {
@@ -49,6 +75,7 @@ impl FocusHandler {
             self.focused = new_focus;
             if let Some(ref mut focused) = self.focused {
                 focused.add_prop(Property::Focused);
+                focused.event_bubble_up(ScrollToWidget(focused.clone()));
             }
         }
     }
@@ -60,7 +87,7 @@ impl EventHandler<KeyboardInputEvent> for FocusHandler {
                 self.focusable.insert(self.focus_index_max, widget_id.clone());
                 self.focusable_map.insert(widget_id.clone(), self.focus_index_max);
                 self.focus_index_max += 1;
-                if self.focused.is_none() {
+                if self.focused.is_none() && self.in_scope(widget_id) {
                     self.set_focus(Some(widget_id.clone()));
                 }
             }
@@ -74,7 +101,33 @@ impl EventHandler<KeyboardInputEvent> for FocusHandler {
                 self.focusable.remove(&index);
             }
             KeyboardInputEvent::FocusChange(ref new_focus) => {
-                self.set_focus(new_focus.clone());
+                if new_focus.as_ref().map_or(true, |widget| self.in_scope(widget)) {
+                    self.set_focus(new_focus.clone());
+                }
+            }
+            KeyboardInputEvent::PushFocusScope(ref root, trap_focus) => {
+                self.scopes.push(FocusScope {
+                    root: root.clone(),
+                    trap_focus: trap_focus,
+                    previous_focus: self.focused.clone(),
+                });
+                if trap_focus && !self.focused.as_ref().map_or(false, |focused| self.in_scope(focused)) {
+                    let new_focus = self.first_in_scope();
+                    self.set_focus(new_focus);
+                }
+            }
+            KeyboardInputEvent::PopFocusScope(ref root) => {
+                if let Some(index) = self.scopes.iter().position(|scope| scope.root == *root) {
+                    let scope = self.scopes.drain(index..).next().unwrap();
+                    let new_focus = scope.previous_focus.and_then(|widget| {
+                        if self.focusable_map.contains_key(&widget) && self.in_scope(&widget) {
+                            Some(widget)
+                        } else {
+                            None
+                        }
+                    });
+                    self.set_focus(new_focus);
+                }
             }
             KeyboardInputEvent::KeyboardInput(ref key_input) => {
                 if let Some(ref focused) = self.focused {
@@ -88,11 +141,14 @@ impl EventHandler<KeyboardInputEvent> for FocusHandler {
                 if char == '\t' {
                     let mut new_focus = self.focused.clone().and_then(|focused| {
                         let index = &self.focusable_map[&focused];
-                        self.focusable.range(Excluded(index), Unbounded).next().map(|(_, v)| v.clone())
+                        self.focusable.range(Excluded(index), Unbounded)
+                            .map(|(_, v)| v)
+                            .find(|widget| self.in_scope(widget))
+                            .cloned()
                     });
                     if new_focus.is_none() {
                         // focus on first, if any
-                        new_focus = self.focusable.iter().next().map(|(_, v)| v.clone());
+                        new_focus = self.first_in_scope();
                     }
                     self.set_focus(new_focus);
                 } else if let Some(ref focused) = self.focused {
@@ -108,14 +164,51 @@ pub enum KeyboardInputEvent {
     AddFocusable(WidgetRef),
     RemoveFocusable(WidgetRef),
     FocusChange(Option<WidgetRef>),
+    /// Sent when an overlay is opened, with the root of the overlay, and whether focus
+    /// should be kept within it
+    PushFocusScope(WidgetRef, bool),
+    /// Sent when an overlay is closed, restores the focus from before it was opened
+    PopFocusScope(WidgetRef),
     KeyboardInput(KeyboardInput),
     ReceivedCharacter(ReceivedCharacter),
 }
 
+/// Returns true if the key event is the release of a key that should activate
+/// the focused widget, ie. space or enter.
+pub fn is_activate_key(input: &glutin::KeyboardInput) -> bool {
+    if input.state == glutin::ElementState::Released {
+        match input.virtual_keycode {
+            Some(glutin::VirtualKeyCode::Space) | Some(glutin::VirtualKeyCode::Return) => true,
+            _ => false,
+        }
+    } else {
+        false
+    }
+}
+
 impl WidgetBuilder {
+    /// Make a widget receive keyboard focus when clicked, and include it in the
+    /// tab order while it is attached to a parent widget.
     pub fn make_focusable(&mut self) -> &mut Self {
         self.add_handler(|_: &ClickEvent, args: EventArgs| {
             args.ui.get_root().event(KeyboardInputEvent::FocusChange(Some(args.widget)));
+        });
+        self.add_handler(|_: &WidgetAttachedEvent, args: EventArgs| {
+            args.ui.event(KeyboardInputEvent::AddFocusable(args.widget));
+        });
+        self.add_handler(|_: &WidgetDetachedEvent, args: EventArgs| {
+            args.ui.event(KeyboardInputEvent::RemoveFocusable(args.widget));
+        })
+    }
+
+    /// Make a focused widget receive a `ClickEvent` when space or enter is pressed,
+    /// so widgets that respond to clicks can be operated with the keyboard.
+    pub fn click_on_activate_key(&mut self) -> &mut Self {
+        self.add_handler(|event: &WidgetKeyboardInput, args: EventArgs| {
+            if is_activate_key(&event.0) {
+                let event = ClickEvent { position: args.widget.visible_bounds().center() };
+                args.widget.event(event);
+            }
         })
     }
 }
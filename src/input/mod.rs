@@ -3,6 +3,7 @@
 
 pub mod mouse;
 pub mod keyboard;
+pub mod window;
 
 use glutin;
 use glutin::ElementState;
@@ -11,6 +12,7 @@ use webrender;
 use event::{EventHandler, EventArgs};
 use input::mouse::{MouseMoved, MouseButton, MouseWheel};
 use input::keyboard::{KeyboardInput, ReceivedCharacter};
+use input::window::WindowInputEvent;
 use geometry::Point;
 use app::App;
 
@@ -42,20 +44,37 @@ impl App {
                 glutin::WindowEvent::ReceivedCharacter(char) => {
                     args.widget.event(ReceivedCharacter(char));
                 }
+                glutin::WindowEvent::HoveredFile(path) => {
+                    args.widget.event(WindowInputEvent::FileHovered(path));
+                }
+                glutin::WindowEvent::HoveredFileCancelled => {
+                    args.widget.event(WindowInputEvent::FileHoverCancelled);
+                }
+                glutin::WindowEvent::DroppedFile(path) => {
+                    args.widget.event(WindowInputEvent::FileDropped(path));
+                }
+                glutin::WindowEvent::Focused(focused) => {
+                    args.widget.event(WindowInputEvent::Focused(focused));
+                }
+                glutin::WindowEvent::Moved(x, y) => {
+                    args.widget.event(WindowInputEvent::Moved(Point::new(x as f32, y as f32)));
+                }
                 _ => (),
             }
         });
     }
 }
 
-/// Triggers exit on Esc key press.
+/// Triggers exit on Esc key press, unless there are popups or modals open.
 #[derive(Debug, Copy, Clone)]
 pub struct EscKeyCloseHandler;
 
 impl EventHandler<KeyboardInput> for EscKeyCloseHandler {
     fn handle(&mut self, event: &KeyboardInput, args: EventArgs) {
         if let Some(glutin::VirtualKeyCode::Escape) = event.0.virtual_keycode {
-            args.ui.close();
+            if event.0.state == ElementState::Pressed && !args.ui.has_popups() {
+                args.ui.close();
+            }
         }
     }
 }
@@ -1,5 +1,7 @@
 //! Mouse input handlers.
 
+use std::collections::HashMap;
+
 use glutin;
 
 use event::{EventHandler, EventArgs};
@@ -7,6 +9,8 @@ use geometry::Point;
 use widget::{WidgetRef, WidgetBuilder};
 use widget::property::Property;
 use layout::LayoutChanged;
+use ui::WidgetDetachedEvent;
+use overlay::is_descendant;
 use app::App;
 
 #[derive(Debug, Copy, Clone)]
@@ -112,6 +116,7 @@ impl App {
         });
 
         self.add_handler(MouseController::new());
+        self.add_handler(CursorHandler::default());
     }
 }
 
@@ -131,3 +136,50 @@ impl WidgetBuilder {
         })
     }
 }
+
+#[derive(Debug, Clone)]
+enum CursorEvent {
+    Enter(WidgetRef, glutin::MouseCursor),
+    Leave(WidgetRef),
+}
+
+/// Sets the mouse cursor to the cursor of the innermost hovered widget with a cursor
+#[derive(Debug, Default)]
+struct CursorHandler {
+    hovered: HashMap<WidgetRef, glutin::MouseCursor>,
+}
+
+impl EventHandler<CursorEvent> for CursorHandler {
+    fn handle(&mut self, event: &CursorEvent, args: EventArgs) {
+        match *event {
+            CursorEvent::Enter(ref widget, cursor) => {
+                self.hovered.insert(widget.clone(), cursor);
+            }
+            CursorEvent::Leave(ref widget) => {
+                self.hovered.remove(widget);
+            }
+        }
+        // hovered widgets are all ancestors of the widget under the cursor,
+        // so the innermost is a descendant of all the others
+        let cursor = self.hovered.iter().find(|&(widget, _)| {
+            self.hovered.keys().all(|other| is_descendant(widget, other))
+        }).map_or(glutin::MouseCursor::Default, |(_, cursor)| *cursor);
+        args.ui.window.borrow_mut().set_cursor(cursor);
+    }
+}
+
+impl WidgetBuilder {
+    /// Set the mouse cursor shown while the mouse is over this widget, unless it's over
+    /// a child widget with its own cursor
+    pub fn set_cursor(&mut self, cursor: glutin::MouseCursor) -> &mut Self {
+        self.add_handler(move |event: &MouseOverEvent, args: EventArgs| {
+            match *event {
+                MouseOverEvent::Over => args.ui.event(CursorEvent::Enter(args.widget.clone(), cursor)),
+                MouseOverEvent::Out => args.ui.event(CursorEvent::Leave(args.widget.clone())),
+            }
+        });
+        self.add_handler(|_: &WidgetDetachedEvent, args: EventArgs| {
+            args.ui.event(CursorEvent::Leave(args.widget.clone()));
+        })
+    }
+}
@@ -0,0 +1,129 @@
+//! Handlers for window level events from the OS, such as files dropped on the window,
+//! window focus and position, and changes to the hidpi factor.
+
+use std::path::{Path, PathBuf};
+
+use event::{EventHandler, EventArgs};
+use widget::{WidgetBuilder, WidgetRef};
+use input::mouse::MouseMoved;
+use geometry::Point;
+use ui::Ui;
+use app::App;
+
+/// Sent to the widget under the cursor when a file is dragged over the window, bubbling up until handled
+#[derive(Debug, Clone)]
+pub struct FileHovered(pub PathBuf);
+/// Sent to the widget that received `FileHovered` when the file is dragged off the window, or the drag is cancelled
+#[derive(Debug, Copy, Clone)]
+pub struct FileHoverCancelled;
+/// Sent to the widget under the cursor when a file is dropped on the window, bubbling up until handled.
+/// If several files are dropped, one event is sent for each.
+#[derive(Debug, Clone)]
+pub struct FileDropped(pub PathBuf);
+
+/// Sent to every widget when the window gains or loses focus
+#[derive(Debug, Copy, Clone)]
+pub struct WindowFocused(pub bool);
+/// Sent to every widget when the window is moved, with the new position of the window on screen
+#[derive(Debug, Copy, Clone)]
+pub struct WindowMoved(pub Point);
+/// Sent to every widget when the hidpi factor of the window changes,
+/// typically when it's moved to a monitor with a different scale factor.
+/// The change is only detected when the window is moved, just before `WindowMoved` is sent.
+#[derive(Debug, Copy, Clone)]
+pub struct HiDpiFactorChanged(pub f32);
+
+#[derive(Debug, Clone)]
+pub(crate) enum WindowInputEvent {
+    MouseMoved(Point),
+    FileHovered(PathBuf),
+    FileHoverCancelled,
+    FileDropped(PathBuf),
+    Focused(bool),
+    Moved(Point),
+    HiDpiFactorChanged(f32),
+}
+
+/// Routes files dragged over the window to the widget under the cursor, and other window events to every widget.
+/// While a file is dragged over the window, most platforms don't report cursor movement,
+/// so the last known cursor position is used.
+struct WindowInputHandler {
+    mouse: Point,
+    hovered: Option<WidgetRef>,
+}
+
+impl EventHandler<WindowInputEvent> for WindowInputHandler {
+    fn handle(&mut self, event: &WindowInputEvent, args: EventArgs) {
+        match *event {
+            WindowInputEvent::MouseMoved(mouse) => {
+                self.mouse = mouse;
+            }
+            WindowInputEvent::FileHovered(ref path) => {
+                self.hovered = args.ui.widget_under_cursor(self.mouse);
+                if let Some(ref widget) = self.hovered {
+                    widget.event_bubble_up(FileHovered(path.clone()));
+                }
+            }
+            WindowInputEvent::FileHoverCancelled => {
+                if let Some(widget) = self.hovered.take() {
+                    widget.event_bubble_up(FileHoverCancelled);
+                }
+            }
+            WindowInputEvent::FileDropped(ref path) => {
+                self.hovered = None;
+                if let Some(widget) = args.ui.widget_under_cursor(self.mouse) {
+                    widget.event_bubble_up(FileDropped(path.clone()));
+                }
+            }
+            WindowInputEvent::Focused(focused) => {
+                args.ui.event_all(WindowFocused(focused));
+            }
+            WindowInputEvent::Moved(position) => {
+                // glutin 0.10 has no event for hidpi factor changes,
+                // so check if the window moved to a monitor with a different factor
+                let hidpi_factor = args.ui.window.borrow().hidpi_factor();
+                if hidpi_factor != args.ui.hidpi_factor() {
+                    args.widget.event(WindowInputEvent::HiDpiFactorChanged(hidpi_factor));
+                }
+                args.ui.event_all(WindowMoved(position));
+            }
+            WindowInputEvent::HiDpiFactorChanged(factor) => {
+                args.ui.hidpi_factor_changed(factor);
+                args.ui.event_all(HiDpiFactorChanged(factor));
+            }
+        }
+    }
+}
+
+impl Ui {
+    /// Send an event to every widget, including widgets in the overlay
+    pub(crate) fn event_all<T: Clone + 'static>(&self, data: T) {
+        self.root.event_subtree(data.clone());
+        self.overlay.root.event_subtree(data);
+    }
+}
+
+impl App {
+    pub fn add_window_handlers(&mut self) {
+        self.add_handler(|event: &MouseMoved, args: EventArgs| {
+            args.widget.event(WindowInputEvent::MouseMoved(event.0));
+        });
+        self.add_handler(WindowInputHandler {
+            mouse: Point::zero(),
+            hovered: None,
+        });
+    }
+}
+
+impl WidgetBuilder {
+    /// Callback function to be called when a file is dropped on this widget.
+    /// The event isn't sent to the widget's parents.
+    pub fn on_file_dropped<F>(&mut self, callback: F) -> &mut Self
+        where F: Fn(&Path, &mut EventArgs) + 'static
+    {
+        self.add_handler(move |event: &FileDropped, mut args: EventArgs| {
+            callback(&event.0, &mut args);
+            *args.handled = true;
+        })
+    }
+}
@@ -59,7 +59,8 @@ extern crate linked_hash_map;
 extern crate stable_bst;
 extern crate gleam;
 extern crate app_units;
-extern crate image;
+pub extern crate image;
+extern crate regex;
 
 
 #[macro_use]
@@ -75,6 +76,7 @@ pub mod app;
 pub mod widgets;
 pub mod draw;
 pub mod ui;
+pub mod overlay;
 pub mod geometry;
 /// Font, image and texture resources
 pub mod resources;
@@ -84,6 +86,8 @@ pub mod prelude;
 pub mod render;
 /// Wrapper around `glutin::Window`
 pub mod window;
+/// Comparing rendered frames with golden images, for tests
+pub mod golden;
 
 #[cfg(not(feature="nightly"))]
 fn type_name<T>() -> &'static str {
@@ -0,0 +1,293 @@
+//! Floating content, such as popups and modal dialogs, that is drawn above the rest of the UI.
+//!
+//! Every widget is clipped to the bounds of it's parent when drawn, so content that needs to
+//! extend beyond it's parent, like the list of options of a dropdown, can't be part of the
+//! main widget tree. Instead it's added to the overlay, a separate widget tree that's drawn
+//! after the root widget, and that is checked first when finding the widget under the cursor.
+//!
+//! Widgets in the overlay can still be positioned relative to widgets in the main tree,
+//! using the usual layout constraints. Since layout doesn't include scrolling, popups are
+//! moved by however far their anchor is scrolled or transformed from its layout.
+//!
+//! Overlays form a stack, the most recently opened is on top. Popups are closed by a mouse
+//! press outside of them, or by the Escape key. Modal overlays cover the window with a backdrop,
+//! that blocks mouse input to everything beneath them, and keep keyboard focus within the modal
+//! until it's closed. Closing an overlay restores the focus to what was focused when it was opened.
+
+use glutin;
+
+use cassowary::strength::*;
+
+use layout::constraint::*;
+use event::{EventHandler, EventArgs};
+use widget::{WidgetRef, WidgetBuilder};
+use input::mouse::{MouseMoved, MouseButton};
+use input::keyboard::{KeyboardInput, KeyboardInputEvent};
+use draw::rect::{RectState, RectStyle};
+use geometry::{Point, Vector};
+use color::*;
+use ui::Ui;
+use app::App;
+
+lazy_static! {
+    pub static ref STYLE_MODAL_BACKDROP: Vec<RectStyle> = {
+        style!(RectStyle::BackgroundColor: BLACK_TRANSLUCENT)
+    };
+}
+
+/// Sent to a popup or modal after it has been closed, either explicitly,
+/// or because of a click outside of it or an Escape key press.
+#[derive(Debug, Copy, Clone)]
+pub struct PopupClosed;
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+enum OverlayKind {
+    Popup,
+    Modal,
+}
+
+struct OverlayEntry {
+    /// The widget added to the overlay root, for modals, this is the backdrop
+    widget: WidgetRef,
+    /// The widget that was opened
+    content: WidgetRef,
+    anchor: Option<WidgetRef>,
+    /// How far a popup is moved from its layout to follow its anchor
+    anchor_offset: Vector,
+    kind: OverlayKind,
+}
+
+impl OverlayEntry {
+    /// An overlay contains a widget if the widget is part of the overlay or it's anchor
+    fn contains(&self, widget: &WidgetRef) -> bool {
+        is_descendant(widget, &self.widget) ||
+            self.anchor.as_ref().map_or(false, |anchor| is_descendant(widget, anchor))
+    }
+}
+
+/// Returns true if `widget` is `ancestor` or one of it's descendants
+pub(crate) fn is_descendant(widget: &WidgetRef, ancestor: &WidgetRef) -> bool {
+    let mut maybe_widget = Some(widget.clone());
+    while let Some(widget) = maybe_widget {
+        if widget == *ancestor {
+            return true;
+        }
+        maybe_widget = widget.parent();
+    }
+    false
+}
+
+/// The root of the overlay widget tree, and the stack of overlays it contains,
+/// in order from bottom to top.
+pub(crate) struct Overlay {
+    pub(crate) root: WidgetRef,
+    entries: Vec<OverlayEntry>,
+}
+
+impl Overlay {
+    pub(crate) fn new(ui_root: &WidgetRef) -> Self {
+        let mut root = WidgetBuilder::new("overlay");
+        root.layout().no_container();
+        let root: WidgetRef = root.into();
+        root.update_layout(|layout| {
+            layout.add(match_layout(ui_root));
+        });
+        Overlay {
+            root: root,
+            entries: Vec::new(),
+        }
+    }
+}
+
+impl Ui {
+    /// Show a widget floating above the rest of the UI, until it is closed with `close_popup`,
+    /// a mouse press outside of it, or the Escape key.
+    ///
+    /// The popup should be positioned using constraints, typically relative to the anchor widget,
+    /// and it's kept within the window bounds. Clicks on the anchor widget don't close the popup,
+    /// so the anchor can toggle the popup itself.
+    pub fn open_popup<U: Into<WidgetRef>>(&mut self, popup: U, anchor: Option<WidgetRef>) -> WidgetRef {
+        let popup = popup.into();
+        {
+            let root = &self.overlay.root;
+            popup.update_layout(|layout| {
+                layout.add(bound_by(root));
+            });
+        }
+        self.push_overlay(OverlayEntry {
+            widget: popup.clone(),
+            content: popup.clone(),
+            anchor: anchor,
+            anchor_offset: Vector::zero(),
+            kind: OverlayKind::Popup,
+        });
+        self.update_popup_offsets();
+        popup
+    }
+
+    /// Show a widget above a backdrop covering the window, until it is closed with `close_popup`.
+    ///
+    /// While it's open, the widgets beneath it don't receive mouse input, and keyboard focus
+    /// is restricted to focusable widgets within the modal. The modal is centered in the window,
+    /// unless other constraints are added to it.
+    pub fn open_modal<U: Into<WidgetRef>>(&mut self, modal: U) -> WidgetRef {
+        let modal = modal.into();
+        let mut backdrop = WidgetBuilder::new("modal_backdrop");
+        backdrop
+            .set_draw_state_with_style(RectState::new(), STYLE_MODAL_BACKDROP.clone())
+            .layout().add(match_layout(&self.overlay.root));
+        modal.update_layout(|layout| {
+            layout.add(constraints![
+                center(&backdrop).strength(STRONG),
+                bound_by(&backdrop),
+            ]);
+        });
+        backdrop.add_child(modal.clone());
+        self.push_overlay(OverlayEntry {
+            widget: backdrop.into(),
+            content: modal.clone(),
+            anchor: None,
+            anchor_offset: Vector::zero(),
+            kind: OverlayKind::Modal,
+        });
+        modal
+    }
+
+    fn push_overlay(&mut self, entry: OverlayEntry) {
+        self.overlay.root.add_child(entry.widget.clone());
+        let trap_focus = entry.kind == OverlayKind::Modal;
+        self.event(KeyboardInputEvent::PushFocusScope(entry.widget.clone(), trap_focus));
+        self.overlay.entries.push(entry);
+        self.redraw();
+    }
+
+    /// Close a popup or modal opened with `open_popup` or `open_modal`,
+    /// along with any overlays opened after it.
+    pub fn close_popup(&mut self, widget: &WidgetRef) {
+        let index = self.overlay.entries.iter().position(|entry| {
+            entry.widget == *widget || entry.content == *widget
+        });
+        if let Some(index) = index {
+            let first = self.overlay.entries[index].widget.clone();
+            while self.overlay.entries.len() > index {
+                let entry = self.overlay.entries.pop().unwrap();
+                self.overlay.root.remove_child(entry.widget.clone());
+                entry.content.event(PopupClosed);
+            }
+            self.event(KeyboardInputEvent::PopFocusScope(first));
+            self.redraw();
+        }
+    }
+
+    /// Close the most recently opened overlay, if it's a popup
+    pub fn close_top_popup(&mut self) {
+        let top = self.overlay.entries.last().and_then(|entry| {
+            if entry.kind == OverlayKind::Popup { Some(entry.widget.clone()) } else { None }
+        });
+        if let Some(top) = top {
+            self.close_popup(&top);
+        }
+    }
+
+    /// Move popups by the distance their anchors are drawn from their layout, by the scroll frames containing them
+    /// and their transforms, since popups are positioned relative to the layout of their anchors
+    pub(crate) fn update_popup_offsets(&mut self) {
+        for entry in &mut self.overlay.entries {
+            let offset = match entry.anchor {
+                Some(ref anchor) => anchor.visible_bounds().origin - anchor.bounds().origin,
+                None => continue,
+            };
+            if offset != entry.anchor_offset {
+                let mut transform = entry.widget.transform();
+                transform.translation = transform.translation + (offset - entry.anchor_offset);
+                entry.widget.set_transform(transform);
+                entry.anchor_offset = offset;
+            }
+        }
+    }
+
+    /// Returns true if there are any popups or modals open
+    pub fn has_popups(&self) -> bool {
+        !self.overlay.entries.is_empty()
+    }
+
+    /// Returns true if there is a modal open
+    pub fn has_modal(&self) -> bool {
+        self.overlay.entries.iter().any(|entry| entry.kind == OverlayKind::Modal)
+    }
+
+    /// Close every popup that doesn't contain the widget that was pressed,
+    /// or that was opened after an overlay that does. Modals are never closed this way.
+    fn close_popups_outside(&mut self, pressed: Option<WidgetRef>) {
+        let keep = pressed.and_then(|pressed| {
+            self.overlay.entries.iter().rposition(|entry| entry.contains(&pressed))
+        });
+        let modal = self.overlay.entries.iter().rposition(|entry| entry.kind == OverlayKind::Modal);
+        let close_from = keep.into_iter().chain(modal).max().map_or(0, |index| index + 1);
+        let close = self.overlay.entries.get(close_from).map(|entry| entry.widget.clone());
+        if let Some(close) = close {
+            self.close_popup(&close);
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone)]
+enum OverlayInputEvent {
+    MouseMoved(Point),
+    MousePressed,
+    EscapePressed,
+}
+
+struct OverlayInputHandler {
+    mouse: Point,
+}
+
+impl OverlayInputHandler {
+    fn new() -> Self {
+        OverlayInputHandler {
+            mouse: Point::zero(),
+        }
+    }
+}
+
+impl EventHandler<OverlayInputEvent> for OverlayInputHandler {
+    fn handle(&mut self, event: &OverlayInputEvent, args: EventArgs) {
+        match *event {
+            OverlayInputEvent::MouseMoved(point) => {
+                self.mouse = point;
+            }
+            OverlayInputEvent::MousePressed => {
+                if args.ui.has_popups() {
+                    let pressed = args.ui.widget_under_cursor(self.mouse);
+                    args.ui.close_popups_outside(pressed);
+                }
+            }
+            OverlayInputEvent::EscapePressed => {
+                args.ui.close_top_popup();
+            }
+        }
+    }
+}
+
+impl App {
+    /// Add handlers that close popups when the mouse is pressed outside of them,
+    /// or when the Escape key is pressed
+    pub fn add_overlay_handlers(&mut self) {
+        self.add_handler(|event: &MouseMoved, args: EventArgs| {
+            args.widget.event(OverlayInputEvent::MouseMoved(event.0));
+        });
+        self.add_handler(|event: &MouseButton, args: EventArgs| {
+            if let MouseButton(glutin::ElementState::Pressed, _) = *event {
+                args.widget.event(OverlayInputEvent::MousePressed);
+            }
+        });
+        self.add_handler(|event: &KeyboardInput, args: EventArgs| {
+            let &KeyboardInput(input) = event;
+            if input.state == glutin::ElementState::Pressed &&
+                input.virtual_keycode == Some(glutin::VirtualKeyCode::Escape) && args.ui.has_popups() {
+                args.widget.event(OverlayInputEvent::EscapePressed);
+            }
+        });
+        self.add_handler(OverlayInputHandler::new());
+    }
+}
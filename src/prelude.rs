@@ -5,14 +5,16 @@ pub use cassowary::WeightedRelation::*;
 
 pub use glutin;
 
-pub use geometry::{Point, Rect, RectExt, Size, SizeExt, Vector};
+pub use geometry::{Point, Rect, RectExt, Size, SizeExt, Vector, Transform};
 pub use event::{EventHandler, EventArgs};
 pub use event::event_global;
 pub use widget::{WidgetRef, WidgetBuilder};
 pub use widget::draw::{Draw, DrawEventHandler};
 pub use widget::property::Property;
 pub use widget::property::states::*;
-pub use render::RenderBuilder;
+pub use render::{RenderBuilder, CornerRadii, Clip, StackingContext, BlendMode};
+pub use render::{BorderStyle, Gradient, GradientStop, BoxShadow, FillRule, ImageFilter};
+pub use widget::transform::WidgetTransform;
 pub use resources::WidgetId;
 pub use resources::id::{Id, IdGen};
 pub use ui::Ui;
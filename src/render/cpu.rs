@@ -0,0 +1,559 @@
+use image::RgbaImage;
+use rusttype::{self, Scale, GlyphId};
+use webrender::api::{ColorF, ImageData, ImageFormat};
+
+use resources::resources;
+use geometry::{Rect, RectExt, Point, Size, Transform};
+use color::*;
+use super::{RenderBuilder, CornerRadii, Clip, Glyph, StackingContext, BlendMode, BorderStyle, Gradient, BoxShadow, FillRule, ImageFilter};
+use super::{polygons_contain, polygons_bounds};
+
+/// Number of samples per pixel along each axis, used to antialias shape edges
+const SAMPLES: usize = 4;
+
+/// A premultiplied RGBA color
+type Pixel = [f32; 4];
+
+fn premultiplied(color: Color) -> Pixel {
+    let color: ColorF = color.into();
+    [color.r * color.a, color.g * color.a, color.b * color.a, color.a]
+}
+
+fn blend(dst: &mut Pixel, src: Pixel, alpha: f32) {
+    let inverse = 1.0 - src[3] * alpha;
+    for i in 0..4 {
+        dst[i] = src[i] * alpha + dst[i] * inverse;
+    }
+}
+
+/// Composite `src` over `dst` with a blend mode, following the W3C compositing spec
+fn blend_with_mode(dst: &mut Pixel, src: Pixel, alpha: f32, mode: BlendMode) {
+    if mode == BlendMode::Normal {
+        blend(dst, src, alpha);
+        return;
+    }
+    let src = [src[0] * alpha, src[1] * alpha, src[2] * alpha, src[3] * alpha];
+    let (src_alpha, dst_alpha) = (src[3], dst[3]);
+    if src_alpha <= 0.0 {
+        return;
+    }
+    let unpremultiply = |pixel: &Pixel| {
+        if pixel[3] > 0.0 { [pixel[0] / pixel[3], pixel[1] / pixel[3], pixel[2] / pixel[3]] } else { [0.0; 3] }
+    };
+    let blended = blend_colors(unpremultiply(&*dst), unpremultiply(&src), mode);
+    for i in 0..3 {
+        dst[i] = (1.0 - dst_alpha) * src[i] + (1.0 - src_alpha) * dst[i] + src_alpha * dst_alpha * blended[i];
+    }
+    dst[3] = src_alpha + dst_alpha * (1.0 - src_alpha);
+}
+
+/// The blended color of a backdrop and source color, without alpha
+fn blend_colors(backdrop: [f32; 3], source: [f32; 3], mode: BlendMode) -> [f32; 3] {
+    let separable = |f: &Fn(f32, f32) -> f32| {
+        [f(backdrop[0], source[0]), f(backdrop[1], source[1]), f(backdrop[2], source[2])]
+    };
+    let hard_light = |b: f32, s: f32| if s <= 0.5 { b * 2.0 * s } else { screen(b, 2.0 * s - 1.0) };
+    match mode {
+        BlendMode::Normal => source,
+        BlendMode::Multiply => separable(&|b, s| b * s),
+        BlendMode::Screen => separable(&screen),
+        BlendMode::Overlay => separable(&|b, s| hard_light(s, b)),
+        BlendMode::Darken => separable(&f32::min),
+        BlendMode::Lighten => separable(&f32::max),
+        BlendMode::ColorDodge => separable(&|b, s| {
+            if b <= 0.0 { 0.0 } else if s >= 1.0 { 1.0 } else { (b / (1.0 - s)).min(1.0) }
+        }),
+        BlendMode::ColorBurn => separable(&|b, s| {
+            if b >= 1.0 { 1.0 } else if s <= 0.0 { 0.0 } else { 1.0 - ((1.0 - b) / s).min(1.0) }
+        }),
+        BlendMode::HardLight => separable(&hard_light),
+        BlendMode::SoftLight => separable(&|b, s| {
+            if s <= 0.5 {
+                b - (1.0 - 2.0 * s) * b * (1.0 - b)
+            } else {
+                let d = if b <= 0.25 { ((16.0 * b - 12.0) * b + 4.0) * b } else { b.sqrt() };
+                b + (2.0 * s - 1.0) * (d - b)
+            }
+        }),
+        BlendMode::Difference => separable(&|b, s| (b - s).abs()),
+        BlendMode::Exclusion => separable(&|b, s| b + s - 2.0 * b * s),
+        BlendMode::Hue => set_lum(set_sat(source, sat(backdrop)), lum(backdrop)),
+        BlendMode::Saturation => set_lum(set_sat(backdrop, sat(source)), lum(backdrop)),
+        BlendMode::Color => set_lum(source, lum(backdrop)),
+        BlendMode::Luminosity => set_lum(backdrop, lum(source)),
+    }
+}
+
+fn screen(b: f32, s: f32) -> f32 {
+    b + s - b * s
+}
+
+fn lum(color: [f32; 3]) -> f32 {
+    0.3 * color[0] + 0.59 * color[1] + 0.11 * color[2]
+}
+
+fn set_lum(color: [f32; 3], lum_value: f32) -> [f32; 3] {
+    let d = lum_value - lum(color);
+    let color = [color[0] + d, color[1] + d, color[2] + d];
+    let l = lum(color);
+    let min = color[0].min(color[1]).min(color[2]);
+    let max = color[0].max(color[1]).max(color[2]);
+    let mut clipped = color;
+    for channel in &mut clipped {
+        if min < 0.0 {
+            *channel = l + (*channel - l) * l / (l - min);
+        }
+        if max > 1.0 {
+            *channel = l + (*channel - l) * (1.0 - l) / (max - l);
+        }
+    }
+    clipped
+}
+
+fn sat(color: [f32; 3]) -> f32 {
+    color[0].max(color[1]).max(color[2]) - color[0].min(color[1]).min(color[2])
+}
+
+fn set_sat(color: [f32; 3], sat_value: f32) -> [f32; 3] {
+    let max = color[0].max(color[1]).max(color[2]);
+    let min = color[0].min(color[1]).min(color[2]);
+    let mut result = [0.0; 3];
+    if max > min {
+        for i in 0..3 {
+            result[i] = (color[i] - min) * sat_value / (max - min);
+        }
+    }
+    result
+}
+
+struct Layer {
+    pixels: Vec<Pixel>,
+    opacity: f32,
+    blend_mode: BlendMode,
+}
+
+struct ClipState {
+    clip: Clip,
+    /// Maps device pixels to the coordinate space the clip was defined in
+    inverse: Transform,
+    /// Device pixel bounds of the clip, left, top, right, bottom
+    bounds: [i32; 4],
+}
+
+impl ClipState {
+    fn contains(&self, point: Point) -> bool {
+        self.clip.contains(self.inverse.transform_point(&point))
+    }
+}
+
+struct StackingState {
+    has_layer: bool,
+}
+
+/// Rasterizes drawing operations on the CPU, to an RGBA image.
+/// Doesn't need a GPU, and gives the same output on every machine, so it's useful for tests.
+///
+/// Shapes are antialiased by supersampling. Text is rasterized with `rusttype`,
+/// ignoring any rotation from stacking context transforms.
+pub struct CpuRenderBuilder {
+    width: u32,
+    height: u32,
+    layers: Vec<Layer>,
+    transforms: Vec<Transform>,
+    clips: Vec<ClipState>,
+    stacking_contexts: Vec<StackingState>,
+}
+
+impl CpuRenderBuilder {
+    /// Create a renderer for an image of `size` density independent pixels,
+    /// scaled by `hidpi_factor`, with a transparent background
+    pub fn new(size: Size, hidpi_factor: f32) -> Self {
+        let width = (size.width * hidpi_factor).ceil().max(0.0) as u32;
+        let height = (size.height * hidpi_factor).ceil().max(0.0) as u32;
+        CpuRenderBuilder {
+            width: width,
+            height: height,
+            layers: vec![Layer {
+                pixels: vec![[0.0; 4]; (width * height) as usize],
+                opacity: 1.0,
+                blend_mode: BlendMode::Normal,
+            }],
+            transforms: vec![Transform::create_scale(hidpi_factor, hidpi_factor)],
+            clips: Vec::new(),
+            stacking_contexts: Vec::new(),
+        }
+    }
+    /// Fill the image with a background color, before drawing anything else
+    pub fn fill_background(&mut self, color: Color) {
+        let color = premultiplied(color);
+        for pixel in &mut self.layers[0].pixels {
+            *pixel = color;
+        }
+    }
+    /// Get the rendered image, with straight, not premultiplied alpha
+    pub fn into_image(mut self) -> RgbaImage {
+        while self.layers.len() > 1 {
+            self.pop_layer();
+        }
+        let mut bytes = Vec::with_capacity((self.width * self.height * 4) as usize);
+        for pixel in &self.layers[0].pixels {
+            let alpha = pixel[3];
+            for i in 0..3 {
+                let value = if alpha > 0.0 { pixel[i] / alpha } else { 0.0 };
+                bytes.push((value.max(0.0).min(1.0) * 255.0).round() as u8);
+            }
+            bytes.push((alpha.max(0.0).min(1.0) * 255.0).round() as u8);
+        }
+        RgbaImage::from_raw(self.width, self.height, bytes).unwrap()
+    }
+
+    fn transform(&self) -> Transform {
+        *self.transforms.last().unwrap()
+    }
+    fn pop_layer(&mut self) {
+        let layer = self.layers.pop().unwrap();
+        let target = self.layers.last_mut().unwrap();
+        for (dst, src) in target.pixels.iter_mut().zip(layer.pixels) {
+            blend_with_mode(dst, src, layer.opacity, layer.blend_mode);
+        }
+    }
+    /// Device pixel bounds of a rect in the current coordinate space, clipped to the image and clip regions
+    fn device_bounds(&self, rect: Rect) -> [i32; 4] {
+        let transform = self.transform();
+        let corners = [
+            rect.origin,
+            Point::new(rect.right(), rect.top()),
+            Point::new(rect.left(), rect.bottom()),
+            Point::new(rect.right(), rect.bottom()),
+        ];
+        let mut bounds = [i32::max_value(), i32::max_value(), i32::min_value(), i32::min_value()];
+        for corner in &corners {
+            let corner = transform.transform_point(corner);
+            bounds[0] = bounds[0].min(corner.x.floor() as i32);
+            bounds[1] = bounds[1].min(corner.y.floor() as i32);
+            bounds[2] = bounds[2].max(corner.x.ceil() as i32);
+            bounds[3] = bounds[3].max(corner.y.ceil() as i32);
+        }
+        let mut limits = [0, 0, self.width as i32, self.height as i32];
+        for clip in &self.clips {
+            limits = [
+                limits[0].max(clip.bounds[0]),
+                limits[1].max(clip.bounds[1]),
+                limits[2].min(clip.bounds[2]),
+                limits[3].min(clip.bounds[3]),
+            ];
+        }
+        [bounds[0].max(limits[0]), bounds[1].max(limits[1]), bounds[2].min(limits[2]), bounds[3].min(limits[3])]
+    }
+    fn clipped(&self, device_point: Point) -> bool {
+        self.clips.iter().any(|clip| !clip.contains(device_point))
+    }
+    /// Fill the pixels covered by a shape within `rect`, `color_at` returns the premultiplied color
+    /// at a point in the current coordinate space, or `None` if the point is outside the shape
+    fn fill<F>(&mut self, rect: Rect, color_at: F)
+        where F: Fn(Point) -> Option<Pixel>
+    {
+        let transform = self.transform();
+        let inverse = match transform.inverse() {
+            Some(inverse) => inverse,
+            None => return,
+        };
+        let bounds = self.device_bounds(rect);
+        let sample_weight = 1.0 / (SAMPLES * SAMPLES) as f32;
+        for y in bounds[1]..bounds[3] {
+            for x in bounds[0]..bounds[2] {
+                let mut color = [0.0; 4];
+                for sample_y in 0..SAMPLES {
+                    for sample_x in 0..SAMPLES {
+                        let device_point = Point::new(
+                            x as f32 + (sample_x as f32 + 0.5) / SAMPLES as f32,
+                            y as f32 + (sample_y as f32 + 0.5) / SAMPLES as f32);
+                        if self.clipped(device_point) {
+                            continue;
+                        }
+                        if let Some(sample) = color_at(inverse.transform_point(&device_point)) {
+                            for i in 0..4 {
+                                color[i] += sample[i] * sample_weight;
+                            }
+                        }
+                    }
+                }
+                if color[3] > 0.0 {
+                    let index = (y as u32 * self.width + x as u32) as usize;
+                    blend(&mut self.layers.last_mut().unwrap().pixels[index], color, 1.0);
+                }
+            }
+        }
+    }
+}
+
+/// Approximate signed distance from a point to the edge of a rect with rounded corners, negative inside
+fn rounded_rect_distance(rect: Rect, radii: CornerRadii, point: Point) -> f32 {
+    let center = rect.center();
+    let half = Size::new(rect.width() / 2.0, rect.height() / 2.0);
+    let radius = match (point.x < center.x, point.y < center.y) {
+        (true, true) => radii.top_left,
+        (false, true) => radii.top_right,
+        (true, false) => radii.bottom_left,
+        (false, false) => radii.bottom_right,
+    };
+    // elliptical corners are treated as circular, with the average radius
+    let radius = ((radius.width + radius.height) / 2.0).min(half.width).min(half.height).max(0.0);
+    let qx = (point.x - center.x).abs() - half.width + radius;
+    let qy = (point.y - center.y).abs() - half.height + radius;
+    let outside = (qx.max(0.0).powi(2) + qy.max(0.0).powi(2)).sqrt();
+    outside + qx.max(qy).min(0.0) - radius
+}
+
+/// Fraction of a shape covered after a gaussian blur, at a signed distance from its edge
+fn blurred_coverage(distance: f32, blur_radius: f32) -> f32 {
+    if blur_radius <= 0.0 {
+        return if distance <= 0.0 { 1.0 } else { 0.0 };
+    }
+    // the blur radius is twice the standard deviation, as in CSS
+    let x = distance / (blur_radius / 2.0 * ::std::f32::consts::SQRT_2);
+    0.5 * (1.0 - erf(x))
+}
+
+/// Approximation of the error function, accurate to about 1e-6
+fn erf(x: f32) -> f32 {
+    let sign = x.signum();
+    let x = x.abs();
+    let t = 1.0 / (1.0 + 0.327_591 * x);
+    let y = 1.0 - (((((1.061_405 * t - 1.453_152) * t) + 1.421_414) * t - 0.284_496_7) * t + 0.254_829_6) * t * (-x * x).exp();
+    sign * y
+}
+
+/// Check if a point in a border, as drawn with a dashed or dotted style, is on a dash or dot
+fn on_border_pattern(rect: Rect, width: f32, style: BorderStyle, point: Point) -> bool {
+    // position along the nearest edge, and the distance from the edge
+    let edges = [
+        (point.x - rect.left(), point.y - rect.top()),
+        (rect.right() - point.x, point.y - rect.top()),
+        (point.y - rect.top(), point.x - rect.left()),
+        (rect.bottom() - point.y, point.x - rect.left()),
+    ];
+    let &(distance, along) = edges.iter().min_by(|a, b| a.0.partial_cmp(&b.0).unwrap()).unwrap();
+    match style {
+        BorderStyle::Solid => true,
+        BorderStyle::Dashed => (along / (width * 3.0)).floor() as i32 % 2 == 0,
+        BorderStyle::Dotted => {
+            let period = width * 2.0;
+            let dot_center = ((along / period).floor() + 0.5) * period;
+            let (dx, dy) = (along - dot_center, distance - width / 2.0);
+            dx * dx + dy * dy <= width * width / 4.0
+        }
+    }
+}
+
+/// Sample an image at a point, using the nearest pixel
+fn sample_image(data: &[u8], format: ImageFormat, width: u32, height: u32, u: f32, v: f32) -> Option<Pixel> {
+    let x = (u * width as f32).floor();
+    let y = (v * height as f32).floor();
+    if x < 0.0 || y < 0.0 || x >= width as f32 || y >= height as f32 {
+        return None;
+    }
+    let index = y as usize * width as usize + x as usize;
+    let byte = |offset: usize| data.get(offset).map_or(0.0, |&val| f32::from(val) / 255.0);
+    let pixel = match format {
+        // images are stored premultiplied, in BGRA order
+        ImageFormat::BGRA8 => [byte(index * 4 + 2), byte(index * 4 + 1), byte(index * 4), byte(index * 4 + 3)],
+        ImageFormat::RGB8 => [byte(index * 3), byte(index * 3 + 1), byte(index * 3 + 2), 1.0],
+        ImageFormat::RG8 => [byte(index * 2), byte(index * 2 + 1), 0.0, 1.0],
+        ImageFormat::A8 => {
+            let alpha = byte(index);
+            [alpha, alpha, alpha, alpha]
+        }
+        ImageFormat::Invalid | ImageFormat::RGBAF32 => return None,
+    };
+    Some(pixel)
+}
+
+/// Sample an image at a point, interpolating between the four nearest pixels
+fn sample_image_bilinear(data: &[u8], format: ImageFormat, width: u32, height: u32, u: f32, v: f32) -> Option<Pixel> {
+    if u < 0.0 || v < 0.0 || u > 1.0 || v > 1.0 || width == 0 || height == 0 {
+        return None;
+    }
+    let (max_x, max_y) = ((width - 1) as f32, (height - 1) as f32);
+    let x = (u * width as f32 - 0.5).max(0.0).min(max_x);
+    let y = (v * height as f32 - 0.5).max(0.0).min(max_y);
+    let (x0, y0) = (x.floor(), y.floor());
+    let (x1, y1) = ((x0 + 1.0).min(max_x), (y0 + 1.0).min(max_y));
+    let (fx, fy) = (x - x0, y - y0);
+    let texel = |x: f32, y: f32| sample_image(data, format, width, height, (x + 0.5) / width as f32, (y + 0.5) / height as f32);
+    match (texel(x0, y0), texel(x1, y0), texel(x0, y1), texel(x1, y1)) {
+        (Some(a), Some(b), Some(c), Some(d)) => {
+            let mut pixel = [0.0; 4];
+            for i in 0..4 {
+                pixel[i] = (a[i] * (1.0 - fx) + b[i] * fx) * (1.0 - fy) + (c[i] * (1.0 - fx) + d[i] * fx) * fy;
+            }
+            Some(pixel)
+        }
+        _ => None,
+    }
+}
+
+impl RenderBuilder for CpuRenderBuilder {
+    fn push_rect(&mut self, rect: Rect, color: Color) {
+        let color = premultiplied(color);
+        self.fill(rect, |point| if rect.contains(&point) { Some(color) } else { None });
+    }
+    fn push_rounded_rect(&mut self, rect: Rect, radii: CornerRadii, color: Color) {
+        let color = premultiplied(color);
+        self.fill(rect, |point| if radii.contains(rect, point) { Some(color) } else { None });
+    }
+    fn push_border(&mut self, rect: Rect, width: f32, color: Color, radii: CornerRadii, style: BorderStyle) {
+        let color = premultiplied(color);
+        let inner_rect = rect.shrink_bounds(width * 2.0);
+        let inner_rect = Rect::new(inner_rect.origin, Size::new(inner_rect.width().max(0.0), inner_rect.height().max(0.0)));
+        let inner_radii = radii.shrink(width);
+        self.fill(rect, |point| {
+            if radii.contains(rect, point) && !inner_radii.contains(inner_rect, point) &&
+                on_border_pattern(rect, width, style, point)
+            {
+                Some(color)
+            } else {
+                None
+            }
+        });
+    }
+    fn push_gradient(&mut self, rect: Rect, radii: CornerRadii, gradient: &Gradient) {
+        self.fill(rect, |point| {
+            if radii.contains(rect, point) {
+                Some(premultiplied(gradient.color_at(gradient.offset_at(rect, point))))
+            } else {
+                None
+            }
+        });
+    }
+    fn push_box_shadow(&mut self, rect: Rect, radii: CornerRadii, shadow: BoxShadow) {
+        let color = premultiplied(shadow.color);
+        let spread = if shadow.inset { -shadow.spread_radius } else { shadow.spread_radius };
+        let shadow_rect = rect.translate(&shadow.offset).inflate(spread, spread);
+        let shadow_radii = radii.shrink(-spread);
+        self.fill(shadow.bounds(rect), |point| {
+            if radii.contains(rect, point) != shadow.inset {
+                return None;
+            }
+            let coverage = blurred_coverage(rounded_rect_distance(shadow_rect, shadow_radii, point), shadow.blur_radius);
+            let alpha = if shadow.inset { 1.0 - coverage } else { coverage };
+            if alpha > 0.0 {
+                Some([color[0] * alpha, color[1] * alpha, color[2] * alpha, color[3] * alpha])
+            } else {
+                None
+            }
+        });
+    }
+    fn push_text(&mut self, _: Rect, glyphs: &[Glyph], font: &str, font_size: f32, color: Color) {
+        let font = resources().get_font(font).info.clone();
+        let color = premultiplied(color);
+        let transform = self.transform();
+        // the scale of the transform, ignoring rotation
+        let scale = (transform.m11 * transform.m22 - transform.m12 * transform.m21).abs().sqrt();
+        for glyph in glyphs {
+            let position = transform.transform_point(&glyph.point);
+            let glyph = match font.glyph(GlyphId(glyph.index)) {
+                Some(glyph) => glyph,
+                None => continue,
+            };
+            let glyph = glyph.scaled(Scale::uniform(font_size * scale))
+                .positioned(rusttype::point(position.x, position.y));
+            if let Some(bounding_box) = glyph.pixel_bounding_box() {
+                let mut coverage = Vec::new();
+                glyph.draw(|x, y, value| {
+                    coverage.push((bounding_box.min.x + x as i32, bounding_box.min.y + y as i32, value));
+                });
+                for (x, y, value) in coverage {
+                    if x < 0 || y < 0 || x >= self.width as i32 || y >= self.height as i32 || value <= 0.0 {
+                        continue;
+                    }
+                    if self.clipped(Point::new(x as f32 + 0.5, y as f32 + 0.5)) {
+                        continue;
+                    }
+                    let index = (y as u32 * self.width + x as u32) as usize;
+                    blend(&mut self.layers.last_mut().unwrap().pixels[index], color, value);
+                }
+            }
+        }
+    }
+    fn push_tiled_image(&mut self, rect: Rect, image: &str, tile_size: Size, tile_spacing: Size, filter: ImageFilter) {
+        let (data, descriptor) = {
+            let mut resources = resources();
+            let info = *resources.get_image(image);
+            match resources.image_data(image) {
+                Some(&ImageData::Raw(ref data)) => (data.clone(), info.info),
+                // external textures and blob images can only be drawn by WebRender
+                _ => return,
+            }
+        };
+        if tile_size.width <= 0.0 || tile_size.height <= 0.0 {
+            return;
+        }
+        let period = Size::new(tile_size.width + tile_spacing.width, tile_size.height + tile_spacing.height);
+        self.fill(rect, |point| {
+            if !rect.contains(&point) {
+                return None;
+            }
+            // position within the tile
+            let x = (point.x - rect.left()) % period.width;
+            let y = (point.y - rect.top()) % period.height;
+            if x >= tile_size.width || y >= tile_size.height {
+                return None;
+            }
+            let (u, v) = (x / tile_size.width, y / tile_size.height);
+            match filter {
+                ImageFilter::Smooth => sample_image_bilinear(&data, descriptor.format, descriptor.width, descriptor.height, u, v),
+                ImageFilter::CrispEdges | ImageFilter::Pixelated => {
+                    sample_image(&data, descriptor.format, descriptor.width, descriptor.height, u, v)
+                }
+            }
+        });
+    }
+    fn push_polygons(&mut self, contours: &[Vec<Point>], fill_rule: FillRule, color: Color) {
+        let bounds = match polygons_bounds(contours) {
+            Some(bounds) => bounds,
+            None => return,
+        };
+        let color = premultiplied(color);
+        self.fill(bounds, |point| if polygons_contain(contours, fill_rule, point) { Some(color) } else { None });
+    }
+    fn push_clip(&mut self, clip: Clip) {
+        let inverse = match self.transform().inverse() {
+            Some(inverse) => inverse,
+            None => Transform::identity(),
+        };
+        let bounds = self.device_bounds(clip.bounds());
+        self.clips.push(ClipState {
+            clip: clip,
+            inverse: inverse,
+            bounds: bounds,
+        });
+    }
+    fn pop_clip(&mut self) {
+        self.clips.pop();
+    }
+    fn push_stacking_context(&mut self, context: StackingContext) {
+        let transform = match context.transform {
+            Some(transform) => transform.post_mul(&self.transform()),
+            None => self.transform(),
+        };
+        self.transforms.push(transform);
+        let has_layer = context.opacity < 1.0 || context.blend_mode != BlendMode::Normal;
+        if has_layer {
+            self.layers.push(Layer {
+                pixels: vec![[0.0; 4]; (self.width * self.height) as usize],
+                opacity: context.opacity.max(0.0),
+                blend_mode: context.blend_mode,
+            });
+        }
+        self.stacking_contexts.push(StackingState { has_layer: has_layer });
+    }
+    fn pop_stacking_context(&mut self) {
+        self.transforms.pop();
+        if let Some(state) = self.stacking_contexts.pop() {
+            if state.has_layer {
+                self.pop_layer();
+            }
+        }
+    }
+}
@@ -0,0 +1,716 @@
+//! Rendering for limn. `Draw` implementations draw using the limn level `RenderBuilder` API,
+//! which is implemented by a WebRender backend, used to draw the window, a CPU rasterizer backend,
+//! that draws to an RGBA image without needing a GPU, and an SVG backend, that exports vector drawings.
+
+mod webrender_backend;
+mod cpu;
+mod svg;
+
+pub use self::webrender_backend::WebRenderBuilder;
+pub use self::cpu::CpuRenderBuilder;
+pub use self::svg::SvgRenderBuilder;
+
+use std::sync::Arc;
+use std::sync::atomic::{self, AtomicBool};
+
+use gleam::gl;
+use glutin;
+use webrender;
+use webrender::api::*;
+
+use window::Window;
+use euclid::TypedPoint2D;
+use resources;
+use geometry::{Rect, RectExt, Point, Size, Vector, Transform};
+use color::{Color, TRANSPARENT};
+
+/// The radii of the corners of a rounded rectangle, the width and height of each
+/// corner are the horizontal and vertical radii of the corner's ellipse
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct CornerRadii {
+    pub top_left: Size,
+    pub top_right: Size,
+    pub bottom_left: Size,
+    pub bottom_right: Size,
+}
+
+impl CornerRadii {
+    pub fn zero() -> Self {
+        CornerRadii::uniform(0.0)
+    }
+    pub fn uniform(radius: f32) -> Self {
+        CornerRadii::uniform_size(Size::new(radius, radius))
+    }
+    pub fn uniform_size(radius: Size) -> Self {
+        CornerRadii {
+            top_left: radius,
+            top_right: radius,
+            bottom_left: radius,
+            bottom_right: radius,
+        }
+    }
+    /// Radii that make a rectangle an ellipse
+    pub fn ellipse(rect: Rect) -> Self {
+        CornerRadii::uniform_size(rect.size / 2.0)
+    }
+    /// The radii of the inner edge of a border of `width`
+    pub fn shrink(&self, width: f32) -> Self {
+        let shrink = |size: Size| Size::new((size.width - width).max(0.0), (size.height - width).max(0.0));
+        CornerRadii {
+            top_left: shrink(self.top_left),
+            top_right: shrink(self.top_right),
+            bottom_left: shrink(self.bottom_left),
+            bottom_right: shrink(self.bottom_right),
+        }
+    }
+    /// Check if a point is inside `rect` with these corner radii
+    pub fn contains(&self, rect: Rect, point: Point) -> bool {
+        if point.x < rect.left() || point.x >= rect.right() || point.y < rect.top() || point.y >= rect.bottom() {
+            return false;
+        }
+        // each corner's radius, the center of its ellipse, and whether it's on the left and top
+        let corners = [
+            (self.top_left, Point::new(rect.left() + self.top_left.width, rect.top() + self.top_left.height), true, true),
+            (self.top_right, Point::new(rect.right() - self.top_right.width, rect.top() + self.top_right.height), false, true),
+            (self.bottom_left, Point::new(rect.left() + self.bottom_left.width, rect.bottom() - self.bottom_left.height), true, false),
+            (self.bottom_right, Point::new(rect.right() - self.bottom_right.width, rect.bottom() - self.bottom_right.height), false, false),
+        ];
+        for &(radius, center, left, top) in &corners {
+            if radius.width <= 0.0 || radius.height <= 0.0 {
+                continue;
+            }
+            let outside_x = if left { point.x < center.x } else { point.x > center.x };
+            let outside_y = if top { point.y < center.y } else { point.y > center.y };
+            if outside_x && outside_y {
+                let dx = (point.x - center.x) / radius.width;
+                let dy = (point.y - center.y) / radius.height;
+                if dx * dx + dy * dy > 1.0 {
+                    return false;
+                }
+            }
+        }
+        true
+    }
+}
+
+/// A region that drawing is restricted to
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum Clip {
+    Rect(Rect),
+    RoundedRect(Rect, CornerRadii),
+}
+
+impl Clip {
+    pub fn bounds(&self) -> Rect {
+        match *self {
+            Clip::Rect(rect) | Clip::RoundedRect(rect, _) => rect,
+        }
+    }
+    pub fn contains(&self, point: Point) -> bool {
+        match *self {
+            Clip::Rect(rect) => rect.contains(&point),
+            Clip::RoundedRect(rect, radii) => radii.contains(rect, point),
+        }
+    }
+}
+
+/// The line style of a border
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum BorderStyle {
+    Solid,
+    Dashed,
+    Dotted,
+}
+
+impl Default for BorderStyle {
+    fn default() -> Self {
+        BorderStyle::Solid
+    }
+}
+
+/// A color at a position along a gradient, from 0.0 at the start to 1.0 at the end
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct GradientStop {
+    pub offset: f32,
+    pub color: Color,
+}
+
+/// A gradient filling a rect. Positions are fractions of the size of the rect,
+/// so (0.0, 0.0) is the top left of the rect and (1.0, 1.0) is the bottom right.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Gradient {
+    /// Colors change along the line from `start` to `end`
+    Linear { start: Point, end: Point, stops: Vec<GradientStop> },
+    /// Colors change from the center to the edge of the ellipse with radii `radius`
+    Radial { center: Point, radius: Size, stops: Vec<GradientStop> },
+}
+
+impl Gradient {
+    pub fn linear(start: Point, end: Point) -> Self {
+        Gradient::Linear { start: start, end: end, stops: Vec::new() }
+    }
+    /// A linear gradient from the top to the bottom of the rect
+    pub fn vertical() -> Self {
+        Gradient::linear(Point::new(0.0, 0.0), Point::new(0.0, 1.0))
+    }
+    /// A linear gradient from the left to the right of the rect
+    pub fn horizontal() -> Self {
+        Gradient::linear(Point::new(0.0, 0.0), Point::new(1.0, 0.0))
+    }
+    pub fn radial(center: Point, radius: Size) -> Self {
+        Gradient::Radial { center: center, radius: radius, stops: Vec::new() }
+    }
+    /// Add a color stop, stops should be added in order of their offsets
+    pub fn add_stop(mut self, offset: f32, color: Color) -> Self {
+        match self {
+            Gradient::Linear { ref mut stops, .. } | Gradient::Radial { ref mut stops, .. } => {
+                stops.push(GradientStop { offset: offset, color: color });
+            }
+        }
+        self
+    }
+    pub fn stops(&self) -> &[GradientStop] {
+        match *self {
+            Gradient::Linear { ref stops, .. } | Gradient::Radial { ref stops, .. } => stops,
+        }
+    }
+    /// The color at a position along the gradient, from 0.0 to 1.0, interpolated between the stops
+    pub fn color_at(&self, offset: f32) -> Color {
+        let stops = self.stops();
+        if stops.is_empty() {
+            return TRANSPARENT;
+        }
+        if offset <= stops[0].offset {
+            return stops[0].color;
+        }
+        for pair in stops.windows(2) {
+            let (start, end) = (pair[0], pair[1]);
+            if offset <= end.offset {
+                let fraction = if end.offset > start.offset {
+                    (offset - start.offset) / (end.offset - start.offset)
+                } else {
+                    1.0
+                };
+                let (start_color, end_color): (ColorF, ColorF) = (start.color.into(), end.color.into());
+                let mix = |a: f32, b: f32| a + (b - a) * fraction;
+                return ColorF::new(
+                    mix(start_color.r, end_color.r),
+                    mix(start_color.g, end_color.g),
+                    mix(start_color.b, end_color.b),
+                    mix(start_color.a, end_color.a)).into();
+            }
+        }
+        stops[stops.len() - 1].color
+    }
+    /// The position along the gradient of a point within `rect`
+    pub fn offset_at(&self, rect: Rect, point: Point) -> f32 {
+        let relative = Point::new(
+            (point.x - rect.left()) / rect.width(),
+            (point.y - rect.top()) / rect.height());
+        match *self {
+            Gradient::Linear { start, end, .. } => {
+                // project onto the gradient line in absolute units, so the gradient isn't skewed
+                let start = Point::new(start.x * rect.width(), start.y * rect.height());
+                let end = Point::new(end.x * rect.width(), end.y * rect.height());
+                let point = Point::new(relative.x * rect.width(), relative.y * rect.height());
+                let line = end - start;
+                let length = line.dot(line);
+                if length > 0.0 { (point - start).dot(line) / length } else { 0.0 }
+            }
+            Gradient::Radial { center, radius, .. } => {
+                let dx = if radius.width > 0.0 { (relative.x - center.x) / radius.width } else { 0.0 };
+                let dy = if radius.height > 0.0 { (relative.y - center.y) / radius.height } else { 0.0 };
+                (dx * dx + dy * dy).sqrt()
+            }
+        }
+    }
+}
+
+/// A shadow cast by a rect, blurred over `blur_radius` and grown by `spread_radius`.
+/// An inset shadow is drawn inside the rect, as if the rect were a hole.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct BoxShadow {
+    pub offset: Vector,
+    pub blur_radius: f32,
+    pub spread_radius: f32,
+    pub color: Color,
+    pub inset: bool,
+}
+
+impl BoxShadow {
+    pub fn new(offset: Vector, blur_radius: f32, color: Color) -> Self {
+        BoxShadow {
+            offset: offset,
+            blur_radius: blur_radius,
+            spread_radius: 0.0,
+            color: color,
+            inset: false,
+        }
+    }
+    pub fn spread(mut self, spread_radius: f32) -> Self {
+        self.spread_radius = spread_radius;
+        self
+    }
+    pub fn inset(mut self) -> Self {
+        self.inset = true;
+        self
+    }
+    /// The area an outset shadow of a rect draws in
+    pub fn bounds(&self, rect: Rect) -> Rect {
+        if self.inset {
+            rect
+        } else {
+            let extent = self.spread_radius + self.blur_radius;
+            rect.translate(&self.offset).inflate(extent, extent).union(&rect)
+        }
+    }
+}
+
+/// How the inside of overlapping or nested polygons is decided, as in SVG's `fill-rule`
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum FillRule {
+    /// Points are inside if the polygons wind around them a non zero number of times
+    NonZero,
+    /// Points are inside if they're within an odd number of polygons
+    EvenOdd,
+}
+
+impl Default for FillRule {
+    fn default() -> Self {
+        FillRule::NonZero
+    }
+}
+
+impl FillRule {
+    fn is_inside(&self, winding: i32) -> bool {
+        match *self {
+            FillRule::NonZero => winding != 0,
+            FillRule::EvenOdd => winding % 2 != 0,
+        }
+    }
+}
+
+/// Check if a point is inside polygons, given as contours of points that are implicitly closed
+pub fn polygons_contain(contours: &[Vec<Point>], fill_rule: FillRule, point: Point) -> bool {
+    let mut winding = 0;
+    for contour in contours {
+        for (i, &start) in contour.iter().enumerate() {
+            let end = contour[(i + 1) % contour.len()];
+            // positive if the point is left of the edge
+            let side = (end.x - start.x) * (point.y - start.y) - (point.x - start.x) * (end.y - start.y);
+            if start.y <= point.y && end.y > point.y && side > 0.0 {
+                winding += 1;
+            } else if start.y > point.y && end.y <= point.y && side < 0.0 {
+                winding -= 1;
+            }
+        }
+    }
+    fill_rule.is_inside(winding)
+}
+
+/// The bounding box of the points of polygons, `None` if there are no points
+pub fn polygons_bounds(contours: &[Vec<Point>]) -> Option<Rect> {
+    let mut points = contours.iter().flat_map(|contour| contour.iter());
+    let first = match points.next() {
+        Some(point) => *point,
+        None => return None,
+    };
+    let (mut min, mut max) = (first, first);
+    for point in points {
+        min = Point::new(min.x.min(point.x), min.y.min(point.y));
+        max = Point::new(max.x.max(point.x), max.y.max(point.y));
+    }
+    Some(Rect::new(min, Size::new(max.x - min.x, max.y - min.y)))
+}
+
+/// Number of scanlines sampled in each row of spans covering a polygon
+const SPAN_SAMPLES: usize = 4;
+/// Number of distinct coverage values of spans, adjacent pixels with the same value are merged
+const SPAN_COVERAGE_LEVELS: f32 = 16.0;
+/// Number of scanlines sampled in each row of pixels of a polygon mask
+const MASK_SAMPLES: usize = 16;
+
+/// Add the fraction of each pixel in the row starting at `y` that's inside the polygons to `coverage`,
+/// sampled on `samples` scanlines, the first pixel of the row starts at `left`
+fn add_row_coverage(contours: &[Vec<Point>], fill_rule: FillRule, left: f32, y: f32, samples: usize,
+                    coverage: &mut [f32], crossings: &mut Vec<(f32, i32)>)
+{
+    for sample in 0..samples {
+        let sample_y = y + (sample as f32 + 0.5) / samples as f32;
+        // where the edges cross the scanline, with their direction
+        crossings.clear();
+        for contour in contours {
+            for (i, &start) in contour.iter().enumerate() {
+                let end = contour[(i + 1) % contour.len()];
+                if (start.y <= sample_y) != (end.y <= sample_y) {
+                    let x = start.x + (sample_y - start.y) / (end.y - start.y) * (end.x - start.x);
+                    crossings.push((x - left, if end.y > start.y { 1 } else { -1 }));
+                }
+            }
+        }
+        crossings.sort_by(|a, b| a.0.partial_cmp(&b.0).unwrap());
+        let mut winding = 0;
+        for pair in crossings.windows(2) {
+            winding += pair[0].1;
+            if fill_rule.is_inside(winding) {
+                add_span_coverage(coverage, pair[0].0, pair[1].0, 1.0 / samples as f32);
+            }
+        }
+    }
+}
+
+/// Cover polygons with rects one pixel high, paired with the fraction of each rect that's inside
+/// the polygons, so they can be drawn by backends that only draw rects, with antialiased edges
+fn polygon_spans(contours: &[Vec<Point>], fill_rule: FillRule) -> Vec<(Rect, f32)> {
+    let mut spans = Vec::new();
+    let bounds = match polygons_bounds(contours) {
+        Some(bounds) => bounds,
+        None => return spans,
+    };
+    let left = bounds.left().floor();
+    let width = (bounds.right().ceil() - left) as usize + 1;
+    let mut coverage = vec![0.0; width];
+    let mut crossings = Vec::new();
+    let mut y = bounds.top().floor();
+    while y < bounds.bottom() {
+        for value in &mut coverage {
+            *value = 0.0;
+        }
+        add_row_coverage(contours, fill_rule, left, y, SPAN_SAMPLES, &mut coverage, &mut crossings);
+        let level = |value: f32| (value.min(1.0) * SPAN_COVERAGE_LEVELS).round();
+        let mut x = 0;
+        while x < width {
+            let start_level = level(coverage[x]);
+            let mut end = x + 1;
+            while end < width && level(coverage[end]) == start_level {
+                end += 1;
+            }
+            if start_level > 0.0 {
+                let rect = Rect::new(Point::new(left + x as f32, y), Size::new((end - x) as f32, 1.0));
+                spans.push((rect, start_level / SPAN_COVERAGE_LEVELS));
+            }
+            x = end;
+        }
+        y += 1.0;
+    }
+    spans
+}
+
+/// The fraction of each pixel of an image of `width` by `height` pixels that's inside polygons,
+/// row by row from the top left, with polygon positions in pixels of the image
+pub(crate) fn polygon_mask(contours: &[Vec<Point>], fill_rule: FillRule, width: u32, height: u32) -> Vec<f32> {
+    let mut mask = vec![0.0; (width * height) as usize];
+    let mut crossings = Vec::new();
+    if width == 0 {
+        return mask;
+    }
+    for (y, row) in mask.chunks_mut(width as usize).enumerate() {
+        add_row_coverage(contours, fill_rule, 0.0, y as f32, MASK_SAMPLES, row, &mut crossings);
+        for value in row.iter_mut() {
+            *value = value.min(1.0);
+        }
+    }
+    mask
+}
+
+/// Add the coverage of a horizontal interval of a scanline to the pixels it overlaps
+fn add_span_coverage(coverage: &mut [f32], start: f32, end: f32, weight: f32) {
+    let (start, end) = (start.max(0.0), end.min(coverage.len() as f32));
+    if end <= start {
+        return;
+    }
+    let (first, last) = (start.floor() as usize, end.floor() as usize);
+    if first == last {
+        coverage[first] += (end - start) * weight;
+        return;
+    }
+    coverage[first] += (first as f32 + 1.0 - start) * weight;
+    for value in &mut coverage[first + 1..last] {
+        *value += weight;
+    }
+    if last < coverage.len() {
+        coverage[last] += (end - last as f32) * weight;
+    }
+}
+
+/// How images are sampled when they're scaled
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum ImageFilter {
+    /// Interpolate between pixels
+    Smooth,
+    /// Keep edges between pixels sharp, using any method that doesn't blur them
+    CrispEdges,
+    /// Scale with nearest neighbor sampling, for pixel art
+    Pixelated,
+}
+
+impl Default for ImageFilter {
+    fn default() -> Self {
+        ImageFilter::Smooth
+    }
+}
+
+/// A glyph in a text run, positioned by the left of its baseline
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Glyph {
+    pub index: u32,
+    pub point: Point,
+}
+
+/// How a stacking context is combined with what's drawn behind it, the blend modes of CSS `mix-blend-mode`
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum BlendMode {
+    Normal,
+    Multiply,
+    Screen,
+    Overlay,
+    Darken,
+    Lighten,
+    ColorDodge,
+    ColorBurn,
+    HardLight,
+    SoftLight,
+    Difference,
+    Exclusion,
+    Hue,
+    Saturation,
+    Color,
+    Luminosity,
+}
+
+impl Default for BlendMode {
+    fn default() -> Self {
+        BlendMode::Normal
+    }
+}
+
+/// A group of drawing operations, with a transform, opacity and blend mode applied to all of them
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct StackingContext {
+    pub transform: Option<Transform>,
+    pub opacity: f32,
+    pub blend_mode: BlendMode,
+}
+
+impl Default for StackingContext {
+    fn default() -> Self {
+        StackingContext {
+            transform: None,
+            opacity: 1.0,
+            blend_mode: BlendMode::Normal,
+        }
+    }
+}
+
+/// The drawing operations available to `Draw` implementations, implemented by each renderer backend.
+/// Positions are in density independent pixels, relative to the top left of the window.
+pub trait RenderBuilder {
+    fn push_rect(&mut self, rect: Rect, color: Color);
+    fn push_rounded_rect(&mut self, rect: Rect, radii: CornerRadii, color: Color);
+    /// Draw a border of `width` inside the edges of `rect`
+    fn push_border(&mut self, rect: Rect, width: f32, color: Color, radii: CornerRadii, style: BorderStyle);
+    /// Fill a rect with a gradient
+    fn push_gradient(&mut self, rect: Rect, radii: CornerRadii, gradient: &Gradient);
+    /// Draw the shadow of a rect, outset shadows are only drawn outside the rect, and inset shadows inside it
+    fn push_box_shadow(&mut self, rect: Rect, radii: CornerRadii, shadow: BoxShadow);
+    /// Draw a run of glyphs from the font with the given name, as used by `Resources::get_font`
+    fn push_text(&mut self, rect: Rect, glyphs: &[Glyph], font: &str, font_size: f32, color: Color);
+    /// Draw an image with the given name, as used by `Resources::get_image`, stretched to fill `rect`
+    fn push_image(&mut self, rect: Rect, image: &str) {
+        self.push_tiled_image(rect, image, rect.size, Size::zero(), ImageFilter::Smooth);
+    }
+    /// Fill `rect` with tiles of an image, starting from its top left. Each tile is the image stretched
+    /// to `tile_size`, with `tile_spacing` between tiles.
+    fn push_tiled_image(&mut self, rect: Rect, image: &str, tile_size: Size, tile_spacing: Size, filter: ImageFilter);
+    /// Fill polygons, given as contours of points that are implicitly closed. By default the polygons
+    /// are covered with rects one pixel high, backends that can fill paths or draw masks override it.
+    fn push_polygons(&mut self, contours: &[Vec<Point>], fill_rule: FillRule, color: Color) {
+        for (rect, coverage) in polygon_spans(contours, fill_rule) {
+            self.push_rect(rect, color.scale_alpha(coverage));
+        }
+    }
+    /// Restrict drawing to a region until the matching `pop_clip`
+    fn push_clip(&mut self, clip: Clip);
+    fn pop_clip(&mut self);
+    /// Apply a transform and opacity to drawing until the matching `pop_stacking_context`
+    fn push_stacking_context(&mut self, context: StackingContext);
+    fn pop_stacking_context(&mut self);
+    /// Draw the following operations moved by a scroll offset and clipped to `clip`, until the matching
+    /// `pop_scroll_frame`. `content` covers everything drawn in the frame. Backends that can scroll
+    /// without drawing the frame again identify it by the id of the widget that draws it.
+    fn push_scroll_frame(&mut self, _id: resources::WidgetId, clip: Rect, _content: Rect, offset: Vector) {
+        self.push_clip(Clip::Rect(clip));
+        self.push_stacking_context(StackingContext {
+            transform: Some(Transform::create_translation(offset.x, offset.y)),
+            ..StackingContext::default()
+        });
+    }
+    fn pop_scroll_frame(&mut self) {
+        self.pop_stacking_context();
+        self.pop_clip();
+    }
+}
+
+// Provides access to the WebRender context and API
+pub(super) struct WebRenderContext {
+    pub renderer: webrender::Renderer,
+    pub render_api: RenderApi,
+    pub epoch: Epoch,
+    pub pipeline_id: PipelineId,
+    pub document_id: DocumentId,
+    pub device_pixel_ratio: f32,
+    pub root_background_color: ColorF,
+    // store frame ready event in case it is received after
+    // update but before the event queue is waiting, otherwise
+    // the event queue can go idle while there is a frame ready
+    pub frame_ready: Arc<AtomicBool>,
+}
+
+impl WebRenderContext {
+    pub fn new(window: &mut Window, events_loop: &glutin::EventsLoop) -> Self {
+        let gl = window.gl();
+        println!("OpenGL version {}", gl.get_string(gl::VERSION));
+        println!("HiDPI factor {}", window.hidpi_factor());
+
+        let opts = webrender::RendererOptions {
+            resource_override_path: None,
+            debug: true,
+            precache_shaders: false,
+            device_pixel_ratio: window.hidpi_factor(),
+            .. webrender::RendererOptions::default()
+        };
+
+        let (mut renderer, sender) = webrender::Renderer::new(gl, opts).unwrap();
+        let api = sender.create_api();
+        resources::init_resources(sender.create_api(), window.hidpi_factor());
+        let document_id = api.add_document(window.size_px());
+
+        let frame_ready = Arc::new(AtomicBool::new(false));
+        let notifier = Box::new(Notifier::new(events_loop.create_proxy(), Arc::clone(&frame_ready)));
+        renderer.set_render_notifier(notifier);
+
+        renderer.set_external_image_handler(Box::new(LimnExternalImageHandler));
+
+        let epoch = Epoch(0);
+        let root_background_color = ColorF::new(0.8, 0.8, 0.8, 1.0);
+
+        let pipeline_id = PipelineId(0, 0);
+        api.set_root_pipeline(document_id, pipeline_id);
+        WebRenderContext {
+            renderer: renderer,
+            render_api: api,
+            epoch: epoch,
+            pipeline_id: pipeline_id,
+            document_id: document_id,
+            device_pixel_ratio: window.hidpi_factor(),
+            root_background_color: root_background_color,
+            frame_ready: frame_ready,
+        }
+    }
+    pub fn deinit(self) {
+        self.renderer.deinit();
+    }
+    pub fn render_builder(&mut self, window_size: LayoutSize) -> WebRenderBuilder {
+        WebRenderBuilder::new(DisplayListBuilder::new(self.pipeline_id, window_size))
+    }
+    pub fn set_display_list(&mut self, builder: DisplayListBuilder, resources: ResourceUpdates, window_size: LayoutSize) {
+        self.render_api.set_display_list(
+            self.document_id,
+            self.epoch,
+            Some(self.root_background_color),
+            window_size,
+            builder.finalize(),
+            true,
+            resources
+        );
+    }
+    pub fn generate_frame(&mut self) {
+        self.render_api.generate_frame(self.document_id, None);
+    }
+    /// Move the content of a scroll frame in the current display list
+    pub fn scroll_frame(&mut self, id: resources::WidgetId, offset: Vector) {
+        let origin = LayoutPoint::new(-offset.x, -offset.y);
+        let clip_id = webrender_backend::scroll_frame_id(id, self.pipeline_id);
+        self.render_api.scroll_node_with_id(self.document_id, origin, clip_id, ScrollClamping::NoClamping);
+    }
+    pub fn frame_ready(&mut self) -> bool {
+        self.frame_ready.load(atomic::Ordering::Acquire)
+    }
+    // if there is a frame ready, update current frame and render it, otherwise, does nothing
+    pub fn update(&mut self, window_size: DeviceUintSize) {
+        self.frame_ready.store(false, atomic::Ordering::Release);
+        self.renderer.update();
+        self.renderer.render(window_size).unwrap();
+    }
+    pub fn toggle_flags(&mut self, toggle_flags: webrender::DebugFlags) {
+        let mut flags = self.renderer.get_debug_flags();
+        flags.toggle(toggle_flags);
+        self.renderer.set_debug_flags(flags);
+    }
+    pub fn set_device_pixel_ratio(&mut self, device_pixel_ratio: f32) {
+        self.device_pixel_ratio = device_pixel_ratio;
+    }
+    pub fn window_resized(&mut self, size: DeviceUintSize) {
+        let window_rect = DeviceUintRect::new(TypedPoint2D::zero(), size);
+        self.render_api.set_window_parameters(self.document_id, size, window_rect, self.device_pixel_ratio);
+    }
+}
+
+struct Notifier {
+    events_proxy: glutin::EventsLoopProxy,
+    frame_ready: Arc<AtomicBool>,
+}
+impl Notifier {
+    fn new(events_proxy: glutin::EventsLoopProxy, frame_ready: Arc<AtomicBool>) -> Self {
+        Notifier {
+            events_proxy: events_proxy,
+            frame_ready: frame_ready,
+        }
+    }
+}
+
+impl RenderNotifier for Notifier {
+    fn new_frame_ready(&mut self) {
+        #[cfg(not(target_os = "android"))]
+        debug!("new frame ready");
+        self.events_proxy.wakeup().ok();
+        self.frame_ready.store(true, atomic::Ordering::Release);
+    }
+
+    fn new_scroll_frame_ready(&mut self, composite_needed: bool) {
+        #[cfg(not(target_os = "android"))]
+        debug!("new scroll frame ready");
+        self.events_proxy.wakeup().ok();
+        if composite_needed {
+            self.frame_ready.store(true, atomic::Ordering::Release);
+        }
+    }
+}
+
+pub fn draw_rect_outline(rect: Rect, color: Color, renderer: &mut RenderBuilder) {
+    renderer.push_border(rect, 1.0, color, CornerRadii::zero(), BorderStyle::Solid);
+}
+
+pub fn draw_horizontal_line(baseline: f32, start: f32, end: f32, color: Color, renderer: &mut RenderBuilder) {
+    renderer.push_rect(Rect::new(Point::new(start, baseline), Size::new(end - start, 1.0)), color);
+}
+
+// This weird thing is required just to pass a texture's id to WebRender
+struct LimnExternalImageHandler;
+
+impl webrender::ExternalImageHandler for LimnExternalImageHandler {
+    // Do not perform any actual locking since rendering happens on the main thread
+    fn lock(&mut self, key: ExternalImageId, _channel_index: u8) -> webrender::ExternalImage {
+        let descriptor = resources::resources().texture_descriptors[&key.0];
+        webrender::ExternalImage {
+            u0: 0.0,
+            u1: descriptor.width as f32,
+            v1: 0.0,
+            v0: descriptor.height as f32,
+            source: webrender::ExternalImageSource::NativeTexture(key.0 as _),
+        }
+    }
+
+    fn unlock(&mut self, _key: ExternalImageId, _channel_index: u8) {
+    }
+}
@@ -0,0 +1,422 @@
+use std::collections::HashMap;
+use std::fmt::Write;
+
+use image::ColorType;
+use image::png::PNGEncoder;
+use rusttype::{Scale, GlyphId, Segment};
+use webrender::api::{ColorF, ImageData, ImageFormat};
+
+use resources::resources;
+use geometry::{Rect, RectExt, Point, Size};
+use color::Color;
+use super::{RenderBuilder, CornerRadii, Clip, Glyph, StackingContext, BlendMode, BorderStyle, Gradient, BoxShadow, FillRule, ImageFilter};
+
+/// Records drawing operations as an SVG document.
+///
+/// Text is drawn as the outlines of its glyphs, in a group that references the font by name,
+/// and images are embedded as PNG data URIs. The output only depends on what's drawn,
+/// so it can be compared between runs.
+pub struct SvgRenderBuilder {
+    view_box: Rect,
+    body: String,
+    depth: usize,
+    /// Number used for the id of the next clip path, gradient or filter
+    next_id: usize,
+    /// Encoded data URIs of the images drawn so far, `None` if an image can't be exported
+    image_uris: HashMap<String, Option<String>>,
+}
+
+impl SvgRenderBuilder {
+    /// Create a document showing the region `view_box`, in density independent pixels
+    pub fn new(view_box: Rect) -> Self {
+        SvgRenderBuilder {
+            view_box: view_box,
+            body: String::new(),
+            depth: 1,
+            next_id: 0,
+            image_uris: HashMap::new(),
+        }
+    }
+    /// Fill the document with a background color, before drawing anything else
+    pub fn fill_background(&mut self, color: Color) {
+        let view_box = self.view_box;
+        self.push_rect(view_box, color);
+    }
+    pub fn into_svg(self) -> String {
+        let view_box = self.view_box;
+        let mut svg = String::new();
+        writeln!(svg, "<svg xmlns=\"http://www.w3.org/2000/svg\" xmlns:xlink=\"http://www.w3.org/1999/xlink\" width=\"{}\" height=\"{}\" viewBox=\"{} {} {} {}\">",
+            num(view_box.width()), num(view_box.height()),
+            num(view_box.left()), num(view_box.top()), num(view_box.width()), num(view_box.height())).unwrap();
+        svg.push_str(&self.body);
+        svg.push_str("</svg>\n");
+        svg
+    }
+
+    fn line(&mut self, line: &str) {
+        for _ in 0..self.depth {
+            self.body.push_str("  ");
+        }
+        self.body.push_str(line);
+        self.body.push('\n');
+    }
+    /// The element for a rect with rounded corners, with extra attributes
+    fn shape(rect: Rect, radii: CornerRadii, attributes: &str) -> String {
+        if radii == CornerRadii::zero() {
+            format!("<rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\"{}/>",
+                num(rect.left()), num(rect.top()), num(rect.width()), num(rect.height()), attributes)
+        } else if radii == CornerRadii::ellipse(rect) {
+            format!("<ellipse cx=\"{}\" cy=\"{}\" rx=\"{}\" ry=\"{}\"{}/>",
+                num(rect.center().x), num(rect.center().y), num(rect.width() / 2.0), num(rect.height() / 2.0), attributes)
+        } else if radii == CornerRadii::uniform_size(radii.top_left) {
+            format!("<rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\" rx=\"{}\" ry=\"{}\"{}/>",
+                num(rect.left()), num(rect.top()), num(rect.width()), num(rect.height()),
+                num(radii.top_left.width), num(radii.top_left.height), attributes)
+        } else {
+            format!("<path d=\"{}\"{}/>", rounded_rect_path(rect, radii), attributes)
+        }
+    }
+    fn next_id(&mut self) -> usize {
+        self.next_id += 1;
+        self.next_id - 1
+    }
+    fn image_uri(&mut self, image: &str) -> Option<String> {
+        if !self.image_uris.contains_key(image) {
+            let uri = {
+                let mut resources = resources();
+                let descriptor = resources.get_image(image).info;
+                match resources.image_data(image) {
+                    Some(&ImageData::Raw(ref data)) => png_data_uri(data, descriptor.format, descriptor.width, descriptor.height),
+                    // external textures and blob images only exist on the GPU
+                    _ => None,
+                }
+            };
+            self.image_uris.insert(image.to_owned(), uri);
+        }
+        self.image_uris[image].clone()
+    }
+}
+
+impl RenderBuilder for SvgRenderBuilder {
+    fn push_rect(&mut self, rect: Rect, color: Color) {
+        let line = SvgRenderBuilder::shape(rect, CornerRadii::zero(), &fill(color));
+        self.line(&line);
+    }
+    fn push_rounded_rect(&mut self, rect: Rect, radii: CornerRadii, color: Color) {
+        let line = SvgRenderBuilder::shape(rect, radii, &fill(color));
+        self.line(&line);
+    }
+    fn push_border(&mut self, rect: Rect, width: f32, color: Color, radii: CornerRadii, style: BorderStyle) {
+        let line = if style == BorderStyle::Solid {
+            let inner_rect = shrink(rect, width);
+            format!("<path d=\"{} {}\" fill-rule=\"evenodd\"{}/>",
+                rounded_rect_path(rect, radii), rounded_rect_path(inner_rect, radii.shrink(width)), fill(color))
+        } else {
+            // dashed and dotted borders are stroked along the middle of the border
+            let dash_array = if style == BorderStyle::Dashed {
+                format!("{} {}", num(width * 3.0), num(width * 3.0))
+            } else {
+                format!("0 {}", num(width * 2.0))
+            };
+            let cap = if style == BorderStyle::Dotted { " stroke-linecap=\"round\"" } else { "" };
+            let stroke = fill(color).replace("fill", "stroke");
+            SvgRenderBuilder::shape(shrink(rect, width / 2.0), radii.shrink(width / 2.0), &format!(
+                " fill=\"none\"{} stroke-width=\"{}\" stroke-dasharray=\"{}\"{}", stroke, num(width), dash_array, cap))
+        };
+        self.line(&line);
+    }
+    fn push_gradient(&mut self, rect: Rect, radii: CornerRadii, gradient: &Gradient) {
+        let id = self.next_id();
+        // positions are fractions of the size of the rect, the same as the bounding box units of SVG gradients
+        let mut definition = match *gradient {
+            Gradient::Linear { start, end, .. } => format!("<linearGradient id=\"gradient{}\" x1=\"{}\" y1=\"{}\" x2=\"{}\" y2=\"{}\">",
+                id, num(start.x), num(start.y), num(end.x), num(end.y)),
+            Gradient::Radial { center, radius, .. } => {
+                // bounding box units make the radius a fraction of the rect's diagonal, so use absolute units
+                let center = Point::new(rect.left() + center.x * rect.width(), rect.top() + center.y * rect.height());
+                let radius = Size::new(radius.width * rect.width(), radius.height * rect.height());
+                let scale_y = if radius.width > 0.0 { radius.height / radius.width } else { 1.0 };
+                format!("<radialGradient id=\"gradient{}\" gradientUnits=\"userSpaceOnUse\" cx=\"{}\" cy=\"{}\" r=\"{}\" \
+                    gradientTransform=\"translate({} {}) scale(1 {}) translate({} {})\">",
+                    id, num(center.x), num(center.y), num(radius.width),
+                    num(center.x), num(center.y), num(scale_y), num(-center.x), num(-center.y))
+            }
+        };
+        for stop in gradient.stops() {
+            let stop_color = fill(stop.color).replace("fill", "stop-color").replace("stop-color-opacity", "stop-opacity");
+            write!(definition, "<stop offset=\"{}\"{}/>", num(stop.offset), stop_color).unwrap();
+        }
+        definition.push_str(match *gradient {
+            Gradient::Linear { .. } => "</linearGradient>",
+            Gradient::Radial { .. } => "</radialGradient>",
+        });
+        self.line(&definition);
+        let line = SvgRenderBuilder::shape(rect, radii, &format!(" fill=\"url(#gradient{})\"", id));
+        self.line(&line);
+    }
+    fn push_box_shadow(&mut self, rect: Rect, radii: CornerRadii, shadow: BoxShadow) {
+        let id = self.next_id();
+        let bounds = shadow.bounds(rect).inflate(shadow.blur_radius, shadow.blur_radius);
+        // the blur radius is twice the standard deviation, as in CSS
+        self.line(&format!("<filter id=\"shadow{}\" filterUnits=\"userSpaceOnUse\" x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\">\
+            <feGaussianBlur stdDeviation=\"{}\"/></filter>",
+            id, num(bounds.left()), num(bounds.top()), num(bounds.width()), num(bounds.height()), num(shadow.blur_radius / 2.0)));
+        // outset shadows are clipped to outside the rect, inset shadows to inside it
+        let clip = if shadow.inset {
+            SvgRenderBuilder::shape(rect, radii, "")
+        } else {
+            format!("<path d=\"{} {}\" clip-rule=\"evenodd\"/>", rounded_rect_path(bounds, CornerRadii::zero()), rounded_rect_path(rect, radii))
+        };
+        self.line(&format!("<clipPath id=\"clip{}\">{}</clipPath>", id, clip));
+        let attributes = format!(" filter=\"url(#shadow{})\"{}", id, fill(shadow.color));
+        let line = if shadow.inset {
+            // a hole the shape of the shadow, in a rect covering the blur
+            let hole = shrink(rect.translate(&shadow.offset), shadow.spread_radius);
+            format!("<path d=\"{} {}\" fill-rule=\"evenodd\"{}/>",
+                rounded_rect_path(bounds, CornerRadii::zero()),
+                rounded_rect_path(hole, radii.shrink(shadow.spread_radius)), attributes)
+        } else {
+            let shadow_rect = rect.translate(&shadow.offset).inflate(shadow.spread_radius, shadow.spread_radius);
+            SvgRenderBuilder::shape(shadow_rect, radii.shrink(-shadow.spread_radius), &attributes)
+        };
+        self.line(&format!("<g clip-path=\"url(#clip{})\">", id));
+        self.depth += 1;
+        self.line(&line);
+        self.depth -= 1;
+        self.line("</g>");
+    }
+    fn push_text(&mut self, _: Rect, glyphs: &[Glyph], font: &str, font_size: f32, color: Color) {
+        let font_info = resources().get_font(font).info.clone();
+        let mut path = String::new();
+        for glyph in glyphs {
+            let point = glyph.point;
+            let shape = font_info.glyph(GlyphId(glyph.index))
+                .and_then(|outline| outline.scaled(Scale::uniform(font_size)).shape());
+            for contour in shape.unwrap_or_default() {
+                let mut first = true;
+                for segment in &contour.segments {
+                    let (start, rest) = match *segment {
+                        Segment::Line(ref line) => (line.p[0], format!("L{} {}", num(point.x + line.p[1].x), num(point.y + line.p[1].y))),
+                        Segment::Curve(ref curve) => (curve.p[0], format!("Q{} {} {} {}",
+                            num(point.x + curve.p[1].x), num(point.y + curve.p[1].y),
+                            num(point.x + curve.p[2].x), num(point.y + curve.p[2].y))),
+                    };
+                    if first {
+                        write!(path, "M{} {}", num(point.x + start.x), num(point.y + start.y)).unwrap();
+                        first = false;
+                    }
+                    path.push_str(&rest);
+                }
+                if !first {
+                    path.push('Z');
+                }
+            }
+        }
+        let line = format!("<g data-font=\"{}\" font-size=\"{}\"{}>", escape(font), num(font_size), fill(color));
+        self.line(&line);
+        self.depth += 1;
+        if !path.is_empty() {
+            self.line(&format!("<path d=\"{}\"/>", path));
+        }
+        self.depth -= 1;
+        self.line("</g>");
+    }
+    fn push_tiled_image(&mut self, rect: Rect, image: &str, tile_size: Size, tile_spacing: Size, filter: ImageFilter) {
+        let uri = match self.image_uri(image) {
+            Some(uri) => uri,
+            None => {
+                self.line(&format!("<!-- image \"{}\" can't be exported -->", escape(image)));
+                return;
+            }
+        };
+        let rendering = match filter {
+            ImageFilter::Smooth => "",
+            ImageFilter::CrispEdges => " style=\"image-rendering: crisp-edges\"",
+            ImageFilter::Pixelated => " style=\"image-rendering: pixelated\"",
+        };
+        if tile_size == rect.size && tile_spacing == Size::zero() {
+            self.line(&format!("<image x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\" preserveAspectRatio=\"none\"{} xlink:href=\"{}\"/>",
+                num(rect.left()), num(rect.top()), num(rect.width()), num(rect.height()), rendering, uri));
+            return;
+        }
+        let id = self.next_id();
+        self.line(&format!("<pattern id=\"pattern{}\" patternUnits=\"userSpaceOnUse\" x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\">",
+            id, num(rect.left()), num(rect.top()),
+            num(tile_size.width + tile_spacing.width), num(tile_size.height + tile_spacing.height)));
+        self.depth += 1;
+        self.line(&format!("<image width=\"{}\" height=\"{}\" preserveAspectRatio=\"none\"{} xlink:href=\"{}\"/>",
+            num(tile_size.width), num(tile_size.height), rendering, uri));
+        self.depth -= 1;
+        self.line("</pattern>");
+        let line = SvgRenderBuilder::shape(rect, CornerRadii::zero(), &format!(" fill=\"url(#pattern{})\"", id));
+        self.line(&line);
+    }
+    fn push_polygons(&mut self, contours: &[Vec<Point>], fill_rule: FillRule, color: Color) {
+        let mut path = String::new();
+        for contour in contours.iter().filter(|contour| !contour.is_empty()) {
+            for (i, point) in contour.iter().enumerate() {
+                write!(path, "{}{} {} ", if i == 0 { "M" } else { "L" }, num(point.x), num(point.y)).unwrap();
+            }
+            path.push('Z');
+        }
+        let fill_rule = match fill_rule {
+            FillRule::NonZero => "nonzero",
+            FillRule::EvenOdd => "evenodd",
+        };
+        self.line(&format!("<path d=\"{}\" fill-rule=\"{}\"{}/>", path, fill_rule, fill(color)));
+    }
+    fn push_clip(&mut self, clip: Clip) {
+        let id = self.next_id();
+        let shape = match clip {
+            Clip::Rect(rect) => SvgRenderBuilder::shape(rect, CornerRadii::zero(), ""),
+            Clip::RoundedRect(rect, radii) => SvgRenderBuilder::shape(rect, radii, ""),
+        };
+        self.line(&format!("<clipPath id=\"clip{}\">{}</clipPath>", id, shape));
+        self.line(&format!("<g clip-path=\"url(#clip{})\">", id));
+        self.depth += 1;
+    }
+    fn pop_clip(&mut self) {
+        self.depth -= 1;
+        self.line("</g>");
+    }
+    fn push_stacking_context(&mut self, context: StackingContext) {
+        let mut line = "<g".to_owned();
+        if let Some(t) = context.transform {
+            write!(line, " transform=\"matrix({} {} {} {} {} {})\"",
+                num(t.m11), num(t.m12), num(t.m21), num(t.m22), num(t.m31), num(t.m32)).unwrap();
+        }
+        if context.opacity < 1.0 {
+            write!(line, " opacity=\"{}\"", num(context.opacity.max(0.0))).unwrap();
+        }
+        if context.blend_mode != BlendMode::Normal {
+            write!(line, " style=\"mix-blend-mode: {}\"", css_blend_mode(context.blend_mode)).unwrap();
+        }
+        line.push('>');
+        self.line(&line);
+        self.depth += 1;
+    }
+    fn pop_stacking_context(&mut self) {
+        self.depth -= 1;
+        self.line("</g>");
+    }
+}
+
+/// A rect shrunk by `width` on each side, without going below zero size
+fn shrink(rect: Rect, width: f32) -> Rect {
+    let rect = rect.shrink_bounds(width * 2.0);
+    Rect::new(rect.origin, Size::new(rect.width().max(0.0), rect.height().max(0.0)))
+}
+
+/// Format a number with at most 3 decimals, without trailing zeros
+fn num(value: f32) -> String {
+    let mut text = format!("{:.3}", value);
+    while text.ends_with('0') {
+        text.pop();
+    }
+    if text.ends_with('.') {
+        text.pop();
+    }
+    if text == "-0" {
+        text = "0".to_owned();
+    }
+    text
+}
+
+fn css_blend_mode(blend_mode: BlendMode) -> &'static str {
+    match blend_mode {
+        BlendMode::Normal => "normal",
+        BlendMode::Multiply => "multiply",
+        BlendMode::Screen => "screen",
+        BlendMode::Overlay => "overlay",
+        BlendMode::Darken => "darken",
+        BlendMode::Lighten => "lighten",
+        BlendMode::ColorDodge => "color-dodge",
+        BlendMode::ColorBurn => "color-burn",
+        BlendMode::HardLight => "hard-light",
+        BlendMode::SoftLight => "soft-light",
+        BlendMode::Difference => "difference",
+        BlendMode::Exclusion => "exclusion",
+        BlendMode::Hue => "hue",
+        BlendMode::Saturation => "saturation",
+        BlendMode::Color => "color",
+        BlendMode::Luminosity => "luminosity",
+    }
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;").replace('"', "&quot;")
+}
+
+/// Fill attributes for a color
+fn fill(color: Color) -> String {
+    let color: ColorF = color.into();
+    let channel = |value: f32| (value.max(0.0).min(1.0) * 255.0).round() as u8;
+    let mut attributes = format!(" fill=\"#{:02x}{:02x}{:02x}\"", channel(color.r), channel(color.g), channel(color.b));
+    if color.a < 1.0 {
+        write!(attributes, " fill-opacity=\"{}\"", num(color.a.max(0.0))).unwrap();
+    }
+    attributes
+}
+
+/// Path data for a rect with rounded corners, clockwise from the top left
+fn rounded_rect_path(rect: Rect, radii: CornerRadii) -> String {
+    let (left, top, right, bottom) = (rect.left(), rect.top(), rect.right(), rect.bottom());
+    let arc = |radius: Size, x: f32, y: f32| {
+        if radius.width > 0.0 && radius.height > 0.0 {
+            format!("A{} {} 0 0 1 {} {}", num(radius.width), num(radius.height), num(x), num(y))
+        } else {
+            format!("L{} {}", num(x), num(y))
+        }
+    };
+    format!("M{} {}H{}{}V{}{}H{}{}V{}{}Z",
+        num(left + radii.top_left.width), num(top),
+        num(right - radii.top_right.width), arc(radii.top_right, right, top + radii.top_right.height),
+        num(bottom - radii.bottom_right.height), arc(radii.bottom_right, right - radii.bottom_right.width, bottom),
+        num(left + radii.bottom_left.width), arc(radii.bottom_left, left, bottom - radii.bottom_left.height),
+        num(top + radii.top_left.height), arc(radii.top_left, left + radii.top_left.width, top))
+}
+
+/// Encode image data as a PNG data URI, images are stored premultiplied, in BGRA order
+fn png_data_uri(data: &[u8], format: ImageFormat, width: u32, height: u32) -> Option<String> {
+    let mut rgba = Vec::with_capacity((width * height * 4) as usize);
+    let pixel_count = (width * height) as usize;
+    for index in 0..pixel_count {
+        let byte = |offset: usize| data.get(offset).cloned().unwrap_or(0);
+        let pixel = match format {
+            ImageFormat::BGRA8 => {
+                let alpha = byte(index * 4 + 3);
+                let unpremultiply = |value: u8| {
+                    if alpha == 0 { 0 } else { (u32::from(value) * 255 / u32::from(alpha)).min(255) as u8 }
+                };
+                [unpremultiply(byte(index * 4 + 2)), unpremultiply(byte(index * 4 + 1)), unpremultiply(byte(index * 4)), alpha]
+            }
+            ImageFormat::RGB8 => [byte(index * 3), byte(index * 3 + 1), byte(index * 3 + 2), 255],
+            ImageFormat::RG8 => [byte(index * 2), byte(index * 2 + 1), 0, 255],
+            ImageFormat::A8 => [255, 255, 255, byte(index)],
+            ImageFormat::Invalid | ImageFormat::RGBAF32 => return None,
+        };
+        rgba.extend_from_slice(&pixel);
+    }
+    let mut png = Vec::new();
+    if PNGEncoder::new(&mut png).encode(&rgba, width, height, ColorType::RGBA(8)).is_err() {
+        return None;
+    }
+    Some(format!("data:image/png;base64,{}", base64(&png)))
+}
+
+fn base64(bytes: &[u8]) -> String {
+    const ALPHABET: &'static [u8] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+    let mut text = String::with_capacity((bytes.len() + 2) / 3 * 4);
+    for chunk in bytes.chunks(3) {
+        let b = [chunk[0], *chunk.get(1).unwrap_or(&0), *chunk.get(2).unwrap_or(&0)];
+        let n = (u32::from(b[0]) << 16) | (u32::from(b[1]) << 8) | u32::from(b[2]);
+        for i in 0..4 {
+            if i <= chunk.len() {
+                text.push(ALPHABET[(n >> (18 - 6 * i) & 0x3F) as usize] as char);
+            } else {
+                text.push('=');
+            }
+        }
+    }
+    text
+}
@@ -0,0 +1,269 @@
+use webrender::api::*;
+
+use resources::{resources, WidgetId, PolygonImageKey};
+use geometry::{Rect, RectExt, Point, Size, Vector, Transform};
+use color::Color;
+use super::{RenderBuilder, CornerRadii, Clip, Glyph, StackingContext, BlendMode, Gradient, BoxShadow};
+use super::{FillRule, polygons_bounds, polygon_mask};
+
+/// Builds a WebRender display list, and the resource updates needed to draw it
+pub struct WebRenderBuilder {
+    pub builder: DisplayListBuilder,
+    pub resources: ResourceUpdates,
+    /// Offsets of the scroll frames in the display list, which are set after the display list
+    pub scroll_offsets: Vec<(WidgetId, Vector)>,
+    /// Transforms of the stacking contexts being built, so polygons can be rasterized at the scale they're drawn at
+    transforms: Vec<Transform>,
+}
+
+impl WebRenderBuilder {
+    pub fn new(builder: DisplayListBuilder) -> Self {
+        WebRenderBuilder {
+            builder: builder,
+            resources: ResourceUpdates::new(),
+            scroll_offsets: Vec::new(),
+            transforms: vec![Transform::identity()],
+        }
+    }
+    fn transform(&self) -> Transform {
+        *self.transforms.last().unwrap()
+    }
+}
+
+/// Polygon points are cached in fractions of a pixel this fine, relative to the bounds of the polygons
+const POLYGON_POINT_PRECISION: f32 = 256.0;
+/// Polygon images are rasterized at this many sub-pixel offsets in each direction,
+/// so they can be placed on device pixels while they're moved by less than a pixel
+const POLYGON_SUBPIXEL_OFFSETS: f32 = 4.0;
+
+/// The clip id of the scroll frame drawn by a widget, stable between display lists
+/// so WebRender can keep its scroll offset
+pub(super) fn scroll_frame_id(id: WidgetId, pipeline_id: PipelineId) -> ClipId {
+    // clip id 0 is the root scroll frame
+    ClipId::new(id.0 as u64 + 1, pipeline_id)
+}
+
+fn border_radius(radii: CornerRadii) -> BorderRadius {
+    BorderRadius {
+        top_left: radii.top_left,
+        top_right: radii.top_right,
+        bottom_left: radii.bottom_left,
+        bottom_right: radii.bottom_right,
+    }
+}
+
+fn mix_blend_mode(blend_mode: BlendMode) -> MixBlendMode {
+    match blend_mode {
+        BlendMode::Normal => MixBlendMode::Normal,
+        BlendMode::Multiply => MixBlendMode::Multiply,
+        BlendMode::Screen => MixBlendMode::Screen,
+        BlendMode::Overlay => MixBlendMode::Overlay,
+        BlendMode::Darken => MixBlendMode::Darken,
+        BlendMode::Lighten => MixBlendMode::Lighten,
+        BlendMode::ColorDodge => MixBlendMode::ColorDodge,
+        BlendMode::ColorBurn => MixBlendMode::ColorBurn,
+        BlendMode::HardLight => MixBlendMode::HardLight,
+        BlendMode::SoftLight => MixBlendMode::SoftLight,
+        BlendMode::Difference => MixBlendMode::Difference,
+        BlendMode::Exclusion => MixBlendMode::Exclusion,
+        BlendMode::Hue => MixBlendMode::Hue,
+        BlendMode::Saturation => MixBlendMode::Saturation,
+        BlendMode::Color => MixBlendMode::Color,
+        BlendMode::Luminosity => MixBlendMode::Luminosity,
+    }
+}
+
+fn clip_rounded(rect: Rect, radii: CornerRadii) -> LocalClip {
+    LocalClip::RoundedRect(rect, ComplexClipRegion::new(rect, border_radius(radii)))
+}
+
+impl RenderBuilder for WebRenderBuilder {
+    fn push_rect(&mut self, rect: Rect, color: Color) {
+        self.builder.push_rect(&PrimitiveInfo::new(rect), color.into());
+    }
+    fn push_rounded_rect(&mut self, rect: Rect, radii: CornerRadii, color: Color) {
+        let info = PrimitiveInfo::with_clip(rect, clip_rounded(rect, radii));
+        self.builder.push_rect(&info, color.into());
+    }
+    fn push_border(&mut self, rect: Rect, width: f32, color: Color, radii: CornerRadii, style: super::BorderStyle) {
+        let widths = BorderWidths { left: width, right: width, top: width, bottom: width };
+        let style = match style {
+            super::BorderStyle::Solid => BorderStyle::Solid,
+            super::BorderStyle::Dashed => BorderStyle::Dashed,
+            super::BorderStyle::Dotted => BorderStyle::Dotted,
+        };
+        let side = BorderSide { color: color.into(), style: style };
+        let border = NormalBorder {
+            left: side,
+            right: side,
+            top: side,
+            bottom: side,
+            radius: border_radius(radii),
+        };
+        self.builder.push_border(&PrimitiveInfo::new(rect), widths, BorderDetails::Normal(border));
+    }
+    fn push_gradient(&mut self, rect: Rect, radii: CornerRadii, gradient: &Gradient) {
+        let stops: Vec<_> = gradient.stops().iter().map(|stop| {
+            GradientStop { offset: stop.offset, color: stop.color.into() }
+        }).collect();
+        let info = PrimitiveInfo::with_clip(rect, clip_rounded(rect, radii));
+        // gradient positions are relative to the primitive
+        let relative = |point: Point| LayoutPoint::new(point.x * rect.width(), point.y * rect.height());
+        match *gradient {
+            Gradient::Linear { start, end, .. } => {
+                let gradient = self.builder.create_gradient(relative(start), relative(end), stops, ExtendMode::Clamp);
+                self.builder.push_gradient(&info, gradient, rect.size, Size::zero());
+            }
+            Gradient::Radial { center, radius, .. } => {
+                let radius = Size::new(radius.width * rect.width(), radius.height * rect.height());
+                let gradient = self.builder.create_radial_gradient(relative(center), radius, stops, ExtendMode::Clamp);
+                self.builder.push_radial_gradient(&info, gradient, rect.size, Size::zero());
+            }
+        }
+    }
+    fn push_box_shadow(&mut self, rect: Rect, radii: CornerRadii, shadow: BoxShadow) {
+        let clip_mode = if shadow.inset { BoxShadowClipMode::Inset } else { BoxShadowClipMode::Outset };
+        self.builder.push_box_shadow(
+            &PrimitiveInfo::new(shadow.bounds(rect)),
+            rect,
+            shadow.offset,
+            shadow.color.into(),
+            shadow.blur_radius,
+            shadow.spread_radius,
+            border_radius(radii),
+            clip_mode,
+        );
+    }
+    fn push_text(&mut self, rect: Rect, glyphs: &[Glyph], font: &str, font_size: f32, color: Color) {
+        let key = *resources().get_font_instance(font, font_size);
+        let glyphs: Vec<GlyphInstance> = glyphs.iter().map(|glyph| {
+            GlyphInstance {
+                index: glyph.index,
+                point: glyph.point,
+            }
+        }).collect();
+        self.builder.push_text(&PrimitiveInfo::new(rect), &glyphs, key, color.into(), None);
+    }
+    fn push_tiled_image(&mut self, rect: Rect, image: &str, tile_size: Size, tile_spacing: Size, filter: super::ImageFilter) {
+        let key = resources().get_image(image).key;
+        let rendering = match filter {
+            super::ImageFilter::Smooth => ImageRendering::Auto,
+            super::ImageFilter::CrispEdges => ImageRendering::CrispEdges,
+            super::ImageFilter::Pixelated => ImageRendering::Pixelated,
+        };
+        self.builder.push_image(
+            &PrimitiveInfo::new(rect),
+            tile_size,
+            tile_spacing,
+            rendering,
+            key,
+        );
+    }
+    /// WebRender can't fill paths, so the polygons are rasterized to an image at the scale they're drawn at,
+    /// including the hidpi factor and the transforms of the stacking contexts containing them.
+    /// The image is reused while the same polygons are drawn in following frames, even if they have moved.
+    fn push_polygons(&mut self, contours: &[Vec<Point>], fill_rule: FillRule, color: Color) {
+        let bounds = match polygons_bounds(contours) {
+            Some(bounds) => bounds,
+            None => return,
+        };
+        let mut resources = resources();
+        let transform = self.transform();
+        let transform_scale = f32::max(
+            (transform.m11 * transform.m11 + transform.m12 * transform.m12).sqrt(),
+            (transform.m21 * transform.m21 + transform.m22 * transform.m22).sqrt());
+        let scale = resources.hidpi_factor * transform_scale;
+        // polygons scaled to nothing aren't drawn
+        if !scale.is_normal() {
+            return;
+        }
+        // the image is placed on the device pixel left of and above the polygons, the offset from that pixel
+        // is rounded to a few sub-pixel positions so the image is still reused as the polygons move
+        let device_origin = transform.transform_point(&bounds.origin) * resources.hidpi_factor;
+        let offset = Vector::new(
+            ((device_origin.x - device_origin.x.floor()) * POLYGON_SUBPIXEL_OFFSETS).round(),
+            ((device_origin.y - device_origin.y.floor()) * POLYGON_SUBPIXEL_OFFSETS).round());
+        let key = PolygonImageKey {
+            contours: contours.iter().map(|contour| {
+                contour.iter().map(|point| {
+                    let local = *point - bounds.origin;
+                    ((local.x * POLYGON_POINT_PRECISION).round() as i32, (local.y * POLYGON_POINT_PRECISION).round() as i32)
+                }).collect()
+            }).collect(),
+            scale: scale.to_bits(),
+            offset: (offset.x as u8, offset.y as u8),
+            width: (bounds.width() * scale + offset.x / POLYGON_SUBPIXEL_OFFSETS).ceil().max(1.0) as u32,
+            height: (bounds.height() * scale + offset.y / POLYGON_SUBPIXEL_OFFSETS).ceil().max(1.0) as u32,
+            fill_rule: fill_rule,
+            color: color,
+        };
+        let offset = offset / POLYGON_SUBPIXEL_OFFSETS;
+        let (width, height) = (key.width, key.height);
+        // rasterize the rounded points in the key, so the image is the same whichever polygons created it
+        let contours: Vec<Vec<Point>> = key.contours.iter().map(|contour| {
+            contour.iter().map(|&(x, y)| {
+                Point::new(x as f32 / POLYGON_POINT_PRECISION * scale, y as f32 / POLYGON_POINT_PRECISION * scale) + offset
+            }).collect()
+        }).collect();
+        let image_key = resources.get_polygon_image(key, || {
+            let color: ColorF = color.into();
+            let rgb = [color.r, color.g, color.b].iter().map(|c| (c * 255.0).round() as u8).collect::<Vec<_>>();
+            let mut pixels = Vec::with_capacity((width * height * 4) as usize);
+            for coverage in polygon_mask(&contours, fill_rule, width, height) {
+                pixels.extend_from_slice(&rgb);
+                pixels.push((color.a * coverage * 255.0).round() as u8);
+            }
+            pixels
+        });
+        let rect = Rect::new(
+            bounds.origin - offset / scale,
+            Size::new(width as f32 / scale, height as f32 / scale));
+        self.builder.push_image(&PrimitiveInfo::new(rect), rect.size, Size::zero(), ImageRendering::Auto, image_key);
+    }
+    fn push_clip(&mut self, clip: Clip) {
+        let complex = match clip {
+            Clip::Rect(_) => vec![],
+            Clip::RoundedRect(rect, radii) => vec![ComplexClipRegion::new(rect, border_radius(radii))],
+        };
+        let clip_id = self.builder.define_clip(None, clip.bounds(), complex, None);
+        self.builder.push_clip_id(clip_id);
+    }
+    fn pop_clip(&mut self) {
+        self.builder.pop_clip_id();
+    }
+    fn push_stacking_context(&mut self, context: StackingContext) {
+        let current = match context.transform {
+            Some(transform) => transform.post_mul(&self.transform()),
+            None => self.transform(),
+        };
+        self.transforms.push(current);
+        let transform = context.transform.map(|transform| PropertyBinding::Value(transform.to_3d()));
+        let filters = if context.opacity < 1.0 {
+            vec![FilterOp::Opacity(PropertyBinding::Value(context.opacity))]
+        } else {
+            vec![]
+        };
+        self.builder.push_stacking_context(
+            &PrimitiveInfo::new(Rect::zero()),
+            ScrollPolicy::Scrollable,
+            transform,
+            TransformStyle::Flat,
+            None,
+            mix_blend_mode(context.blend_mode),
+            filters,
+        );
+    }
+    fn pop_stacking_context(&mut self) {
+        self.transforms.pop();
+        self.builder.pop_stacking_context();
+    }
+    fn push_scroll_frame(&mut self, id: WidgetId, clip: Rect, content: Rect, offset: Vector) {
+        let clip_id = scroll_frame_id(id, self.builder.pipeline_id);
+        self.builder.define_scroll_frame(Some(clip_id), content, clip, vec![], None, ScrollSensitivity::Script);
+        self.builder.push_clip_id(clip_id);
+        self.scroll_offsets.push((id, offset));
+    }
+    fn pop_scroll_frame(&mut self) {
+        self.builder.pop_clip_id();
+    }
+}
@@ -1,9 +1,14 @@
 #[macro_use]
 pub mod id;
+pub mod svg;
 
-use std::sync::{Mutex, MutexGuard};
-use std::collections::HashMap;
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+use std::fs::File;
+use std::io::Read;
 use std::default::Default;
+use std::mem;
 
 use webrender::api::*;
 use image;
@@ -11,8 +16,12 @@ use rusttype;
 use app_units;
 
 use text_layout;
+use geometry::Size;
+use render::FillRule;
+use color::Color;
 
 use self::id::{Id, IdGen};
+use self::svg::{Svg, SvgLayer};
 
 pub type Font = rusttype::Font<'static>;
 
@@ -20,8 +29,10 @@ lazy_static! {
     static ref RES: Mutex<Resources> = Mutex::new(Resources::new());
 }
 
-pub fn init_resources(render_api: RenderApi) {
-    RES.try_lock().unwrap().render = Some(render_api);
+pub fn init_resources(render_api: RenderApi, hidpi_factor: f32) {
+    let mut res = RES.try_lock().unwrap();
+    res.render = Some(render_api);
+    res.hidpi_factor = hidpi_factor;
 }
 // Allow global access to Resources
 pub fn resources() -> MutexGuard<'static, Resources> {
@@ -40,6 +51,16 @@ pub struct FontInfo {
 pub struct ImageInfo {
     pub key: ImageKey,
     pub info: ImageDescriptor,
+    /// Number of image pixels per density independent pixel,
+    /// greater than 1 for hidpi variants of images, such as `icon@2x.png`
+    pub scale: f32,
+}
+
+impl ImageInfo {
+    /// The size of the image in density independent pixels
+    pub fn size(&self) -> Size {
+        Size::new(self.info.width as f32 / self.scale, self.info.height as f32 / self.scale)
+    }
 }
 
 /// Map for a given `Id` and resource type.
@@ -80,8 +101,39 @@ pub struct Resources {
     pub fonts: HashMap<String, FontInfo>,
     pub font_instances: HashMap<(String, app_units::Au), FontInstanceKey>,
     pub images: HashMap<String, ImageInfo>,
+    /// Image data is kept for renderers that draw images without WebRender
+    image_data: HashMap<String, ImageData>,
     pub texture_descriptors: HashMap<u64, ImageDescriptor>,
+    pub svgs: HashMap<String, Svg>,
+    /// SVG documents that couldn't be loaded, so the error is only logged once
+    failed_svgs: HashSet<String>,
+    /// Tessellated SVG documents, for each size in device pixels they're drawn at
+    svg_layers: HashMap<(String, u32, u32), Arc<Vec<SvgLayer>>>,
     pub widget_id: IdGen<WidgetId>,
+    pub hidpi_factor: f32,
+    /// Images loaded from files, that are reloaded when the hidpi factor changes
+    image_files: HashSet<String>,
+    /// Font instances rasterized for the previous hidpi factor, deleted once they are no longer drawn
+    stale_font_instances: Vec<FontInstanceKey>,
+    /// Polygons rasterized to images, with whether they're drawn in the display list being built
+    polygon_images: HashMap<PolygonImageKey, (ImageKey, bool)>,
+    /// Used to create resource keys when there's no WebRender instance
+    next_key: u32,
+}
+
+/// Identifies polygons rasterized to an image: the positions of their points relative to their bounds,
+/// rounded to a fraction of a pixel, the scale and sub-pixel offset they're rasterized at,
+/// the size of the image, and how the polygons are filled
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub(crate) struct PolygonImageKey {
+    pub contours: Vec<Vec<(i32, i32)>>,
+    /// The bits of the scale from the polygons' coordinates to pixels of the image
+    pub scale: u32,
+    pub offset: (u8, u8),
+    pub width: u32,
+    pub height: u32,
+    pub fill_rule: FillRule,
+    pub color: Color,
 }
 
 impl Default for Resources {
@@ -91,8 +143,17 @@ impl Default for Resources {
             fonts: HashMap::new(),
             font_instances: HashMap::new(),
             images: HashMap::new(),
+            image_data: HashMap::new(),
             texture_descriptors: HashMap::new(),
+            svgs: HashMap::new(),
+            failed_svgs: HashSet::new(),
+            svg_layers: HashMap::new(),
             widget_id: IdGen::new(),
+            hidpi_factor: 1.0,
+            image_files: HashSet::new(),
+            stale_font_instances: Vec::new(),
+            polygon_images: HashMap::new(),
+            next_key: 0,
         }
     }
 }
@@ -107,85 +168,328 @@ impl Resources {
         self.widget_id.next_id()
     }
 
+    // Without WebRender, resources can still be loaded for the CPU renderer
+    fn update_resources(&self, resources: ResourceUpdates) {
+        if let Some(ref render) = self.render {
+            render.update_resources(resources);
+        }
+    }
+    fn next_key(&mut self) -> u32 {
+        self.next_key += 1;
+        self.next_key
+    }
+    fn generate_image_key(&mut self) -> ImageKey {
+        if let Some(ref render) = self.render {
+            return render.generate_image_key();
+        }
+        ImageKey::new(IdNamespace(0), self.next_key())
+    }
+    fn generate_font_key(&mut self) -> FontKey {
+        if let Some(ref render) = self.render {
+            return render.generate_font_key();
+        }
+        FontKey::new(IdNamespace(0), self.next_key())
+    }
+    fn generate_font_instance_key(&mut self) -> FontInstanceKey {
+        if let Some(ref render) = self.render {
+            return render.generate_font_instance_key();
+        }
+        FontInstanceKey::new(IdNamespace(0), self.next_key())
+    }
+
+    /// Get an image from the assets/images directory, loading it if needed.
+    /// If there are variants of the image for higher hidpi factors, named like `icon@2x.png`,
+    /// the variant closest to the window's hidpi factor is used.
     pub fn get_image(&mut self, name: &str) -> &ImageInfo {
-        if self.images.contains_key(name) {
-            &self.images[name]
-        } else {
-            let (data, descriptor) = load_image(name).unwrap();
-            self.put_image(name, data, descriptor)
+        if !self.images.contains_key(name) {
+            let (data, descriptor, scale) = load_image(name, self.hidpi_factor).unwrap();
+            self.put_image_with_scale(name, data, descriptor, scale);
+            self.image_files.insert(name.to_owned());
         }
+        &self.images[name]
     }
 
     pub fn put_image(&mut self, name: &str, data: ImageData, descriptor: ImageDescriptor) -> &ImageInfo {
-        let key = self.render.as_ref().unwrap().generate_image_key();
+        self.put_image_with_scale(name, data, descriptor, 1.0)
+    }
+
+    /// Add an image with `scale` image pixels per density independent pixel
+    pub fn put_image_with_scale(&mut self, name: &str, data: ImageData, descriptor: ImageDescriptor, scale: f32) -> &ImageInfo {
+        let key = self.generate_image_key();
         let mut resources = ResourceUpdates::new();
-        resources.add_image(key, descriptor, data, None);
-        self.render.as_ref().unwrap().update_resources(resources);
-        let image_info = ImageInfo { key: key, info: descriptor };
+        resources.add_image(key, descriptor, data.clone(), None);
+        self.update_resources(resources);
+        self.image_data.insert(name.to_owned(), data);
+        let image_info = ImageInfo { key: key, info: descriptor, scale: scale };
         self.images.insert(name.to_owned(), image_info);
         &self.images[name]
     }
 
+    /// Add an image decoded from the bytes of an image file, in any format supported by the `image` crate
+    pub fn put_image_bytes(&mut self, name: &str, bytes: &[u8]) -> Result<&ImageInfo, image::ImageError> {
+        let (data, descriptor) = image_data(image::load_from_memory(bytes)?);
+        Ok(self.put_image(name, data, descriptor))
+    }
+
+    /// Add an image loaded from a file at any path, unlike `get_image`, hidpi variants aren't used
+    pub fn put_image_file<P: AsRef<Path>>(&mut self, name: &str, path: P) -> Result<&ImageInfo, image::ImageError> {
+        let mut bytes = Vec::new();
+        File::open(path)?.read_to_end(&mut bytes)?;
+        self.put_image_bytes(name, &bytes)
+    }
+
+    /// Add an image from straight, not premultiplied, RGBA pixels, row by row from the top left.
+    /// Fails if the number of pixels doesn't match the size.
+    pub fn put_image_rgba(&mut self, name: &str, width: u32, height: u32, pixels: &[u8])
+        -> Result<&ImageInfo, image::ImageError>
+    {
+        let (data, descriptor) = rgba_image_data(width, height, pixels)?;
+        Ok(self.put_image(name, data, descriptor))
+    }
+
+    /// Replace the pixels of an image with straight RGBA pixels, the size of the image can change.
+    /// Fails if the number of pixels doesn't match the size, leaving the image unchanged.
+    pub fn update_image_rgba(&mut self, name: &str, width: u32, height: u32, pixels: &[u8])
+        -> Result<&ImageInfo, image::ImageError>
+    {
+        let (data, descriptor) = rgba_image_data(width, height, pixels)?;
+        Ok(self.update_image(name, data, descriptor))
+    }
+
+    /// Replace the pixels of an image, the image is added if there's no image with the name
     pub fn update_image(&mut self, name: &str, data: ImageData, descriptor: ImageDescriptor) -> &ImageInfo {
-        let mut image_info = self.images.remove(name).expect("update image");
+        let mut image_info = match self.images.remove(name) {
+            Some(image_info) => image_info,
+            None => return self.put_image(name, data, descriptor),
+        };
         let mut resources = ResourceUpdates::new();
-        resources.update_image(image_info.key, descriptor, data, None);
-        self.render.as_ref().unwrap().update_resources(resources);
+        resources.update_image(image_info.key, descriptor, data.clone(), None);
+        self.update_resources(resources);
+        self.image_data.insert(name.to_owned(), data);
         image_info.info = descriptor;
         self.images.insert(name.to_owned(), image_info);
         &self.images[name]
     }
 
+    /// Remove an image, deleting it from WebRender. It must no longer be drawn.
+    pub fn delete_image(&mut self, name: &str) {
+        if let Some(image_info) = self.images.remove(name) {
+            let mut resources = ResourceUpdates::new();
+            resources.delete_image(image_info.key);
+            self.update_resources(resources);
+        }
+        self.image_data.remove(name);
+        self.image_files.remove(name);
+    }
+
+    /// Get the data of an image, if it's been loaded
+    pub fn image_data(&self, name: &str) -> Option<&ImageData> {
+        self.image_data.get(name)
+    }
+
+    /// Get an SVG document from the assets/images directory, loading it if needed.
+    /// If the document can't be loaded, the error is logged once and `None` is returned.
+    pub fn get_svg(&mut self, name: &str) -> Option<&Svg> {
+        if !self.svgs.contains_key(name) && !self.failed_svgs.contains(name) {
+            match Svg::load(name) {
+                Ok(svg) => {
+                    self.svgs.insert(name.to_owned(), svg);
+                }
+                Err(err) => {
+                    error!("failed to load SVG {}: {}", name, err);
+                    self.failed_svgs.insert(name.to_owned());
+                }
+            }
+        }
+        self.svgs.get(name)
+    }
+
+    /// Add an SVG document, replacing any document with the same name
+    pub fn put_svg(&mut self, name: &str, svg: Svg) -> &Svg {
+        self.failed_svgs.remove(name);
+        self.svg_layers.retain(|key, _| key.0 != name);
+        self.svgs.insert(name.to_owned(), svg);
+        &self.svgs[name]
+    }
+
+    /// Get an SVG document tessellated to a size in density independent pixels, with its top left at the origin.
+    /// Tessellations are cached for each size in device pixels, so curves stay smooth at any hidpi factor.
+    #[cfg_attr(feature = "cargo-clippy", allow(map_entry))]
+    pub fn get_svg_layers(&mut self, name: &str, size: Size) -> Arc<Vec<SvgLayer>> {
+        let hidpi_factor = self.hidpi_factor;
+        let key = (name.to_owned(), (size.width * hidpi_factor).round() as u32, (size.height * hidpi_factor).round() as u32);
+        if !self.svg_layers.contains_key(&key) {
+            let size = Size::new(key.1 as f32 / hidpi_factor, key.2 as f32 / hidpi_factor);
+            let layers = match self.get_svg(name) {
+                Some(svg) => svg.tessellate(size),
+                None => Vec::new(),
+            };
+            self.svg_layers.insert(key.clone(), Arc::new(layers));
+        }
+        self.svg_layers[&key].clone()
+    }
+
     pub fn get_font(&mut self, name: &str) -> &FontInfo {
         if !self.fonts.contains_key(name) {
             let data = load_font_data(name).unwrap();
-            let key = self.render.as_ref().unwrap().generate_font_key();
+            let key = self.generate_font_key();
             let mut resources = ResourceUpdates::new();
             resources.add_raw_font(key, data, 0);
 
             let font = load_font(name).unwrap();
-            self.render.as_ref().unwrap().update_resources(resources);
+            self.update_resources(resources);
             let font_info = FontInfo { key: key, info: font };
             self.fonts.insert(name.to_owned(), font_info);
         }
         &self.fonts[name]
     }
 
+    /// Update resources that depend on the hidpi factor: font instances are rasterized again,
+    /// and images loaded from files are reloaded if a better variant exists for the new factor
+    pub fn set_hidpi_factor(&mut self, hidpi_factor: f32) {
+        if hidpi_factor == self.hidpi_factor {
+            return;
+        }
+        self.hidpi_factor = hidpi_factor;
+        // the current display list still uses the old font instances
+        let font_instances: Vec<FontInstanceKey> = self.font_instances.drain().map(|(_, key)| key).collect();
+        self.stale_font_instances.extend(font_instances);
+
+        let mut resources = ResourceUpdates::new();
+        for name in &self.image_files {
+            let image_info = self.images.get_mut(name).unwrap();
+            if variant_scale(name, hidpi_factor).1 == image_info.scale {
+                continue;
+            }
+            match load_image(name, hidpi_factor) {
+                Ok((data, descriptor, scale)) => {
+                    resources.update_image(image_info.key, descriptor, data.clone(), None);
+                    self.image_data.insert(name.clone(), data);
+                    image_info.info = descriptor;
+                    image_info.scale = scale;
+                }
+                Err(err) => warn!("failed to reload image {}: {}", name, err),
+            }
+        }
+        self.update_resources(resources);
+    }
+
+    /// Get the image of polygons, for renderers that can't fill polygons themselves. The image is created
+    /// from the straight RGBA pixels returned by `rasterize`, unless it was drawn in the previous display list.
+    pub(crate) fn get_polygon_image<F>(&mut self, key: PolygonImageKey, rasterize: F) -> ImageKey
+        where F: FnOnce() -> Vec<u8>
+    {
+        if let Some(&mut (image_key, ref mut drawn)) = self.polygon_images.get_mut(&key) {
+            *drawn = true;
+            return image_key;
+        }
+        let (data, descriptor) = bgra_image_data(key.width, key.height, &rasterize());
+        let image_key = self.generate_image_key();
+        let mut resources = ResourceUpdates::new();
+        resources.add_image(image_key, descriptor, data, None);
+        self.update_resources(resources);
+        self.polygon_images.insert(key, (image_key, true));
+        image_key
+    }
+
+    /// Delete resources replaced after a hidpi factor change, and polygon images that weren't drawn,
+    /// to be called with the resource updates for a display list that no longer uses them
+    pub(crate) fn delete_stale_resources(&mut self, resources: &mut ResourceUpdates) {
+        for key in self.stale_font_instances.drain(..) {
+            resources.delete_font_instance(key);
+        }
+        self.polygon_images.retain(|_, &mut (image_key, ref mut drawn)| {
+            if !*drawn {
+                resources.delete_image(image_key);
+            }
+            mem::replace(drawn, false)
+        });
+    }
+
     #[cfg_attr(feature = "cargo-clippy", allow(map_entry))]
     pub fn get_font_instance(&mut self, name: &str, font_size: f32) -> &FontInstanceKey {
         let font_key = self.get_font(name).key;
         let size = app_units::Au::from_f32_px(text_layout::px_to_pt(font_size));
         if !self.font_instances.contains_key(&(name.to_owned(), size)) {
-            let instance_key = self.render.as_ref().unwrap().generate_font_instance_key();
+            let instance_key = self.generate_font_instance_key();
             let mut resources = ResourceUpdates::new();
             resources.add_font_instance(instance_key, font_key, size, None, None, Vec::new());
-            self.render.as_ref().unwrap().update_resources(resources);
+            self.update_resources(resources);
             self.font_instances.insert((name.to_owned(), size), instance_key);
         }
         &self.font_instances[&(name.to_owned(), size)]
     }
 }
-fn load_image(file: &str) -> Result<(ImageData, ImageDescriptor), image::ImageError> {
-    use image::GenericImage;
+/// Find the best variant of an image file for a hidpi factor, returning the file name and its scale.
+/// Variants are named with the scale after the file stem, like `icon@2x.png`,
+/// the smallest variant with a scale at least the hidpi factor is used, or the largest if there are none.
+fn variant_scale(file: &str, hidpi_factor: f32) -> (String, f32) {
+    let path = Path::new(file);
+    let mut best = (file.to_owned(), 1.0);
+    if hidpi_factor <= 1.0 {
+        return best;
+    }
+    if let (Some(stem), Some(ext)) = (path.file_stem(), path.extension()) {
+        for scale in 2..5 {
+            let variant = path.with_file_name(format!("{}@{}x.{}", stem.to_string_lossy(), scale, ext.to_string_lossy()));
+            if Path::new("assets/images").join(&variant).exists() {
+                best = (variant.to_string_lossy().into_owned(), scale as f32);
+                if scale as f32 >= hidpi_factor {
+                    break;
+                }
+            }
+        }
+    }
+    best
+}
+
+fn load_image(file: &str, hidpi_factor: f32) -> Result<(ImageData, ImageDescriptor, f32), image::ImageError> {
+    let (file, scale) = variant_scale(file, hidpi_factor);
     let image = try!(image::open(format!("assets/images/{}", file)));
-    let image_dims = image.dimensions();
-    let format = match image {
-        image::ImageLuma8(_) => ImageFormat::A8,
-        image::ImageRgb8(_) => ImageFormat::RGB8,
-        image::ImageRgba8(_) => ImageFormat::BGRA8,
-        image::ImageLumaA8(_) => {
-            return Err(image::ImageError::UnsupportedError("ImageLumaA8 unsupported".to_string()));
-        }
-    };
-    let mut bytes = image.raw_pixels();
-    if format == ImageFormat::BGRA8 {
-        premultiply(bytes.as_mut_slice());
-    }
-    let opaque = is_image_opaque(format, &bytes[..]);
-    let descriptor = ImageDescriptor::new(image_dims.0, image_dims.1, format, opaque);
-    let data = ImageData::new(bytes);
-    Ok((data, descriptor))
+    let (data, descriptor) = image_data(image);
+    Ok((data, descriptor, scale))
+}
+
+/// Convert an image of any color type to image data. Images with alpha are stored premultiplied,
+/// in BGRA order, and grayscale images are converted to RGB.
+pub fn image_data(image: image::DynamicImage) -> (ImageData, ImageDescriptor) {
+    match image {
+        image::ImageRgb8(image) => {
+            let (width, height) = image.dimensions();
+            let descriptor = ImageDescriptor::new(width, height, ImageFormat::RGB8, true);
+            (ImageData::new(image.into_raw()), descriptor)
+        }
+        image::ImageLuma8(_) => image_data(image::ImageRgb8(image.to_rgb())),
+        image::ImageRgba8(_) | image::ImageLumaA8(_) => {
+            let image = image.to_rgba();
+            let (width, height) = image.dimensions();
+            bgra_image_data(width, height, &image.into_raw())
+        }
+    }
+}
+
+/// Convert straight, not premultiplied, RGBA pixels to image data.
+/// Fails with `ImageError::DimensionError` if the number of pixels doesn't match the size.
+pub fn rgba_image_data(width: u32, height: u32, pixels: &[u8]) -> Result<(ImageData, ImageDescriptor), image::ImageError> {
+    let len = (width as usize).checked_mul(height as usize).and_then(|len| len.checked_mul(4));
+    if len != Some(pixels.len()) {
+        return Err(image::ImageError::DimensionError);
+    }
+    Ok(bgra_image_data(width, height, pixels))
 }
+
+/// Convert RGBA pixels, that are known to match the size, to premultiplied BGRA image data
+fn bgra_image_data(width: u32, height: u32, pixels: &[u8]) -> (ImageData, ImageDescriptor) {
+    let mut bytes = Vec::with_capacity(pixels.len());
+    for pixel in pixels.chunks(4) {
+        bytes.extend_from_slice(&[pixel[2], pixel[1], pixel[0], pixel[3]]);
+    }
+    premultiply(bytes.as_mut_slice());
+    let opaque = is_image_opaque(ImageFormat::BGRA8, &bytes[..]);
+    let descriptor = ImageDescriptor::new(width, height, ImageFormat::BGRA8, opaque);
+    (ImageData::new(bytes), descriptor)
+}
+
 fn is_image_opaque(format: ImageFormat, bytes: &[u8]) -> bool {
     match format {
         ImageFormat::BGRA8 => {
@@ -221,8 +525,6 @@ pub fn premultiply(data: &mut [u8]) {
 }
 
 fn load_font_data(name: &str) -> Result<Vec<u8>, ::std::io::Error> {
-    use std::fs::File;
-    use std::io::Read;
     let mut file = File::open(format!("assets/fonts/{}.ttf", name)).expect("Font missing");
     let mut data = Vec::new();
     try!(file.read_to_end(&mut data));
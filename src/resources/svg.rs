@@ -0,0 +1,579 @@
+//! Loading SVG documents, used for icons that stay sharp at any size.
+//!
+//! Only the subset of SVG used by typical icons is supported: paths and basic shapes, in groups,
+//! with fill and stroke presentation attributes or inline styles. Transforms, text, gradients,
+//! and style sheets are ignored.
+
+use std::fmt;
+use std::io::{self, Read};
+use std::fs::File;
+use std::f32::consts::PI;
+use std::collections::HashMap;
+
+use regex::Regex;
+use webrender::api::ColorF;
+
+use draw::path::{Path, PathState, StrokeStyle, LineJoin, LineCap};
+use render::FillRule;
+use geometry::{Rect, Point, Size};
+use color::*;
+
+/// Elements whose content isn't drawn directly
+const NON_RENDERED_ELEMENTS: &'static [&'static str] = &[
+    "defs", "clipPath", "mask", "pattern", "symbol", "marker", "linearGradient", "radialGradient",
+    "title", "desc", "metadata", "style", "text",
+];
+
+lazy_static! {
+    static ref ATTRIBUTE: Regex = Regex::new(r#"([\w:-]+)\s*=\s*(?:"([^"]*)"|'([^']*)')"#).unwrap();
+}
+
+#[derive(Debug)]
+pub enum SvgError {
+    Io(io::Error),
+    Parse(String),
+}
+
+impl fmt::Display for SvgError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            SvgError::Io(ref err) => write!(f, "{}", err),
+            SvgError::Parse(ref message) => write!(f, "invalid SVG: {}", message),
+        }
+    }
+}
+
+impl From<io::Error> for SvgError {
+    fn from(err: io::Error) -> Self {
+        SvgError::Io(err)
+    }
+}
+
+/// A parsed SVG document
+#[derive(Debug, Clone)]
+pub struct Svg {
+    /// The region of the document's coordinates that's drawn
+    pub view_box: Rect,
+    /// The size of the document in density independent pixels, from its width and height, or its view box
+    pub size: Size,
+    /// The shapes in the document, in drawing order, each with the document's view box
+    pub shapes: Vec<PathState>,
+}
+
+/// Polygons filled with one color, part of a tessellated SVG document
+#[derive(Debug, Clone)]
+pub struct SvgLayer {
+    pub polygons: Vec<Vec<Point>>,
+    pub fill_rule: FillRule,
+    pub color: Color,
+}
+
+impl Svg {
+    pub fn parse(source: &str) -> Result<Svg, SvgError> {
+        let mut svg: Option<Svg> = None;
+        // the elements that are open, with the presentation attributes they pass to their children
+        let mut open_elements: Vec<(String, Presentation)> = Vec::new();
+        let mut rest = source;
+        while let Some(start) = rest.find('<') {
+            rest = &rest[start..];
+            let (tag_end, skip) = if rest.starts_with("<!--") {
+                (rest.find("-->").map(|end| end + 2), true)
+            } else {
+                (rest.find('>'), rest.starts_with("<?") || rest.starts_with("<!"))
+            };
+            let tag_end = match tag_end {
+                Some(tag_end) => tag_end,
+                None => return Err(SvgError::Parse("unterminated tag".to_owned())),
+            };
+            let tag = &rest[1..tag_end];
+            rest = &rest[tag_end + 1..];
+            if skip {
+                continue;
+            }
+            if tag.starts_with('/') {
+                open_elements.pop();
+                continue;
+            }
+            let self_closing = tag.ends_with('/');
+            let tag = tag.trim_right_matches('/');
+            let name = tag.split_whitespace().next().unwrap_or("");
+            let attributes: HashMap<&str, &str> = ATTRIBUTE.captures_iter(tag).map(|captures| {
+                let value = captures.get(2).or_else(|| captures.get(3)).map_or("", |value| value.as_str());
+                (captures.get(1).unwrap().as_str(), value)
+            }).collect();
+
+            let hidden = open_elements.iter().any(|&(ref name, _)| NON_RENDERED_ELEMENTS.contains(&name.as_str()));
+            let mut presentation = match open_elements.last() {
+                Some(&(_, ref presentation)) => presentation.clone(),
+                None => Presentation::default(),
+            };
+            presentation.apply(&attributes);
+            if attributes.contains_key("transform") {
+                warn!("SVG transforms aren't supported, ignoring transform of {}", name);
+            }
+            if name == "svg" && svg.is_none() {
+                svg = Some(parse_root(&attributes)?);
+            } else if !hidden {
+                if let Some(ref mut svg) = svg {
+                    if let Some(path) = parse_shape(name, &attributes)? {
+                        let shape = presentation.shape(path, svg.view_box);
+                        svg.shapes.push(shape);
+                    }
+                }
+            }
+            if !self_closing {
+                open_elements.push((name.to_owned(), presentation));
+            }
+        }
+        match svg {
+            Some(svg) => Ok(svg),
+            None => Err(SvgError::Parse("no svg element".to_owned())),
+        }
+    }
+
+    /// Load an SVG document from the assets/images directory
+    pub fn load(file: &str) -> Result<Svg, SvgError> {
+        let mut source = String::new();
+        File::open(format!("assets/images/{}", file))?.read_to_string(&mut source)?;
+        Svg::parse(&source)
+    }
+
+    /// Tessellate the shapes of the document, scaled to `size`, with its top left at the origin
+    pub fn tessellate(&self, size: Size) -> Vec<SvgLayer> {
+        let bounds = Rect::new(Point::zero(), size);
+        let mut layers = Vec::new();
+        for shape in &self.shapes {
+            let (fill, stroke) = shape.tessellate(bounds);
+            if let Some(color) = shape.fill_color {
+                layers.push(SvgLayer { polygons: fill, fill_rule: shape.fill_rule, color: color });
+            }
+            if let Some(color) = shape.stroke_color {
+                layers.push(SvgLayer { polygons: stroke, fill_rule: FillRule::NonZero, color: color });
+            }
+        }
+        layers
+    }
+}
+
+fn parse_root(attributes: &HashMap<&str, &str>) -> Result<Svg, SvgError> {
+    let width = attributes.get("width").and_then(|width| parse_length(width));
+    let height = attributes.get("height").and_then(|height| parse_length(height));
+    let view_box = match attributes.get("viewBox") {
+        Some(view_box) => {
+            let values = parse_numbers(view_box)?;
+            if values.len() != 4 {
+                return Err(SvgError::Parse(format!("invalid viewBox \"{}\"", view_box)));
+            }
+            Rect::new(Point::new(values[0], values[1]), Size::new(values[2], values[3]))
+        }
+        None => match (width, height) {
+            (Some(width), Some(height)) => Rect::new(Point::zero(), Size::new(width, height)),
+            _ => return Err(SvgError::Parse("the svg element has no viewBox or size".to_owned())),
+        },
+    };
+    Ok(Svg {
+        view_box: view_box,
+        size: Size::new(width.unwrap_or(view_box.size.width), height.unwrap_or(view_box.size.height)),
+        shapes: Vec::new(),
+    })
+}
+
+/// The path of a shape element, `None` if the element isn't a shape
+fn parse_shape(name: &str, attributes: &HashMap<&str, &str>) -> Result<Option<Path>, SvgError> {
+    let number = |name: &str| attributes.get(name).and_then(|value| parse_length(value)).unwrap_or(0.0);
+    let path = match name {
+        "path" => parse_path_data(attributes.get("d").cloned().unwrap_or(""))?,
+        "rect" => {
+            let rect = Rect::new(Point::new(number("x"), number("y")), Size::new(number("width"), number("height")));
+            let rx = attributes.get("rx").and_then(|value| parse_length(value));
+            let ry = attributes.get("ry").and_then(|value| parse_length(value));
+            let radii = Size::new(
+                rx.or(ry).unwrap_or(0.0).min(rect.size.width / 2.0),
+                ry.or(rx).unwrap_or(0.0).min(rect.size.height / 2.0));
+            rect_path(rect, radii)
+        }
+        "circle" => {
+            let radius = number("r");
+            ellipse_path(Point::new(number("cx"), number("cy")), Size::new(radius, radius))
+        }
+        "ellipse" => ellipse_path(Point::new(number("cx"), number("cy")), Size::new(number("rx"), number("ry"))),
+        "line" => Path::new()
+            .move_to(Point::new(number("x1"), number("y1")))
+            .line_to(Point::new(number("x2"), number("y2"))),
+        "polyline" | "polygon" => {
+            let values = parse_numbers(attributes.get("points").cloned().unwrap_or(""))?;
+            let mut path = Path::new();
+            for (i, point) in values.chunks(2).filter(|point| point.len() == 2).enumerate() {
+                let point = Point::new(point[0], point[1]);
+                path = if i == 0 { path.move_to(point) } else { path.line_to(point) };
+            }
+            if name == "polygon" { path.close() } else { path }
+        }
+        _ => return Ok(None),
+    };
+    Ok(Some(path))
+}
+
+fn rect_path(rect: Rect, radii: Size) -> Path {
+    let (left, top, right, bottom) = (rect.origin.x, rect.origin.y, rect.origin.x + rect.size.width, rect.origin.y + rect.size.height);
+    if radii.width <= 0.0 || radii.height <= 0.0 {
+        return Path::new()
+            .move_to(Point::new(left, top))
+            .line_to(Point::new(right, top))
+            .line_to(Point::new(right, bottom))
+            .line_to(Point::new(left, bottom))
+            .close();
+    }
+    let (rx, ry) = (radii.width, radii.height);
+    Path::new()
+        .move_to(Point::new(left + rx, top))
+        .line_to(Point::new(right - rx, top))
+        .arc_to(radii, 0.0, false, true, Point::new(right, top + ry))
+        .line_to(Point::new(right, bottom - ry))
+        .arc_to(radii, 0.0, false, true, Point::new(right - rx, bottom))
+        .line_to(Point::new(left + rx, bottom))
+        .arc_to(radii, 0.0, false, true, Point::new(left, bottom - ry))
+        .line_to(Point::new(left, top + ry))
+        .arc_to(radii, 0.0, false, true, Point::new(left + rx, top))
+        .close()
+}
+
+fn ellipse_path(center: Point, radius: Size) -> Path {
+    Path::new().arc(center, radius, 0.0, 2.0 * PI).close()
+}
+
+/// Presentation attributes, inherited by the children of an element
+#[derive(Debug, Clone)]
+struct Presentation {
+    fill: Option<Color>,
+    fill_rule: FillRule,
+    fill_opacity: f32,
+    stroke: Option<Color>,
+    stroke_opacity: f32,
+    stroke_style: StrokeStyle,
+    opacity: f32,
+}
+
+impl Default for Presentation {
+    fn default() -> Self {
+        Presentation {
+            fill: Some(BLACK),
+            fill_rule: FillRule::NonZero,
+            fill_opacity: 1.0,
+            stroke: None,
+            stroke_opacity: 1.0,
+            stroke_style: StrokeStyle::default(),
+            opacity: 1.0,
+        }
+    }
+}
+
+impl Presentation {
+    /// Apply the presentation attributes and inline style of an element
+    fn apply(&mut self, attributes: &HashMap<&str, &str>) {
+        for (&name, &value) in attributes {
+            if name != "style" {
+                self.set(name, value);
+            }
+        }
+        // the inline style takes precedence over attributes
+        if let Some(style) = attributes.get("style") {
+            for declaration in style.split(';') {
+                let mut parts = declaration.splitn(2, ':');
+                if let (Some(name), Some(value)) = (parts.next(), parts.next()) {
+                    self.set(name.trim(), value.trim());
+                }
+            }
+        }
+    }
+    fn set(&mut self, name: &str, value: &str) {
+        match name {
+            "fill" => if let Some(color) = parse_paint(value) { self.fill = color },
+            "stroke" => if let Some(color) = parse_paint(value) { self.stroke = color },
+            "fill-rule" => self.fill_rule = if value == "evenodd" { FillRule::EvenOdd } else { FillRule::NonZero },
+            "fill-opacity" => self.fill_opacity = parse_opacity(value),
+            "stroke-opacity" => self.stroke_opacity = parse_opacity(value),
+            // group opacity is approximated by applying it to each shape in the group
+            "opacity" => self.opacity *= parse_opacity(value),
+            "stroke-width" => if let Some(width) = parse_length(value) { self.stroke_style.width = width },
+            "stroke-linejoin" => self.stroke_style.join = match value {
+                "round" => LineJoin::Round,
+                "bevel" => LineJoin::Bevel,
+                _ => LineJoin::Miter,
+            },
+            "stroke-linecap" => self.stroke_style.cap = match value {
+                "round" => LineCap::Round,
+                "square" => LineCap::Square,
+                _ => LineCap::Butt,
+            },
+            "stroke-dasharray" => self.stroke_style.dashes = parse_numbers(value).unwrap_or_else(|_| Vec::new()),
+            "stroke-dashoffset" => if let Some(offset) = parse_length(value) { self.stroke_style.dash_offset = offset },
+            _ => (),
+        }
+    }
+    fn shape(&self, path: Path, view_box: Rect) -> PathState {
+        let mut shape = PathState::new(path);
+        shape.view_box = Some(view_box);
+        shape.fill_color = self.fill.map(|color| color.scale_alpha(self.fill_opacity * self.opacity));
+        shape.fill_rule = self.fill_rule;
+        shape.stroke_color = self.stroke.map(|color| color.scale_alpha(self.stroke_opacity * self.opacity));
+        shape.stroke = self.stroke_style.clone();
+        shape
+    }
+}
+
+/// Parse a fill or stroke, `Some(None)` for `none`, or `None` if the paint isn't supported
+fn parse_paint(value: &str) -> Option<Option<Color>> {
+    let rgb = |r: f32, g: f32, b: f32| Some(Some(Color::from(ColorF::new(r, g, b, 1.0))));
+    match value {
+        "none" | "transparent" => return Some(None),
+        // there's no current color, icons are tinted instead
+        "currentColor" | "black" => return Some(Some(BLACK)),
+        "white" => return Some(Some(WHITE)),
+        "red" => return Some(Some(RED)),
+        "green" => return rgb(0.0, 128.0 / 255.0, 0.0),
+        "blue" => return Some(Some(BLUE)),
+        "gray" | "grey" => return Some(Some(GRAY_50)),
+        _ => (),
+    }
+    if value.starts_with('#') {
+        let digits = &value[1..];
+        if let Ok(value) = u32::from_str_radix(digits, 16) {
+            match digits.len() {
+                3 => {
+                    let channel = |offset: u32| (value >> offset & 0xF) as f32 / 15.0;
+                    return rgb(channel(8), channel(4), channel(0));
+                }
+                6 => {
+                    let channel = |offset: u32| (value >> offset & 0xFF) as f32 / 255.0;
+                    return rgb(channel(16), channel(8), channel(0));
+                }
+                _ => (),
+            }
+        }
+    }
+    if value.starts_with("rgb(") && value.ends_with(')') {
+        let channels: Vec<f32> = value[4..value.len() - 1].split(',').filter_map(|channel| {
+            let channel = channel.trim();
+            if channel.ends_with('%') {
+                channel.trim_right_matches('%').parse::<f32>().ok().map(|percent| percent / 100.0)
+            } else {
+                channel.parse::<f32>().ok().map(|value| value / 255.0)
+            }
+        }).collect();
+        if channels.len() == 3 {
+            return rgb(channels[0], channels[1], channels[2]);
+        }
+    }
+    warn!("unsupported SVG paint {}", value);
+    None
+}
+
+fn parse_opacity(value: &str) -> f32 {
+    value.parse::<f32>().unwrap_or(1.0).max(0.0).min(1.0)
+}
+
+/// Parse a length in user units, lengths with units other than pixels aren't supported
+fn parse_length(value: &str) -> Option<f32> {
+    value.trim().trim_right_matches("px").parse::<f32>().ok()
+}
+
+/// Parse a list of numbers separated by whitespace or commas
+fn parse_numbers(value: &str) -> Result<Vec<f32>, SvgError> {
+    let mut tokens = PathTokens::new(value);
+    let mut numbers = Vec::new();
+    while tokens.has_number() {
+        numbers.push(tokens.number()?);
+    }
+    if tokens.at_end() {
+        Ok(numbers)
+    } else {
+        Err(SvgError::Parse(format!("invalid list of numbers \"{}\"", value)))
+    }
+}
+
+fn is_digit(byte: u8) -> bool {
+    byte >= b'0' && byte <= b'9'
+}
+
+/// Splits path data into commands, numbers and flags
+struct PathTokens<'a> {
+    data: &'a [u8],
+    position: usize,
+}
+
+impl<'a> PathTokens<'a> {
+    fn new(data: &'a str) -> Self {
+        PathTokens { data: data.as_bytes(), position: 0 }
+    }
+    fn skip_separators(&mut self) {
+        while self.position < self.data.len() {
+            match self.data[self.position] {
+                b' ' | b'\t' | b'\r' | b'\n' | b',' => self.position += 1,
+                _ => break,
+            }
+        }
+    }
+    fn peek(&mut self) -> Option<u8> {
+        self.skip_separators();
+        self.data.get(self.position).cloned()
+    }
+    fn at_end(&mut self) -> bool {
+        self.peek().is_none()
+    }
+    fn has_number(&mut self) -> bool {
+        match self.peek() {
+            Some(byte) => is_digit(byte) || byte == b'.' || byte == b'-' || byte == b'+',
+            None => false,
+        }
+    }
+    fn command(&mut self) -> Option<char> {
+        match self.peek() {
+            Some(byte) if (byte as char).is_alphabetic() => {
+                self.position += 1;
+                Some(byte as char)
+            }
+            _ => None,
+        }
+    }
+    fn skip_digits(&mut self) {
+        while self.position < self.data.len() && is_digit(self.data[self.position]) {
+            self.position += 1;
+        }
+    }
+    fn number(&mut self) -> Result<f32, SvgError> {
+        self.skip_separators();
+        let start = self.position;
+        if self.position < self.data.len() && (self.data[self.position] == b'-' || self.data[self.position] == b'+') {
+            self.position += 1;
+        }
+        self.skip_digits();
+        if self.position < self.data.len() && self.data[self.position] == b'.' {
+            self.position += 1;
+            self.skip_digits();
+        }
+        if self.position < self.data.len() && (self.data[self.position] == b'e' || self.data[self.position] == b'E') {
+            self.position += 1;
+            if self.position < self.data.len() && (self.data[self.position] == b'-' || self.data[self.position] == b'+') {
+                self.position += 1;
+            }
+            self.skip_digits();
+        }
+        let text = String::from_utf8_lossy(&self.data[start..self.position]);
+        text.parse::<f32>().map_err(|_| SvgError::Parse(format!("invalid number \"{}\"", text)))
+    }
+    /// Arc flags can be written without separators, like `a1 1 0 013 4`
+    fn flag(&mut self) -> Result<bool, SvgError> {
+        match self.peek() {
+            Some(b'0') => { self.position += 1; Ok(false) }
+            Some(b'1') => { self.position += 1; Ok(true) }
+            _ => Err(SvgError::Parse("invalid arc flag".to_owned())),
+        }
+    }
+    fn point(&mut self) -> Result<Point, SvgError> {
+        let x = self.number()?;
+        let y = self.number()?;
+        Ok(Point::new(x, y))
+    }
+}
+
+/// Parse the `d` attribute of a path element
+fn parse_path_data(data: &str) -> Result<Path, SvgError> {
+    let mut tokens = PathTokens::new(data);
+    let mut path = Path::new();
+    let mut current = Point::zero();
+    let mut subpath_start = Point::zero();
+    // the last control points of cubic and quadratic curves, reflected by smooth curves
+    let mut last_cubic_control: Option<Point> = None;
+    let mut last_quad_control: Option<Point> = None;
+    let mut command = match tokens.command() {
+        Some(command) => command,
+        None if tokens.at_end() => return Ok(path),
+        None => return Err(SvgError::Parse(format!("invalid path data \"{}\"", data))),
+    };
+    loop {
+        let relative = command.is_lowercase();
+        // relative coordinates are offset from the position at the start of the command
+        let origin = if relative { current } else { Point::zero() };
+        let offset = |point: Point| Point::new(point.x + origin.x, point.y + origin.y);
+        let (mut cubic_control, mut quad_control) = (None, None);
+        match command {
+            'M' | 'm' => {
+                current = offset(tokens.point()?);
+                subpath_start = current;
+                path = path.move_to(current);
+                // coordinates following a move are lines
+                command = if relative { 'l' } else { 'L' };
+            }
+            'L' | 'l' => {
+                current = offset(tokens.point()?);
+                path = path.line_to(current);
+            }
+            'H' | 'h' => {
+                let x = tokens.number()?;
+                current = Point::new(origin.x + x, current.y);
+                path = path.line_to(current);
+            }
+            'V' | 'v' => {
+                let y = tokens.number()?;
+                current = Point::new(current.x, origin.y + y);
+                path = path.line_to(current);
+            }
+            'C' | 'c' | 'S' | 's' => {
+                let control_1 = if command == 'C' || command == 'c' {
+                    offset(tokens.point()?)
+                } else {
+                    reflect(current, last_cubic_control)
+                };
+                let control_2 = offset(tokens.point()?);
+                let to = offset(tokens.point()?);
+                path = path.cubic_to(control_1, control_2, to);
+                cubic_control = Some(control_2);
+                current = to;
+            }
+            'Q' | 'q' | 'T' | 't' => {
+                let control = if command == 'Q' || command == 'q' {
+                    offset(tokens.point()?)
+                } else {
+                    reflect(current, last_quad_control)
+                };
+                let to = offset(tokens.point()?);
+                path = path.quad_to(control, to);
+                quad_control = Some(control);
+                current = to;
+            }
+            'A' | 'a' => {
+                let radii = Size::new(tokens.number()?, tokens.number()?);
+                let x_rotation = tokens.number()?.to_radians();
+                let large_arc = tokens.flag()?;
+                let sweep = tokens.flag()?;
+                let to = offset(tokens.point()?);
+                path = path.arc_to(radii, x_rotation, large_arc, sweep, to);
+                current = to;
+            }
+            'Z' | 'z' => {
+                path = path.close();
+                current = subpath_start;
+            }
+            _ => return Err(SvgError::Parse(format!("unknown path command {}", command))),
+        }
+        last_cubic_control = cubic_control;
+        last_quad_control = quad_control;
+        // commands are repeated while they're followed by more numbers
+        if command == 'Z' || command == 'z' || !tokens.has_number() {
+            command = match tokens.command() {
+                Some(command) => command,
+                None if tokens.at_end() => return Ok(path),
+                None => return Err(SvgError::Parse(format!("invalid path data \"{}\"", data))),
+            };
+        }
+    }
+}
+
+/// The reflection of the last control point of a curve around its end, used by smooth curves
+fn reflect(current: Point, control: Option<Point>) -> Point {
+    match control {
+        Some(control) => Point::new(2.0 * current.x - control.x, 2.0 * current.y - control.y),
+        None => current,
+    }
+}
@@ -1,24 +1,29 @@
 //! Contains `Ui`, which contains application global state and is accessible to every event handler.
 
-use std::collections::{HashSet, HashMap, VecDeque};
+use std::collections::{HashMap, VecDeque};
 use std::any::{Any, TypeId};
 use std::rc::Rc;
 use std::cell::RefCell;
+use std::time::Duration;
+use std::io;
 
 use cassowary::Constraint;
 use cassowary::strength::*;
 
 use glutin;
+use gleam::gl;
+use image::RgbaImage;
 
 use window::Window;
 use app::App;
 use widget::{WidgetRef, WidgetBuilder};
 use layout::{LimnSolver, LayoutChanged, LayoutVars, ExactFrame};
 use layout::constraint::*;
-use geometry::{Point, Rect, Size};
-use resources::WidgetId;
+use geometry::{Point, Rect, Size, Vector};
+use resources::{self, WidgetId};
 use event::{Target, EventArgs};
-use render::WebRenderContext;
+use render::{WebRenderContext, RenderBuilder, CpuRenderBuilder, SvgRenderBuilder};
+use overlay::Overlay;
 
 /// If true, the constraint that matches the root layout size to the window size
 /// is required. This can be useful for debugging but can result in panics from resizing the window.
@@ -28,6 +33,7 @@ const WINDOW_CONSTRAINT_REQUIRED: bool = false;
 /// `Ui` is accessible to every event handler, so features helper methods that can be accessed at any time.
 pub struct Ui {
     pub(crate) root: WidgetRef,
+    pub(crate) overlay: Overlay,
     widget_map: HashMap<WidgetId, WidgetRef>,
     pub(crate) solver: LimnSolver,
     pub(crate) render: WebRenderContext,
@@ -46,9 +52,12 @@ impl Ui {
         // x will crash if window size set to (0, 0)
         root.layout().add(min_size(Size::new(1.0, 1.0)));
         let render = WebRenderContext::new(&mut window, events_loop);
+        let root: WidgetRef = root.into();
+        let overlay = Overlay::new(&root);
         Ui {
             widget_map: HashMap::new(),
-            root: root.into(),
+            root: root,
+            overlay: overlay,
             solver: LimnSolver::new(),
             render: render,
             needs_redraw: true,
@@ -71,6 +80,11 @@ impl Ui {
         self.get_root().event(data);
     }
 
+    /// Send an event to the root widget once `delay` has passed
+    pub fn event_delayed<T: 'static>(&self, data: T, delay: Duration) {
+        self.get_root().event_delayed(data, delay);
+    }
+
     pub fn close(&mut self) {
         self.should_close = true;
     }
@@ -85,6 +99,12 @@ impl Ui {
     }
 
     pub(super) fn window_resized(&mut self, window_dims: Size) {
+        let clamped_dims = self.window.borrow().clamp_size(window_dims);
+        if clamped_dims != window_dims {
+            // the window manager ignored the window size limits
+            self.window.borrow_mut().resize(clamped_dims.width as u32, clamped_dims.height as u32);
+        }
+        let window_dims = clamped_dims;
         let window_size = self.window.borrow_mut().size_px();
         self.render.window_resized(window_size);
         let mut root = self.get_root();
@@ -110,6 +130,19 @@ impl Ui {
         self.needs_redraw = true;
     }
 
+    /// The hidpi factor the window is currently drawn with
+    pub fn hidpi_factor(&self) -> f32 {
+        self.render.device_pixel_ratio
+    }
+
+    /// Update the renderer and resources for a new hidpi factor, then relayout and redraw the window
+    pub(crate) fn hidpi_factor_changed(&mut self, hidpi_factor: f32) {
+        self.render.set_device_pixel_ratio(hidpi_factor);
+        resources::resources().set_hidpi_factor(hidpi_factor);
+        let window_dims = self.window.borrow().size_dp();
+        self.window_resized(window_dims);
+    }
+
     pub fn check_layout_changes(&mut self) {
 
         let changes = self.solver.fetch_changes();
@@ -134,21 +167,97 @@ impl Ui {
         }
     }
 
+    fn draw_widgets(&mut self, renderer: &mut RenderBuilder) {
+        let crop_to = Rect::new(Point::zero(), Size::new(::std::f32::MAX, ::std::f32::MAX));
+        self.root.widget_mut().draw(crop_to, renderer);
+        self.overlay.root.widget_mut().draw(crop_to, renderer);
+        if self.debug_draw_bounds {
+            self.root.widget_mut().draw_debug(renderer);
+            self.overlay.root.widget_mut().draw_debug(renderer);
+        }
+    }
+
     fn draw(&mut self) {
         let window_size = self.window.borrow_mut().size_dp();
-        let (builder, resources) = {
+        let (builder, mut resource_updates, scroll_offsets) = {
             let mut renderer = self.render.render_builder(window_size);
-            let crop_to = Rect::new(Point::zero(), Size::new(::std::f32::MAX, ::std::f32::MAX));
-            self.root.widget_mut().draw(crop_to, &mut renderer);
-            if self.debug_draw_bounds {
-                self.root.widget_mut().draw_debug(&mut renderer);
-            }
-            (renderer.builder, renderer.resources)
+            self.draw_widgets(&mut renderer);
+            (renderer.builder, renderer.resources, renderer.scroll_offsets)
         };
-        self.render.set_display_list(builder, resources, window_size);
+        resources::resources().delete_stale_resources(&mut resource_updates);
+        self.render.set_display_list(builder, resource_updates, window_size);
+        // scroll frames that are new in this display list start at the origin
+        for (id, offset) in scroll_offsets {
+            self.render.scroll_frame(id, offset);
+        }
         self.render.generate_frame();
     }
 
+    /// Set the scroll offset of a scroll frame widget, see `WidgetBuilder::make_scroll_frame`.
+    /// Only moves the widget's children in the current frame, without changing their layout or drawing them again.
+    pub fn scroll_widget(&mut self, widget: &WidgetRef, offset: Vector) {
+        if widget.scroll_offset().is_none() {
+            return;
+        }
+        widget.widget_mut().scroll_offset = Some(offset);
+        self.render.scroll_frame(widget.id(), offset);
+        self.render.generate_frame();
+        // popups anchored in the scroll frame are drawn again to follow their anchors
+        if self.has_popups() {
+            self.update_popup_offsets();
+        }
+    }
+
+    /// Render the current state of the UI with the CPU renderer, returning an RGBA image
+    /// of the window at its hidpi factor, which can be saved as a PNG with `RgbaImage::save`.
+    /// Doesn't depend on the GPU, so the output is the same on every machine.
+    pub fn capture_frame(&mut self) -> RgbaImage {
+        let window_size = self.window.borrow().size_dp();
+        let mut renderer = CpuRenderBuilder::new(window_size, self.hidpi_factor());
+        renderer.fill_background(self.render.root_background_color.into());
+        self.draw_widgets(&mut renderer);
+        renderer.into_image()
+    }
+
+    /// Export the current state of the UI as an SVG document, the size of the window
+    pub fn export_svg(&mut self) -> String {
+        let window_size = self.window.borrow().size_dp();
+        let mut renderer = SvgRenderBuilder::new(Rect::new(Point::zero(), window_size));
+        renderer.fill_background(self.render.root_background_color.into());
+        self.draw_widgets(&mut renderer);
+        renderer.into_svg()
+    }
+
+    /// Export a widget and its children as an SVG document, the size of the widget's bounds
+    pub fn export_widget_svg(&self, widget: &WidgetRef) -> String {
+        let bounds = widget.bounds();
+        let mut renderer = SvgRenderBuilder::new(bounds);
+        widget.widget_mut().draw(bounds, &mut renderer);
+        renderer.into_svg()
+    }
+
+    /// Read back the last frame drawn by WebRender from the window's framebuffer.
+    /// With a software OpenGL implementation, such as Mesa's llvmpipe, this works without a GPU.
+    /// Fails if OpenGL reports an error or returns fewer pixels than the size of the window.
+    pub fn read_framebuffer(&self) -> Result<RgbaImage, io::Error> {
+        let window = self.window.borrow();
+        let size = window.size_px();
+        let gl_api = window.gl();
+        let pixels = gl_api.read_pixels(0, 0, size.width as i32, size.height as i32, gl::RGBA, gl::UNSIGNED_BYTE);
+        let error = gl_api.get_error();
+        if error != gl::NO_ERROR {
+            return Err(io::Error::new(io::ErrorKind::Other, format!("glReadPixels failed with error {:#x}", error)));
+        }
+        // OpenGL rows go from the bottom of the window to the top
+        let mut flipped = Vec::with_capacity(pixels.len());
+        for row in pixels.chunks(size.width as usize * 4).rev() {
+            flipped.extend_from_slice(row);
+        }
+        RgbaImage::from_raw(size.width, size.height, flipped).ok_or_else(|| {
+            io::Error::new(io::ErrorKind::InvalidData, "framebuffer is smaller than the window")
+        })
+    }
+
     // Call after drawing
     pub(super) fn update(&mut self) {
         self.render.update(self.window.borrow_mut().size_px());
@@ -157,11 +266,12 @@ impl Ui {
     }
 
     pub fn widgets_bfs(&self) -> WidgetsBfs {
-        WidgetsBfs::new(self.get_root())
+        WidgetsBfs::new(vec![self.get_root(), self.overlay.root.clone()])
     }
 
+    /// Iterate over the widgets under the cursor, starting with widgets in the overlay
     pub fn widgets_under_cursor(&mut self, point: Point) -> WidgetsUnderCursor {
-        WidgetsUnderCursor::new(point, self.get_root())
+        WidgetsUnderCursor::new(point, vec![self.get_root(), self.overlay.root.clone()])
     }
 
     /// Find the first widget under the cursor, ie. the last to be drawn that is under the cursor
@@ -261,61 +371,46 @@ pub enum ChildrenUpdatedEvent {
 
 
 pub struct WidgetsUnderCursor {
-    point: Point,
-    dfs: WidgetsDfsPostReverse,
+    /// Widgets that still need to be checked, with the cursor in the coordinates of their bounds,
+    /// and whether their children have been added after them
+    stack: Vec<(WidgetRef, Option<Point>, bool)>,
 }
 impl WidgetsUnderCursor {
-    fn new(point: Point, root: WidgetRef) -> Self {
+    fn new(point: Point, roots: Vec<WidgetRef>) -> Self {
+        let stack = roots.into_iter().map(|root| {
+            let point = root.widget().parent_to_local(point);
+            (root, point, false)
+        }).collect();
         WidgetsUnderCursor {
-            point: point,
-            dfs: WidgetsDfsPostReverse::new(root),
-        }
-    }
-}
-
-impl Iterator for WidgetsUnderCursor {
-    type Item = WidgetRef;
-    fn next(&mut self) -> Option<WidgetRef> {
-        for widget_ref in self.dfs.by_ref() {
-            let widget = &widget_ref.widget();
-            if widget.is_under_cursor(self.point) {
-                return Some(widget_ref.clone());
-            }
+            stack: stack,
         }
-        None
     }
 }
 
 // Iterates in reverse of draw order, that is, depth first post order,
-// with siblings in reverse of insertion order
-struct WidgetsDfsPostReverse {
-    stack: Vec<WidgetRef>,
-    discovered: HashSet<WidgetRef>,
-    finished: HashSet<WidgetRef>,
-}
-
-impl WidgetsDfsPostReverse {
-    fn new(root: WidgetRef) -> Self {
-        WidgetsDfsPostReverse {
-            stack: vec![root],
-            discovered: HashSet::new(),
-            finished: HashSet::new(),
-        }
-    }
-}
-
-impl Iterator for WidgetsDfsPostReverse {
+// with siblings in reverse of insertion order. Roots are given in draw order.
+// The cursor is converted to the coordinates of each widget from those of its parent,
+// since transformed widgets and widgets in scroll frames are hit tested where they're drawn,
+// not where they're laid out.
+impl Iterator for WidgetsUnderCursor {
     type Item = WidgetRef;
     fn next(&mut self) -> Option<WidgetRef> {
-        while let Some(widget_ref) = self.stack.last().cloned() {
-            if self.discovered.insert(widget_ref.clone()) {
-                for child in &widget_ref.children() {
-                    self.stack.push(child.clone());
+        while let Some((widget_ref, point, children_added)) = self.stack.pop() {
+            if children_added {
+                if let Some(point) = point {
+                    if widget_ref.widget().is_under_cursor(point) {
+                        return Some(widget_ref);
+                    }
                 }
-            } else {
-                self.stack.pop();
-                if self.finished.insert(widget_ref.clone()) {
-                    return Some(widget_ref.clone());
+                continue;
+            }
+            self.stack.push((widget_ref.clone(), point, true));
+            // children of a scroll frame are clipped to it
+            let child_point = point.and_then(|point| widget_ref.widget().local_to_children(point));
+            if let Some(child_point) = child_point {
+                for child in widget_ref.children() {
+                    let point = child.widget().parent_to_local(child_point);
+                    self.stack.push((child, point, false));
                 }
             }
         }
@@ -328,10 +423,8 @@ pub struct WidgetsBfs {
 }
 
 impl WidgetsBfs {
-    fn new(root: WidgetRef) -> Self {
-        let mut queue = VecDeque::new();
-        queue.push_front(root);
-        WidgetsBfs { queue: queue }
+    fn new(roots: Vec<WidgetRef>) -> Self {
+        WidgetsBfs { queue: roots.into_iter().collect() }
     }
 }
 
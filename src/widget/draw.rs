@@ -13,6 +13,11 @@ use geometry::{Rect, Point};
 
 pub trait Draw: Downcast {
     fn draw(&mut self, bounds: Rect, crop_to: Rect, renderer: &mut RenderBuilder);
+    /// The area the draw state draws in, drawing outside of it is clipped. By default, the widget's bounds,
+    /// draw states that draw outside of the bounds, like shadows, can extend it.
+    fn visual_bounds(&self, bounds: Rect) -> Rect {
+        bounds
+    }
     fn is_under_cursor(&self, bounds: Rect, cursor: Point) -> bool {
         bounds.contains(&cursor)
     }
@@ -23,21 +23,23 @@
 pub mod style;
 pub mod property;
 pub mod draw;
+pub mod transform;
 
 use std::any::{TypeId, Any};
 use std::collections::HashMap;
 use std::rc::{Rc, Weak};
 use std::cell::{RefCell, Ref, RefMut};
+use std::time::Duration;
 use std::hash::{Hash, Hasher};
 use std::ops::{Deref, DerefMut};
 use std::fmt;
 
-use render::RenderBuilder;
+use render::{RenderBuilder, Clip, StackingContext, BlendMode};
 use event::{self, EventHandler, EventArgs, EventHandlerWrapper};
 use layout::{Layout, LayoutVars, LayoutRef};
 use ui::Ui;
 use resources::{resources, WidgetId};
-use geometry::{Point, Rect};
+use geometry::{Point, Rect, RectExt, Size, Vector, Transform};
 use render;
 use color::Color;
 use event::Target;
@@ -45,6 +47,7 @@ use layout::UpdateLayout;
 
 use self::property::{PropSet, Property};
 use self::draw::{Draw, DrawWrapper};
+use self::transform::WidgetTransform;
 use self::style::Style;
 
 #[derive(Clone)]
@@ -130,6 +133,97 @@ impl WidgetRef {
     pub fn bounds(&self) -> Rect {
         self.0.borrow().bounds
     }
+    /// The scroll offset of the widget's children, if the widget is a scroll frame
+    pub fn scroll_offset(&self) -> Option<Vector> {
+        self.0.borrow().scroll_offset
+    }
+    pub fn opacity(&self) -> f32 {
+        self.0.borrow().opacity
+    }
+    pub fn transform(&self) -> WidgetTransform {
+        self.0.borrow().transform
+    }
+    pub fn blend_mode(&self) -> BlendMode {
+        self.0.borrow().blend_mode
+    }
+    /// Set the opacity of the widget and its children, from 0.0, invisible, to 1.0, opaque
+    pub fn set_opacity(&mut self, opacity: f32) {
+        self.widget_mut().opacity = opacity;
+        self.set_updated(true);
+        self.event(self::style::StyleUpdated);
+    }
+    /// Set the transform of the widget and its children, see `WidgetTransform`
+    pub fn set_transform(&mut self, transform: WidgetTransform) {
+        self.widget_mut().transform = transform;
+        self.set_updated(true);
+        self.event(self::style::StyleUpdated);
+    }
+    /// Set how the widget and its children are blended with the widgets behind them
+    pub fn set_blend_mode(&mut self, blend_mode: BlendMode) {
+        self.widget_mut().blend_mode = blend_mode;
+        self.set_updated(true);
+        self.event(self::style::StyleUpdated);
+    }
+    /// The widget and the widgets containing it, from the root to the widget
+    fn ancestors_from_root(&self) -> Vec<WidgetRef> {
+        let mut ancestors = vec![self.clone()];
+        let mut maybe_widget = self.parent();
+        while let Some(widget) = maybe_widget {
+            maybe_widget = widget.parent();
+            ancestors.push(widget);
+        }
+        ancestors.reverse();
+        ancestors
+    }
+    /// Transform from the coordinates of the widget's bounds to the window, applying the transforms of the widget
+    /// and the widgets containing it, and the scroll offsets of the scroll frames containing it
+    pub fn local_to_window(&self) -> Transform {
+        let mut transform = Transform::identity();
+        for widget in self.ancestors_from_root().iter().rev() {
+            let widget = widget.widget();
+            if widget.id != self.id() {
+                if let Some(scroll_offset) = widget.scroll_offset {
+                    transform = transform.post_mul(&Transform::create_translation(scroll_offset.x, scroll_offset.y));
+                }
+            }
+            if let Some(widget_transform) = widget.bounds_transform() {
+                transform = transform.post_mul(&widget_transform);
+            }
+        }
+        transform
+    }
+    /// The bounding rectangle of the widget where it's drawn in the window, after transforms and scrolling.
+    /// Use this rather than `bounds` to compare with mouse positions.
+    pub fn visible_bounds(&self) -> Rect {
+        let bounds = self.bounds();
+        let transform = self.local_to_window();
+        let corners = [
+            bounds.origin,
+            Point::new(bounds.right(), bounds.top()),
+            Point::new(bounds.left(), bounds.bottom()),
+            Point::new(bounds.right(), bounds.bottom()),
+        ];
+        let corners: Vec<Point> = corners.iter().map(|corner| transform.transform_point(corner)).collect();
+        let min = corners.iter().fold(corners[0], |min, corner| Point::new(min.x.min(corner.x), min.y.min(corner.y)));
+        let max = corners.iter().fold(corners[0], |max, corner| Point::new(max.x.max(corner.x), max.y.max(corner.y)));
+        Rect::new(min, (max - min).to_size())
+    }
+    /// Convert a point in the window to the coordinates of the widget's bounds, undoing the transforms
+    /// and scroll offsets of the widget and the widgets containing it.
+    /// Returns `None` if the point is clipped by a scroll frame, or the widget is scaled to nothing.
+    pub fn window_to_local(&self, point: Point) -> Option<Point> {
+        let ancestors = self.ancestors_from_root();
+        let mut point = Some(point);
+        // each widget is drawn in the coordinates of the widget containing it
+        for (index, widget) in ancestors.iter().enumerate() {
+            let widget = widget.widget();
+            point = point.and_then(|point| widget.parent_to_local(point));
+            if index + 1 < ancestors.len() {
+                point = point.and_then(|point| widget.local_to_children(point));
+            }
+        }
+        point
+    }
 
     pub fn update<F, T: Draw + 'static>(&mut self, f: F)
         where F: FnOnce(&mut T)
@@ -163,7 +257,7 @@ impl WidgetRef {
                 layout.add_child(child_layout);
             });
         });
-        self.event(::ui::WidgetAttachedEvent);
+        child.event(::ui::WidgetAttachedEvent);
         self.event(::ui::ChildAttachedEvent(self.id(), child.layout().vars));
         self.event(::ui::ChildrenUpdatedEvent::Added(child));
         self
@@ -181,7 +275,7 @@ impl WidgetRef {
             widget.children.remove(index);
         }
         self.event(::ui::ChildrenUpdatedEvent::Removed(child_ref.clone()));
-        child_ref.event(::ui::WidgetDetachedEvent);
+        child_ref.event_subtree(::ui::WidgetDetachedEvent);
         event::event(Target::Root, ::ui::RemoveWidget(child_ref.clone()));
     }
 
@@ -202,6 +296,15 @@ impl WidgetRef {
     pub fn event<T: 'static>(&self, data: T) {
         event::event(Target::Widget(self.clone()), data);
     }
+    /// Send an event to this widget once `delay` has passed
+    pub fn event_delayed<T: 'static>(&self, data: T, delay: Duration) {
+        event::event_delayed(Target::Widget(self.clone()), data, delay);
+    }
+    /// Send a `FrameEvent` to this widget after the next frame is drawn, waking the app if it's idle.
+    /// Animated widgets should request a frame on every `FrameEvent` until the animation is finished.
+    pub fn request_frame(&self) {
+        event::event(Target::Root, ::app::RequestFrame(self.clone()));
+    }
     pub fn event_subtree<T: 'static>(&self, data: T) {
         event::event(Target::SubTree(self.clone()), data);
     }
@@ -335,6 +438,11 @@ pub struct Widget {
     has_updated: bool,
     pub(super) layout: Layout,
     pub(super) bounds: Rect,
+    /// Offset of the children of a scroll frame, `None` if the widget isn't a scroll frame
+    pub(crate) scroll_offset: Option<Vector>,
+    transform: WidgetTransform,
+    opacity: f32,
+    blend_mode: BlendMode,
     name: String,
     debug_color: Option<Color>,
     children: Vec<WidgetRef>,
@@ -353,6 +461,10 @@ impl Widget {
             layout: Layout::new(id.0, Some(name.clone())),
             has_updated: false,
             bounds: Rect::zero(),
+            scroll_offset: None,
+            transform: WidgetTransform::default(),
+            opacity: 1.0,
+            blend_mode: BlendMode::Normal,
             name: name,
             debug_color: None,
             children: Vec::new(),
@@ -366,20 +478,73 @@ impl Widget {
     pub fn layout(&mut self) -> &mut Layout {
         &mut self.layout
     }
+    /// The widget's transform for its current bounds, if it has one
+    fn bounds_transform(&self) -> Option<Transform> {
+        if self.transform.is_identity() {
+            None
+        } else {
+            Some(self.transform.to_transform(self.bounds))
+        }
+    }
+    /// Convert a point in the coordinates the widget is drawn in, those of its parent's children,
+    /// to the coordinates of its bounds. Returns `None` if the widget is scaled to nothing.
+    pub(crate) fn parent_to_local(&self, point: Point) -> Option<Point> {
+        match self.bounds_transform() {
+            Some(transform) => transform.inverse().map(|inverse| inverse.transform_point(&point)),
+            None => Some(point),
+        }
+    }
+    /// Convert a point in the coordinates of the widget's bounds to the coordinates its children are drawn in.
+    /// Returns `None` if the widget is a scroll frame and the point is outside of it.
+    pub(crate) fn local_to_children(&self, point: Point) -> Option<Point> {
+        match self.scroll_offset {
+            Some(_) if !self.bounds.contains(&point) => None,
+            Some(scroll_offset) => Some(point - scroll_offset),
+            None => Some(point),
+        }
+    }
     pub fn draw(&mut self, crop_to: Rect, renderer: &mut RenderBuilder) {
         let bounds = self.bounds;
-        let clip_id = renderer.builder.define_clip(None, bounds, vec![], None);
-        renderer.builder.push_clip_id(clip_id);
+        let transform = self.bounds_transform();
+        let has_stacking_context = transform.is_some() || self.opacity < 1.0 || self.blend_mode != BlendMode::Normal;
+        if has_stacking_context {
+            renderer.push_stacking_context(StackingContext {
+                transform: transform,
+                opacity: self.opacity,
+                blend_mode: self.blend_mode,
+            });
+        }
+        // crop_to is in window coordinates, it doesn't apply to the children of a transformed widget
+        let crop_to = if transform.is_some() {
+            Rect::new(Point::zero(), Size::new(::std::f32::MAX, ::std::f32::MAX))
+        } else {
+            crop_to
+        };
         if let Some(draw_state) = self.draw_state.as_mut() {
+            renderer.push_clip(Clip::Rect(draw_state.state.visual_bounds(bounds)));
             draw_state.state.draw(bounds, crop_to, renderer);
+            renderer.pop_clip();
         }
-        if let Some(crop_to) = crop_to.intersection(&bounds) {
+        renderer.push_clip(Clip::Rect(bounds));
+        if let Some(scroll_offset) = self.scroll_offset {
+            // the whole content is drawn, so it can be scrolled without drawing it again
+            let content = self.children.iter().fold(bounds, |content, child| content.union(&child.bounds()));
+            let crop_to = Rect::new(Point::zero(), Size::new(::std::f32::MAX, ::std::f32::MAX));
+            renderer.push_scroll_frame(self.id, bounds, content, scroll_offset);
+            for child in &self.children {
+                child.widget_mut().draw(crop_to, renderer);
+            }
+            renderer.pop_scroll_frame();
+        } else if let Some(crop_to) = crop_to.intersection(&bounds) {
             for child in &self.children {
                 let mut child = child.widget_mut();
                 child.draw(crop_to, renderer);
             }
         }
-        renderer.builder.pop_clip_id();
+        renderer.pop_clip();
+        if has_stacking_context {
+            renderer.pop_stacking_context();
+        }
     }
     pub fn draw_debug(&mut self, renderer: &mut RenderBuilder) {
         let color = self.debug_color.unwrap_or(::color::GREEN);
@@ -471,6 +636,32 @@ impl WidgetBuilder {
         self
     }
 
+    /// Set the opacity of the widget and its children, from 0.0, invisible, to 1.0, opaque
+    pub fn set_opacity(&mut self, opacity: f32) -> &mut Self {
+        self.widget.widget_mut().opacity = opacity;
+        self
+    }
+
+    /// Translate, rotate or scale the widget and its children where they're drawn, see `WidgetTransform`
+    pub fn set_transform(&mut self, transform: WidgetTransform) -> &mut Self {
+        self.widget.widget_mut().transform = transform;
+        self
+    }
+
+    /// Set how the widget and its children are blended with the widgets behind them
+    pub fn set_blend_mode(&mut self, blend_mode: BlendMode) -> &mut Self {
+        self.widget.widget_mut().blend_mode = blend_mode;
+        self
+    }
+
+    /// Make the widget a scroll frame, its children are drawn moved by a scroll offset
+    /// and clipped to its bounds. The offset is set with `Ui::scroll_widget`, which moves the children
+    /// without changing their layout or drawing them again.
+    pub fn make_scroll_frame(&mut self) -> &mut Self {
+        self.widget.widget_mut().scroll_offset = Some(Vector::zero());
+        self
+    }
+
     /// Recursively sets a certain property on the current widget
     pub fn add_prop(&mut self, property: Property) -> &mut Self {
         self.widget.widget_mut().props.insert(property);
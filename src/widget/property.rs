@@ -7,7 +7,10 @@ pub enum Property {
     Selected,
     Pressed,
     Inactive,
+    Invalid,
     Focused,
+    Indeterminate,
+    DragOver,
 }
 pub type PropSet = BTreeSet<Property>;
 
@@ -19,7 +22,11 @@ pub mod states {
         pub static ref ACTIVATED: PropSet = btreeset!{Property::Activated};
         pub static ref ACTIVATED_PRESSED: PropSet = btreeset!{Property::Activated, Property::Pressed};
         pub static ref SELECTED: PropSet = btreeset!{Property::Selected};
+        pub static ref ACTIVATED_SELECTED: PropSet = btreeset!{Property::Activated, Property::Selected};
         pub static ref INACTIVE: PropSet = btreeset!{Property::Inactive};
+        pub static ref INVALID: PropSet = btreeset!{Property::Invalid};
         pub static ref FOCUSED: PropSet = btreeset!{Property::Focused};
+        pub static ref INDETERMINATE: PropSet = btreeset!{Property::Indeterminate};
+        pub static ref DRAG_OVER: PropSet = btreeset!{Property::DragOver};
     }
 }
@@ -0,0 +1,66 @@
+//! 2D transforms of widgets, applied to the widget and its children when they're drawn.
+
+use euclid::Radians;
+
+use geometry::{Rect, Point, Vector, Transform};
+
+/// Translates, rotates and scales a widget, around an origin within its bounds.
+/// The layout of the widget isn't affected, only where it's drawn and receives mouse events.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct WidgetTransform {
+    pub translation: Vector,
+    /// Clockwise rotation in radians
+    pub rotation: f32,
+    pub scale: Vector,
+    /// The point the widget is rotated and scaled around, as a fraction of its size,
+    /// (0.5, 0.5), the center of the widget, by default
+    pub origin: Point,
+}
+
+impl Default for WidgetTransform {
+    fn default() -> Self {
+        WidgetTransform {
+            translation: Vector::zero(),
+            rotation: 0.0,
+            scale: Vector::new(1.0, 1.0),
+            origin: Point::new(0.5, 0.5),
+        }
+    }
+}
+
+impl WidgetTransform {
+    pub fn new() -> Self {
+        Self::default()
+    }
+    pub fn translate(mut self, x: f32, y: f32) -> Self {
+        self.translation = Vector::new(x, y);
+        self
+    }
+    /// Rotate clockwise by an angle in radians
+    pub fn rotate(mut self, rotation: f32) -> Self {
+        self.rotation = rotation;
+        self
+    }
+    pub fn scale(mut self, x: f32, y: f32) -> Self {
+        self.scale = Vector::new(x, y);
+        self
+    }
+    /// Set the point the widget is rotated and scaled around, as a fraction of its size
+    pub fn set_origin(mut self, x: f32, y: f32) -> Self {
+        self.origin = Point::new(x, y);
+        self
+    }
+    pub fn is_identity(&self) -> bool {
+        self.translation == Vector::zero() && self.rotation == 0.0 && self.scale == Vector::new(1.0, 1.0)
+    }
+    /// The transform for a widget with the given bounds
+    pub fn to_transform(&self, bounds: Rect) -> Transform {
+        let origin = Point::new(
+            bounds.origin.x + bounds.size.width * self.origin.x,
+            bounds.origin.y + bounds.size.height * self.origin.y);
+        Transform::create_translation(-origin.x, -origin.y)
+            .post_mul(&Transform::create_scale(self.scale.x, self.scale.y))
+            .post_mul(&Transform::create_rotation(Radians::new(self.rotation)))
+            .post_mul(&Transform::create_translation(origin.x + self.translation.x, origin.y + self.translation.y))
+    }
+}
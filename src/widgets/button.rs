@@ -192,7 +192,10 @@ impl WidgetBuilder {
         where F: Fn(&ClickEvent, &mut EventArgs) + 'static
     {
         self.add_handler(move |event: &ClickEvent, mut args: EventArgs| {
-            (on_click)(event, &mut args);
+            // an inactive widget still consumes the click, so it doesn't reach its parents
+            if !args.widget.props().contains(&Property::Inactive) {
+                (on_click)(event, &mut args);
+            }
             *args.handled = true;
         })
     }
@@ -0,0 +1,243 @@
+use cassowary::strength::*;
+
+use layout::constraint::*;
+use event::{EventHandler, EventArgs};
+use widget::WidgetBuilder;
+use widget::property::Property;
+use widget::property::states::*;
+use widgets::text::TextBuilder;
+use input::mouse::ClickEvent;
+use draw::rect::{RectState, RectStyle};
+use draw::text::TextStyle;
+use geometry::Size;
+use color::*;
+
+static COLOR_CHECKBOX_DEFAULT: Color = WHITE;
+static COLOR_CHECKBOX_MOUSEOVER: Color = GRAY_90;
+static COLOR_CHECKBOX_INACTIVE: Color = GRAY_90;
+static COLOR_CHECKBOX_MARK: Color = BLUE_HIGHLIGHT;
+static COLOR_CHECKBOX_TEXT_INACTIVE: Color = GRAY_70;
+
+static CHECKBOX_BORDER: (f32, Color) = (1.0, GRAY_40);
+static CHECKBOX_BORDER_FOCUSED: (f32, Color) = (1.0, BLUE);
+static CHECKBOX_BORDER_INACTIVE: (f32, Color) = (1.0, GRAY_70);
+
+const CHECKBOX_SIZE: f32 = 20.0;
+
+lazy_static! {
+    pub static ref STYLE_CHECKBOX: Vec<RectStyle> = {
+        style!(
+            RectStyle::BackgroundColor: selector!(COLOR_CHECKBOX_DEFAULT,
+                INACTIVE: COLOR_CHECKBOX_INACTIVE,
+                MOUSEOVER: COLOR_CHECKBOX_MOUSEOVER),
+            RectStyle::CornerRadius: Some(3.0),
+            RectStyle::Border: selector!(Some(CHECKBOX_BORDER),
+                INACTIVE: Some(CHECKBOX_BORDER_INACTIVE),
+                FOCUSED: Some(CHECKBOX_BORDER_FOCUSED))
+        )
+    };
+    pub static ref STYLE_CHECKBOX_MARK: Vec<RectStyle> = {
+        style!(
+            RectStyle::BackgroundColor: selector!(TRANSPARENT,
+                ACTIVATED: COLOR_CHECKBOX_MARK),
+            RectStyle::CornerRadius: Some(2.0)
+        )
+    };
+    pub static ref STYLE_CHECKBOX_DASH: Vec<RectStyle> = {
+        style!(
+            RectStyle::BackgroundColor: selector!(TRANSPARENT,
+                INDETERMINATE: COLOR_CHECKBOX_MARK)
+        )
+    };
+    pub static ref STYLE_CHECKBOX_TEXT: Vec<TextStyle> = {
+        style!(TextStyle::TextColor: selector!(BLACK, INACTIVE: COLOR_CHECKBOX_TEXT_INACTIVE))
+    };
+}
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum CheckState {
+    Unchecked,
+    Checked,
+    /// Neither checked or unchecked, typically used when the check box
+    /// represents a group of items, some of which are checked.
+    Indeterminate,
+}
+
+impl CheckState {
+    /// The state the check box moves to when clicked.
+    /// An indeterminate check box becomes checked.
+    fn toggled(&self) -> Self {
+        match *self {
+            CheckState::Checked => CheckState::Unchecked,
+            CheckState::Unchecked | CheckState::Indeterminate => CheckState::Checked,
+        }
+    }
+}
+
+/// Emitted by a check box when it's state changes as a result of user input
+#[derive(Debug, Copy, Clone)]
+pub struct CheckBoxEvent(pub CheckState);
+
+/// Send to a check box to change it's state, without emitting a `CheckBoxEvent`
+#[derive(Debug, Copy, Clone)]
+pub struct SetCheckState(pub CheckState);
+
+#[derive(Debug, Clone)]
+struct CheckBoxHandler {
+    state: CheckState,
+}
+
+impl CheckBoxHandler {
+    fn new(state: CheckState) -> Self {
+        CheckBoxHandler { state: state }
+    }
+}
+
+fn set_check_props(args: &mut EventArgs, state: CheckState) {
+    match state {
+        CheckState::Checked => {
+            args.widget.remove_prop(Property::Indeterminate);
+            args.widget.add_prop(Property::Activated);
+        }
+        CheckState::Unchecked => {
+            args.widget.remove_prop(Property::Indeterminate);
+            args.widget.remove_prop(Property::Activated);
+        }
+        CheckState::Indeterminate => {
+            args.widget.remove_prop(Property::Activated);
+            args.widget.add_prop(Property::Indeterminate);
+        }
+    }
+}
+
+impl EventHandler<CheckBoxInputEvent> for CheckBoxHandler {
+    fn handle(&mut self, event: &CheckBoxInputEvent, mut args: EventArgs) {
+        match *event {
+            CheckBoxInputEvent::Click => {
+                if args.widget.props().contains(&Property::Inactive) {
+                    return;
+                }
+                self.state = self.state.toggled();
+                set_check_props(&mut args, self.state);
+                args.widget.event(CheckBoxEvent(self.state));
+            }
+            CheckBoxInputEvent::SetState(state) => {
+                self.state = state;
+                set_check_props(&mut args, self.state);
+            }
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone)]
+enum CheckBoxInputEvent {
+    Click,
+    SetState(CheckState),
+}
+
+pub struct CheckBoxBuilder {
+    pub widget: WidgetBuilder,
+    check_box: WidgetBuilder,
+    init_state: CheckState,
+}
+
+widget_builder!(CheckBoxBuilder);
+
+impl Default for CheckBoxBuilder {
+    fn default() -> Self {
+        let mut widget = WidgetBuilder::new("checkbox");
+        widget
+            .enable_hover()
+            .make_focusable()
+            .click_on_activate_key();
+        widget.layout().add(shrink());
+
+        let mut check_box = WidgetBuilder::new("checkbox_box");
+        check_box.set_draw_state_with_style(RectState::new(), STYLE_CHECKBOX.clone());
+        check_box.layout().add(constraints![
+            size(Size::new(CHECKBOX_SIZE, CHECKBOX_SIZE)),
+            align_left(&widget),
+            align_top(&widget).strength(WEAK),
+            bound_by(&widget),
+            center_vertical(&widget),
+        ]);
+
+        let mut check_mark = WidgetBuilder::new("checkbox_mark");
+        check_mark.set_draw_state_with_style(RectState::new(), STYLE_CHECKBOX_MARK.clone());
+        check_mark.layout().add(match_layout(&check_box).padding(5.0));
+
+        let mut check_dash = WidgetBuilder::new("checkbox_dash");
+        check_dash.set_draw_state_with_style(RectState::new(), STYLE_CHECKBOX_DASH.clone());
+        check_dash.layout().add(constraints![
+            align_left(&check_box).padding(5.0),
+            align_right(&check_box).padding(5.0),
+            center_vertical(&check_box),
+            height(3.0),
+        ]);
+
+        check_box
+            .add_child(check_mark)
+            .add_child(check_dash);
+
+        CheckBoxBuilder {
+            widget: widget,
+            check_box: check_box,
+            init_state: CheckState::Unchecked,
+        }
+    }
+}
+
+impl CheckBoxBuilder {
+    /// Creates a new, unchecked `CheckBoxBuilder`
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the initial state of the check box
+    pub fn set_state(&mut self, state: CheckState) -> &mut Self {
+        self.init_state = state;
+        self
+    }
+
+    /// Add a text label to the right of the check box
+    pub fn set_text(&mut self, text: &str) -> &mut Self {
+        let style = style!(parent: STYLE_CHECKBOX_TEXT,
+            TextStyle::Text: text.to_owned());
+        let mut text_widget = TextBuilder::new_with_style(style);
+        text_widget.set_name("checkbox_text");
+        text_widget.layout().add(constraints![
+            to_right_of(&self.check_box).padding(8.0),
+            center_vertical(&self.widget),
+            bound_by(&self.widget),
+        ]);
+        self.widget.add_child(text_widget);
+        self
+    }
+
+    /// Callback function to be called when the check box state changes
+    pub fn on_change<F>(&mut self, callback: F) -> &mut Self
+        where F: Fn(&CheckBoxEvent, EventArgs) + 'static
+    {
+        self.widget.add_handler(callback);
+        self
+    }
+}
+
+impl Into<WidgetBuilder> for CheckBoxBuilder {
+    fn into(self) -> WidgetBuilder {
+        let (mut widget, check_box) = (self.widget, self.check_box);
+        widget
+            .add_handler(CheckBoxHandler::new(self.init_state))
+            .add_handler(|_: &ClickEvent, mut args: EventArgs| {
+                args.widget.event(CheckBoxInputEvent::Click);
+                *args.handled = true;
+            })
+            .add_handler(|event: &SetCheckState, args: EventArgs| {
+                args.widget.event(CheckBoxInputEvent::SetState(event.0));
+            });
+        widget.add_child(check_box);
+        // applied as an event so the properties reach every descendant
+        widget.widget_ref().event(CheckBoxInputEvent::SetState(self.init_state));
+        widget
+    }
+}
@@ -0,0 +1,326 @@
+//! Drag and drop, moving typed payloads from drag sources to drop targets.
+//!
+//! A widget becomes a drag source with `WidgetBuilder::set_drag_source`. Once it's dragged a small
+//! distance, its payload is created, and any drop target under the cursor that accepts the payload type
+//! receives `DropEvent`s as the payload enters, moves over, leaves, or is dropped on it.
+
+use std::any::{Any, TypeId};
+use std::collections::HashMap;
+use std::rc::Rc;
+
+use event::{EventHandler, EventArgs};
+use widget::{WidgetBuilder, WidgetRef};
+use widget::property::Property;
+use widgets::drag::{DragEvent, DragState};
+use ui::{WidgetAttachedEvent, WidgetDetachedEvent};
+use overlay::is_descendant;
+use geometry::{Point, Vector};
+use app::App;
+
+/// Distance the mouse has to move while pressed before a drag and drop starts,
+/// so clicking a drag source doesn't start a drag
+const DRAG_THRESHOLD: f32 = 4.0;
+
+/// The data carried by a drag and drop, created by the drag source when the drag starts
+#[derive(Clone)]
+pub struct DragPayload {
+    data: Rc<Any>,
+    type_id: TypeId,
+}
+
+impl DragPayload {
+    fn new<T: 'static>(data: T) -> Self {
+        DragPayload {
+            data: Rc::new(data),
+            type_id: TypeId::of::<T>(),
+        }
+    }
+    /// Returns the payload, if it's of type `T`
+    pub fn get<T: 'static>(&self) -> Option<&T> {
+        self.data.downcast_ref::<T>()
+    }
+    pub fn is<T: 'static>(&self) -> bool {
+        self.type_id == TypeId::of::<T>()
+    }
+}
+
+impl ::std::fmt::Debug for DragPayload {
+    fn fmt(&self, f: &mut ::std::fmt::Formatter) -> ::std::fmt::Result {
+        write!(f, "DragPayload({:?})", self.type_id)
+    }
+}
+
+/// Creates the payload of a drag and drop, and optionally a preview widget that follows the cursor
+pub struct DragSource {
+    payload: Box<Fn() -> DragPayload>,
+    preview: Option<Box<Fn() -> WidgetBuilder>>,
+}
+
+impl DragSource {
+    /// A drag source with a payload that is cloned every time the widget is dragged
+    pub fn new<T: Clone + 'static>(payload: T) -> Self {
+        DragSource {
+            payload: Box::new(move || DragPayload::new(payload.clone())),
+            preview: None,
+        }
+    }
+    /// Show a widget under the cursor while dragging, the function is called to create
+    /// the widget every time a drag starts
+    pub fn set_preview<F>(mut self, preview: F) -> Self
+        where F: Fn() -> WidgetBuilder + 'static
+    {
+        self.preview = Some(Box::new(preview));
+        self
+    }
+}
+
+/// Declares the payload types a drop target accepts
+#[derive(Default)]
+pub struct DropTarget {
+    accepts: Vec<TypeId>,
+}
+
+impl DropTarget {
+    pub fn new() -> Self {
+        Self::default()
+    }
+    /// Accept payloads of type `T`
+    pub fn accept<T: 'static>(mut self) -> Self {
+        self.accepts.push(TypeId::of::<T>());
+        self
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum DropEventKind {
+    /// A payload was dragged onto the target
+    Enter,
+    /// A payload was dragged within the target
+    Over,
+    /// A payload was dragged off the target
+    Leave,
+    /// A payload was dropped on the target
+    Drop,
+}
+
+/// Sent to a drop target as an accepted payload is dragged over it
+#[derive(Debug, Clone)]
+pub struct DropEvent {
+    pub kind: DropEventKind,
+    pub payload: DragPayload,
+    /// The source of the payload
+    pub source: WidgetRef,
+    /// Mouse position
+    pub position: Point,
+}
+
+/// Sent to a drag source when a drag and drop it started ends,
+/// with the drop target, if the payload was dropped on one
+#[derive(Debug, Clone)]
+pub struct DragFinished {
+    pub target: Option<WidgetRef>,
+}
+
+enum DragDropEvent {
+    RegisterTarget(WidgetRef, Rc<Vec<TypeId>>),
+    UnregisterTarget(WidgetRef),
+    Start(WidgetRef, Rc<DragSource>, DragEvent),
+    Move(Point),
+    Release(Point),
+}
+
+struct DragSession {
+    source: WidgetRef,
+    payload: DragPayload,
+    preview: Option<WidgetRef>,
+    /// Offset from the cursor to the top left of the preview
+    grab_offset: Vector,
+    target: Option<WidgetRef>,
+}
+
+/// Tracks the drop targets and the current drag and drop
+#[derive(Default)]
+struct DragDropHandler {
+    targets: HashMap<WidgetRef, Rc<Vec<TypeId>>>,
+    session: Option<DragSession>,
+}
+
+impl DragDropHandler {
+    /// Find the innermost drop target under the cursor that accepts the payload, ignoring the preview
+    fn find_target(&self, position: Point, args: &mut EventArgs) -> Option<WidgetRef> {
+        let session = self.session.as_ref().unwrap();
+        let mut maybe_widget = args.ui.widgets_under_cursor(position).find(|widget| {
+            session.preview.as_ref().map_or(true, |preview| !is_descendant(widget, preview))
+        });
+        while let Some(widget) = maybe_widget {
+            if let Some(accepts) = self.targets.get(&widget) {
+                if accepts.contains(&session.payload.type_id) {
+                    return Some(widget);
+                }
+            }
+            maybe_widget = widget.parent();
+        }
+        None
+    }
+    fn send(&mut self, kind: DropEventKind, position: Point) {
+        let session = self.session.as_mut().unwrap();
+        if let Some(ref mut target) = session.target {
+            match kind {
+                DropEventKind::Enter => target.add_prop(Property::DragOver),
+                DropEventKind::Leave | DropEventKind::Drop => target.remove_prop(Property::DragOver),
+                DropEventKind::Over => (),
+            }
+            target.event(DropEvent {
+                kind: kind,
+                payload: session.payload.clone(),
+                source: session.source.clone(),
+                position: position,
+            });
+        }
+    }
+    fn move_to(&mut self, position: Point, args: &mut EventArgs) {
+        let target = self.find_target(position, args);
+        let target_changed = {
+            let session = self.session.as_ref().unwrap();
+            if let Some(ref preview) = session.preview {
+                let preview_position = position + session.grab_offset;
+                preview.update_layout(|layout| {
+                    layout.edit_left().set(preview_position.x);
+                    layout.edit_top().set(preview_position.y);
+                });
+            }
+            target != session.target
+        };
+        if target_changed {
+            self.send(DropEventKind::Leave, position);
+            self.session.as_mut().unwrap().target = target;
+            self.send(DropEventKind::Enter, position);
+        }
+        self.send(DropEventKind::Over, position);
+    }
+}
+
+impl EventHandler<DragDropEvent> for DragDropHandler {
+    fn handle(&mut self, event: &DragDropEvent, mut args: EventArgs) {
+        match *event {
+            DragDropEvent::RegisterTarget(ref widget, ref accepts) => {
+                self.targets.insert(widget.clone(), accepts.clone());
+            }
+            DragDropEvent::UnregisterTarget(ref widget) => {
+                self.targets.remove(widget);
+            }
+            DragDropEvent::Start(ref source, ref drag_source, ref event) => {
+                if self.session.is_some() {
+                    return;
+                }
+                let bounds = source.visible_bounds();
+                let start_position = event.position - event.offset;
+                let preview = drag_source.preview.as_ref().map(|preview| {
+                    let mut preview = preview();
+                    preview.layout().add(::layout::constraint::shrink());
+                    args.ui.open_popup(preview, None)
+                });
+                self.session = Some(DragSession {
+                    source: source.clone(),
+                    payload: (drag_source.payload)(),
+                    preview: preview,
+                    grab_offset: bounds.origin - start_position,
+                    target: None,
+                });
+                self.move_to(event.position, &mut args);
+            }
+            DragDropEvent::Move(position) => {
+                if self.session.is_some() {
+                    self.move_to(position, &mut args);
+                }
+            }
+            DragDropEvent::Release(position) => {
+                if self.session.is_none() {
+                    return;
+                }
+                self.move_to(position, &mut args);
+                self.send(DropEventKind::Drop, position);
+                let session = self.session.take().unwrap();
+                if let Some(preview) = session.preview {
+                    args.ui.close_popup(&preview);
+                }
+                session.source.event(DragFinished { target: session.target });
+            }
+        }
+    }
+}
+
+/// Starts a drag and drop once the source has been dragged past the threshold
+struct DragSourceHandler {
+    source: Rc<DragSource>,
+    started: bool,
+}
+
+impl EventHandler<DragEvent> for DragSourceHandler {
+    fn handle(&mut self, event: &DragEvent, args: EventArgs) {
+        match event.state {
+            DragState::Start => {
+                self.started = false;
+            }
+            DragState::Moved => {
+                if self.started {
+                    args.ui.event(DragDropEvent::Move(event.position));
+                } else if event.offset.length() >= DRAG_THRESHOLD {
+                    self.started = true;
+                    args.ui.event(DragDropEvent::Start(args.widget.clone(), self.source.clone(), *event));
+                }
+            }
+            DragState::End => {
+                if self.started {
+                    self.started = false;
+                    args.ui.event(DragDropEvent::Release(event.position));
+                }
+            }
+        }
+    }
+}
+
+impl WidgetBuilder {
+    /// Make this widget a source of drag and drop payloads, see `DragSource`.
+    /// The widget receives a `DragFinished` event when a drag it started ends.
+    pub fn set_drag_source(&mut self, source: DragSource) -> &mut Self {
+        self.make_draggable();
+        self.add_handler(DragSourceHandler {
+            source: Rc::new(source),
+            started: false,
+        })
+    }
+
+    /// Make this widget a drop target for the payload types accepted by `target`.
+    /// While an accepted payload is dragged over the widget, it has the `DragOver` property.
+    pub fn set_drop_target(&mut self, target: DropTarget) -> &mut Self {
+        let accepts = Rc::new(target.accepts);
+        self.add_handler(move |_: &WidgetAttachedEvent, args: EventArgs| {
+            args.ui.event(DragDropEvent::RegisterTarget(args.widget.clone(), accepts.clone()));
+        });
+        self.add_handler(|_: &WidgetDetachedEvent, args: EventArgs| {
+            args.ui.event(DragDropEvent::UnregisterTarget(args.widget.clone()));
+        })
+    }
+
+    /// Callback function to be called when a payload of type `T` is dropped on this widget,
+    /// which should be a drop target accepting `T`
+    pub fn on_drop<T, F>(&mut self, callback: F) -> &mut Self
+        where T: 'static, F: Fn(&T, &DropEvent, &mut EventArgs) + 'static
+    {
+        self.add_handler(move |event: &DropEvent, mut args: EventArgs| {
+            if event.kind == DropEventKind::Drop {
+                if let Some(payload) = event.payload.get::<T>() {
+                    callback(payload, event, &mut args);
+                }
+            }
+        })
+    }
+}
+
+impl App {
+    /// Add the handler that tracks drop targets and drag and drop sessions
+    pub fn add_drag_drop_handlers(&mut self) {
+        self.add_handler(DragDropHandler::default());
+    }
+}
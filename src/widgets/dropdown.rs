@@ -0,0 +1,310 @@
+//! A select control, that shows the list of options in a popup when clicked.
+
+use cassowary::strength::*;
+
+use text_layout::Align;
+
+use layout::constraint::*;
+use layout::linear_layout::{LinearLayoutSettings, Orientation, ItemAlignment};
+use event::{EventHandler, EventArgs};
+use widget::{WidgetBuilder, WidgetRef};
+use widget::property::Property;
+use widget::property::states::*;
+use widgets::button::{STYLE_BUTTON, STYLE_BUTTON_TEXT};
+use widgets::text::TextBuilder;
+use widgets::edit_text::EditTextBuilder;
+use input::mouse::ClickEvent;
+use input::keyboard::KeyboardInputEvent;
+use overlay::PopupClosed;
+use draw::rect::{RectState, RectStyle};
+use draw::text::{TextState, TextStyle};
+use geometry::Size;
+use color::*;
+
+static COLOR_DROPDOWN_ITEM_DEFAULT: Color = WHITE;
+static COLOR_DROPDOWN_ITEM_MOUSEOVER: Color = GRAY_90;
+static COLOR_DROPDOWN_ITEM_SELECTED: Color = BLUE_HIGHLIGHT;
+
+lazy_static! {
+    pub static ref STYLE_DROPDOWN_POPUP: Vec<RectStyle> = {
+        style!(
+            RectStyle::BackgroundColor: WHITE,
+            RectStyle::Border: Some((1.0, GRAY_40))
+        )
+    };
+    pub static ref STYLE_DROPDOWN_ITEM: Vec<RectStyle> = {
+        style!(RectStyle::BackgroundColor: selector!(COLOR_DROPDOWN_ITEM_DEFAULT,
+            SELECTED: COLOR_DROPDOWN_ITEM_SELECTED,
+            MOUSEOVER: COLOR_DROPDOWN_ITEM_MOUSEOVER))
+    };
+    pub static ref STYLE_DROPDOWN_ITEM_TEXT: Vec<TextStyle> = {
+        style!(TextStyle::TextColor: selector!(BLACK, SELECTED: WHITE))
+    };
+}
+
+/// Emitted by a dropdown when an option is selected
+#[derive(Debug, Clone)]
+pub struct DropdownSelected {
+    pub index: usize,
+    pub value: String,
+}
+
+#[derive(Debug, Clone)]
+enum DropdownInputEvent {
+    Click,
+    Select(usize),
+    Filter(String),
+    PopupClosed,
+}
+
+struct DropdownHandler {
+    items: Vec<String>,
+    selected: Option<usize>,
+    filter: bool,
+    text: WidgetRef,
+    popup: Option<WidgetRef>,
+    list: Option<WidgetRef>,
+}
+
+impl DropdownHandler {
+    fn open_popup(&mut self, args: &mut EventArgs) {
+        let dropdown = args.widget.clone();
+        let mut popup = WidgetBuilder::new("dropdown_popup");
+        popup.set_draw_state_with_style(RectState::new(), STYLE_DROPDOWN_POPUP.clone());
+        popup.layout().add(constraints![
+            align_below(&dropdown).strength(STRONG),
+            align_left(&dropdown).strength(STRONG),
+            min_width(dropdown.bounds().size.width),
+            shrink(),
+        ]);
+        {
+            let dropdown = dropdown.clone();
+            popup.add_handler(move |_: &PopupClosed, _: EventArgs| {
+                dropdown.event(DropdownInputEvent::PopupClosed);
+            });
+        }
+
+        let mut layout_settings = LinearLayoutSettings::new(Orientation::Vertical);
+        layout_settings.item_align = ItemAlignment::Fill;
+        let mut list = WidgetBuilder::new("dropdown_list");
+        list.linear_layout(layout_settings);
+        list.layout().add(constraints![
+            align_left(&popup),
+            align_right(&popup),
+            align_bottom(&popup),
+        ]);
+
+        let mut filter_ref = None;
+        if self.filter {
+            let mut filter = EditTextBuilder::new();
+            {
+                let dropdown = dropdown.clone();
+                filter.on_text_changed(move |event, _| {
+                    dropdown.event(DropdownInputEvent::Filter(event.0.clone()));
+                });
+            }
+            filter.layout().add(constraints![
+                align_top(&popup).padding(5.0),
+                align_left(&popup).padding(5.0),
+                align_right(&popup).padding(5.0),
+            ]);
+            list.layout().add(below(&filter).padding(5.0));
+            filter_ref = Some(filter.widget_ref());
+            popup.add_child(filter);
+        } else {
+            list.layout().add(align_top(&popup));
+        }
+
+        for (index, item) in self.items.iter().enumerate() {
+            list.add_child(self.build_item(&dropdown, index, item));
+        }
+        self.list = Some(list.widget_ref());
+        popup.add_child(list);
+
+        self.popup = Some(args.ui.open_popup(popup, Some(dropdown)));
+        args.widget.add_prop(Property::Activated);
+        if let Some(filter_ref) = filter_ref {
+            args.ui.event(KeyboardInputEvent::FocusChange(Some(filter_ref)));
+        }
+    }
+
+    fn build_item(&self, dropdown: &WidgetRef, index: usize, text: &str) -> WidgetBuilder {
+        let mut text_widget = TextBuilder::new_with_style(STYLE_DROPDOWN_ITEM_TEXT.clone());
+        text_widget.set_name("dropdown_item_text");
+        text_widget.widget_ref().update(|state: &mut TextState| state.text = text.to_owned());
+
+        let mut item = WidgetBuilder::new("dropdown_item");
+        item.set_draw_state_with_style(RectState::new(), STYLE_DROPDOWN_ITEM.clone())
+            .enable_hover();
+        if self.selected == Some(index) {
+            item.add_prop(Property::Selected);
+        }
+        let dropdown = dropdown.clone();
+        item.add_handler(move |_: &ClickEvent, mut args: EventArgs| {
+            dropdown.event(DropdownInputEvent::Select(index));
+            *args.handled = true;
+        });
+        text_widget.layout().add(constraints![
+            align_left(&item).padding(10.0),
+            align_top(&item).padding(5.0),
+            align_bottom(&item).padding(5.0),
+            bound_right(&item).padding(10.0),
+        ]);
+        item.add_child(text_widget);
+        item
+    }
+
+    fn filter_items(&mut self, filter: &str, dropdown: &WidgetRef) {
+        if let Some(mut list) = self.list.clone() {
+            for child in list.children() {
+                list.remove_child(child);
+            }
+            let filter = filter.to_lowercase();
+            for (index, item) in self.items.iter().enumerate() {
+                if item.to_lowercase().contains(&filter) {
+                    list.add_child(self.build_item(dropdown, index, item));
+                }
+            }
+        }
+    }
+}
+
+impl EventHandler<DropdownInputEvent> for DropdownHandler {
+    fn handle(&mut self, event: &DropdownInputEvent, mut args: EventArgs) {
+        match *event {
+            DropdownInputEvent::Click => {
+                if args.widget.props().contains(&Property::Inactive) {
+                    return;
+                }
+                if let Some(popup) = self.popup.clone() {
+                    args.ui.close_popup(&popup);
+                } else {
+                    self.open_popup(&mut args);
+                }
+            }
+            DropdownInputEvent::Select(index) => {
+                self.selected = Some(index);
+                let value = self.items[index].clone();
+                {
+                    let value = value.clone();
+                    self.text.update(|state: &mut TextState| state.text = value);
+                }
+                if let Some(popup) = self.popup.clone() {
+                    args.ui.close_popup(&popup);
+                }
+                args.widget.event(DropdownSelected { index: index, value: value });
+            }
+            DropdownInputEvent::Filter(ref filter) => {
+                self.filter_items(filter, &args.widget);
+            }
+            DropdownInputEvent::PopupClosed => {
+                self.popup = None;
+                self.list = None;
+                args.widget.remove_prop(Property::Activated);
+            }
+        }
+    }
+}
+
+pub struct DropdownBuilder {
+    pub widget: WidgetBuilder,
+    text: WidgetBuilder,
+    items: Vec<String>,
+    selected: Option<usize>,
+    filter: bool,
+}
+
+widget_builder!(DropdownBuilder);
+
+impl Default for DropdownBuilder {
+    fn default() -> Self {
+        let mut widget = WidgetBuilder::new("dropdown");
+        widget
+            .set_draw_state_with_style(RectState::new(), STYLE_BUTTON.clone())
+            .enable_hover()
+            .make_focusable()
+            .click_on_activate_key();
+        widget.layout().add(constraints![
+            min_size(Size::new(150.0, 30.0)),
+            shrink(),
+        ]);
+
+        let style = style!(parent: STYLE_BUTTON_TEXT, TextStyle::Align: Align::Start);
+        let mut text = TextBuilder::new_with_style(style);
+        text.set_name("dropdown_text");
+        text.layout().add(constraints![
+            align_left(&widget).padding(10.0),
+            bound_right(&widget).padding(10.0),
+            bound_top(&widget).padding(5.0),
+            bound_bottom(&widget).padding(5.0),
+            center_vertical(&widget),
+        ]);
+
+        DropdownBuilder {
+            widget: widget,
+            text: text,
+            items: Vec::new(),
+            selected: None,
+            filter: false,
+        }
+    }
+}
+
+impl DropdownBuilder {
+    /// Creates a new, empty `DropdownBuilder`
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the options that can be selected
+    pub fn set_items<S: Into<String>>(&mut self, items: Vec<S>) -> &mut Self {
+        self.items = items.into_iter().map(|item| item.into()).collect();
+        self
+    }
+
+    /// Set the index of the initially selected option
+    pub fn set_selected(&mut self, index: usize) -> &mut Self {
+        self.selected = Some(index);
+        self
+    }
+
+    /// Show a text field above the options in the popup, that filters the
+    /// options to those containing the entered text
+    pub fn enable_filter(&mut self) -> &mut Self {
+        self.filter = true;
+        self
+    }
+
+    /// Callback function to be called when an option is selected
+    pub fn on_selected<F>(&mut self, callback: F) -> &mut Self
+        where F: Fn(&DropdownSelected, EventArgs) + 'static
+    {
+        self.widget.add_handler(callback);
+        self
+    }
+}
+
+impl Into<WidgetBuilder> for DropdownBuilder {
+    fn into(self) -> WidgetBuilder {
+        let DropdownBuilder { mut widget, text, items, selected, filter } = self;
+        if let Some(value) = selected.and_then(|index| items.get(index)) {
+            let value = value.clone();
+            text.widget_ref().update(|state: &mut TextState| state.text = value);
+        }
+        widget
+            .add_handler(DropdownHandler {
+                items: items,
+                selected: selected,
+                filter: filter,
+                text: text.widget_ref(),
+                popup: None,
+                list: None,
+            })
+            .add_handler(|_: &ClickEvent, mut args: EventArgs| {
+                args.widget.event(DropdownInputEvent::Click);
+                *args.handled = true;
+            });
+        widget.add_child(text);
+        widget
+    }
+}
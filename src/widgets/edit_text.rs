@@ -1,12 +1,18 @@
+use std::rc::Rc;
+use std::cell::RefCell;
+
 use cassowary::Constraint;
+use glutin;
 
 use layout::constraint::ConstraintBuilder;
 use layout::constraint::*;
 use widget::style::StyleUpdated;
-use widget::WidgetBuilder;
+use widget::{WidgetBuilder, WidgetRef};
 use widget::property::states::*;
+use widgets::validation::{self, Validator, InputFilter, ValidationHandler, ValidationEvent};
+use widgets::tooltip::Tooltip;
+use input::keyboard::WidgetReceivedCharacter;
 use ui::{WidgetAttachedEvent, WidgetDetachedEvent};
-use input::keyboard::{WidgetReceivedCharacter, KeyboardInputEvent};
 use draw::rect::{RectState, RectStyle};
 use draw::text::TextState;
 use event::{EventHandler, EventArgs};
@@ -14,30 +20,40 @@ use color::*;
 
 const BACKSPACE: char = '\u{8}';
 
-fn edit_text_handle_char(event: &WidgetReceivedCharacter, mut args: EventArgs) {
-    let &WidgetReceivedCharacter(char) = event;
-    let text = {
-        let bounds = args.widget.bounds();
-        let draw_state = args.widget.draw_state();
-        let text_draw_state = draw_state.downcast_ref::<TextState>().unwrap();
-        let mut text = text_draw_state.text.clone();
-        match char {
-            BACKSPACE => {
-                text.pop();
-            }
-            _ => {
-                text.push(char);
-                if !text_draw_state.text_fits(&text, bounds) {
+/// Edits the text as characters are typed, ignoring edits rejected by the filters
+struct EditTextCharHandler {
+    filters: Vec<InputFilter>,
+}
+
+impl EventHandler<WidgetReceivedCharacter> for EditTextCharHandler {
+    fn handle(&mut self, event: &WidgetReceivedCharacter, mut args: EventArgs) {
+        let &WidgetReceivedCharacter(char) = event;
+        let text = {
+            let bounds = args.widget.bounds();
+            let draw_state = args.widget.draw_state();
+            let text_draw_state = draw_state.downcast_ref::<TextState>().unwrap();
+            let mut text = text_draw_state.text.clone();
+            match char {
+                BACKSPACE => {
                     text.pop();
                 }
+                _ => {
+                    text.push(char);
+                    if !text_draw_state.text_fits(&text, bounds) {
+                        text.pop();
+                    }
+                }
             }
+            text
+        };
+        if char != BACKSPACE && !self.filters.iter().all(|filter| filter.accepts(&text)) {
+            return;
         }
-        text
-    };
-    args.widget.update(|state: &mut TextState| {
-        state.text = text.clone()
-    });
-    args.widget.event(TextUpdated(text.clone()));
+        args.widget.update(|state: &mut TextState| {
+            state.text = text.clone()
+        });
+        args.widget.event(TextUpdated(text.clone()));
+    }
 }
 
 pub struct TextUpdated(pub String);
@@ -49,31 +65,32 @@ pub fn text_change_handle(event: &TextUpdated, mut args: EventArgs) {
 pub struct EditTextBuilder {
     pub widget: WidgetBuilder,
     pub text_widget: WidgetBuilder,
+    filters: Vec<InputFilter>,
+    validators: Vec<Validator>,
+    error_labels: Vec<WidgetRef>,
+    error_tooltip: bool,
 }
 
 impl Default for EditTextBuilder {
     fn default() -> Self {
         let default_border = Some((1.0, GRAY_70));
         let focused_border = Some((1.0, BLUE));
+        let invalid_border = Some((2.0, RED));
         let rect_style = style!(
-            RectStyle::Border: selector!(default_border, FOCUSED: focused_border),
+            RectStyle::Border: selector!(default_border,
+                INVALID: invalid_border,
+                FOCUSED: focused_border),
             RectStyle::CornerRadius: Some(3.0));
         let mut widget = WidgetBuilder::new("edit_text");
         widget
             .set_draw_state_with_style(RectState::new(), rect_style)
-            .add_handler(|_: &WidgetAttachedEvent, args: EventArgs| {
-                args.ui.event(KeyboardInputEvent::AddFocusable(args.widget));
-            })
-            .add_handler(|_: &WidgetDetachedEvent, args: EventArgs| {
-                args.ui.event(KeyboardInputEvent::RemoveFocusable(args.widget));
-            })
+            .set_cursor(glutin::MouseCursor::Text)
             .make_focusable();
 
         let mut text_widget = WidgetBuilder::new("edit_text_text");
         text_widget
             .set_draw_state(TextState::default())
             .add_handler(TextUpdatedHandler::default())
-            .add_handler(edit_text_handle_char)
             .add_handler(text_change_handle);
 
         text_widget.layout().add(constraints![
@@ -85,6 +102,10 @@ impl Default for EditTextBuilder {
         EditTextBuilder {
             widget: widget,
             text_widget: text_widget,
+            filters: Vec::new(),
+            validators: Vec::new(),
+            error_labels: Vec::new(),
+            error_tooltip: false,
         }
     }
 }
@@ -101,12 +122,66 @@ impl EditTextBuilder {
         self.text_widget.add_handler(callback);
         self
     }
+
+    /// Add a filter that can reject edits as they are typed, eg. to only allow numbers
+    pub fn add_filter(&mut self, filter: InputFilter) -> &mut Self {
+        self.filters.push(filter);
+        self
+    }
+
+    /// Add a validator that checks the text whenever it changes. If any validator fails,
+    /// the widget gets the `Invalid` property and a `ValidityChanged` event is emitted.
+    /// Any `FormBuilder` containing the widget will be invalid until the text is valid.
+    pub fn add_validator(&mut self, validator: Validator) -> &mut Self {
+        self.validators.push(validator);
+        self
+    }
+
+    /// Creates a text widget that shows the error message of the first validator
+    /// that failed, or nothing if the text is valid, to be laid out near this widget
+    pub fn error_label(&mut self) -> WidgetBuilder {
+        let label = validation::build_error_label();
+        self.error_labels.push(label.widget_ref());
+        label
+    }
+
+    /// Show the error message of the first validator that failed in a tooltip
+    pub fn show_errors_in_tooltip(&mut self) -> &mut Self {
+        self.error_tooltip = true;
+        self
+    }
 }
 
 widget_builder!(EditTextBuilder);
 
 impl Into<WidgetBuilder> for EditTextBuilder {
     fn into(mut self) -> WidgetBuilder {
+        self.text_widget.add_handler(EditTextCharHandler { filters: self.filters });
+        if !self.validators.is_empty() {
+            let message = Rc::new(RefCell::new(None));
+            if self.error_tooltip {
+                let message = message.clone();
+                self.widget.set_tooltip(Tooltip::widget(move || validation::build_error_tooltip(&message)));
+            }
+            let handler = ValidationHandler {
+                field: self.widget.widget_ref(),
+                validators: self.validators,
+                error_labels: self.error_labels,
+                message: message,
+                validity: None,
+            };
+            self.text_widget
+                .add_handler(handler)
+                .add_handler(|_: &WidgetAttachedEvent, args: EventArgs| {
+                    args.widget.event(ValidationEvent::Attached);
+                })
+                .add_handler(|_: &WidgetDetachedEvent, args: EventArgs| {
+                    args.widget.event(ValidationEvent::Detached);
+                })
+                .add_handler(|event: &TextUpdated, args: EventArgs| {
+                    args.widget.event(ValidationEvent::Text(event.0.clone()));
+                });
+        }
         self.widget.add_child(self.text_widget);
         self.widget
     }
@@ -0,0 +1,119 @@
+//! A container that tracks whether the validated fields within it are valid.
+
+use event::{EventHandler, EventArgs};
+use widget::{WidgetBuilder, WidgetRef};
+use widget::property::Property;
+use widgets::validation::{FieldValidity, FieldDetached};
+
+/// Emitted by a form when it becomes valid or invalid
+#[derive(Debug, Copy, Clone)]
+pub struct FormValidityChanged(pub bool);
+
+enum FormEvent {
+    Validity(WidgetRef, bool),
+    Detached(WidgetRef),
+}
+
+struct FormHandler {
+    fields: Vec<(WidgetRef, bool)>,
+    submit_buttons: Vec<WidgetRef>,
+    valid: bool,
+}
+
+impl FormHandler {
+    fn update_buttons(&mut self) {
+        for button in &mut self.submit_buttons {
+            if self.valid {
+                button.remove_prop(Property::Inactive);
+            } else {
+                button.add_prop(Property::Inactive);
+            }
+        }
+    }
+}
+
+impl EventHandler<FormEvent> for FormHandler {
+    fn handle(&mut self, event: &FormEvent, args: EventArgs) {
+        match *event {
+            FormEvent::Validity(ref field, valid) => {
+                match self.fields.iter().position(|&(ref other, _)| other == field) {
+                    Some(index) => self.fields[index].1 = valid,
+                    None => self.fields.push((field.clone(), valid)),
+                }
+            }
+            FormEvent::Detached(ref field) => {
+                self.fields.retain(|&(ref other, _)| other != field);
+            }
+        }
+        let form_valid = self.fields.iter().all(|&(_, valid)| valid);
+        if form_valid != self.valid {
+            self.valid = form_valid;
+            self.update_buttons();
+            args.widget.event(FormValidityChanged(form_valid));
+        }
+    }
+}
+
+/// A container for validated fields, see `EditTextBuilder::add_validator`.
+/// The form is valid only if every field within it is valid,
+/// while it's invalid, its submit buttons get the `Inactive` property.
+pub struct FormBuilder {
+    pub widget: WidgetBuilder,
+    submit_buttons: Vec<WidgetRef>,
+}
+
+widget_builder!(FormBuilder);
+
+impl Default for FormBuilder {
+    fn default() -> Self {
+        FormBuilder {
+            widget: WidgetBuilder::new("form"),
+            submit_buttons: Vec::new(),
+        }
+    }
+}
+
+impl FormBuilder {
+    /// Creates a new, empty `FormBuilder`
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Disable a widget, by setting the `Inactive` property, while the form is invalid
+    pub fn add_submit_button(&mut self, button: &WidgetBuilder) -> &mut Self {
+        self.submit_buttons.push(button.widget_ref());
+        self
+    }
+
+    /// Callback function to be called when the form becomes valid or invalid
+    pub fn on_validity_changed<F>(&mut self, callback: F) -> &mut Self
+        where F: Fn(bool, &mut EventArgs) + 'static
+    {
+        self.widget.add_handler(move |event: &FormValidityChanged, mut args: EventArgs| {
+            callback(event.0, &mut args);
+        });
+        self
+    }
+}
+
+impl Into<WidgetBuilder> for FormBuilder {
+    fn into(self) -> WidgetBuilder {
+        let mut widget = self.widget;
+        let handler = FormHandler {
+            fields: Vec::new(),
+            submit_buttons: self.submit_buttons,
+            valid: true,
+        };
+        widget
+            .add_handler(handler)
+            .add_handler(|event: &FieldValidity, mut args: EventArgs| {
+                args.widget.event(FormEvent::Validity(event.0.clone(), event.1));
+                *args.handled = true;
+            })
+            .add_handler(|event: &FieldDetached, mut args: EventArgs| {
+                args.widget.event(FormEvent::Detached(event.0.clone()));
+                *args.handled = true;
+            });
+        widget
+    }
+}
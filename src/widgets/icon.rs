@@ -0,0 +1,37 @@
+use cassowary::strength::*;
+
+use widget::WidgetBuilder;
+use widget::property::states::*;
+use draw::icon::{IconState, IconStyle};
+use layout::constraint::*;
+use color::*;
+
+static COLOR_ICON_INACTIVE: Color = GRAY_70;
+
+lazy_static! {
+    pub static ref STYLE_ICON: Vec<IconStyle> = {
+        style!(IconStyle::Tint: selector!(None, INACTIVE: Some(COLOR_ICON_INACTIVE)))
+    };
+}
+
+/// An SVG icon from the assets/images directory. It's the size of the document by default,
+/// but can be sized with other constraints, since it's drawn sharply at any size.
+/// Icons are drawn with their own colors, or tinted through `IconStyle::Tint`.
+#[derive(Debug, Copy, Clone)]
+pub struct IconBuilder;
+
+impl IconBuilder {
+    /// An icon that's gray while the widget is inactive
+    #[cfg_attr(feature = "cargo-clippy", allow(new_ret_no_self))]
+    pub fn new(icon: &str) -> WidgetBuilder {
+        IconBuilder::new_with_style(icon, STYLE_ICON.clone())
+    }
+    pub fn new_with_style(icon: &str, style: Vec<IconStyle>) -> WidgetBuilder {
+        let icon_draw_state = IconState::new(icon);
+        let icon_size = icon_draw_state.measure();
+        let mut widget = WidgetBuilder::new("icon");
+        widget.set_draw_state_with_style(icon_draw_state, style);
+        widget.layout().add(size(icon_size).strength(STRONG));
+        widget
+    }
+}
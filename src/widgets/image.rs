@@ -1,5 +1,5 @@
 use widget::WidgetBuilder;
-use draw::image::ImageState;
+use draw::image::{ImageState, ImageStyle};
 use layout::constraint::*;
 
 #[derive(Debug, Copy, Clone)]
@@ -15,4 +15,11 @@ impl ImageBuilder {
         widget.layout().add(size(image_size));
         widget
     }
+    /// An image drawn with a style, that isn't sized to the image, so it can be
+    /// fitted, tiled or drawn as a nine-patch in bounds set by other constraints
+    pub fn new_with_style(file: &str, style: Vec<ImageStyle>) -> WidgetBuilder {
+        let mut widget = WidgetBuilder::new("image");
+        widget.set_draw_state_with_style(ImageState::new(file), style);
+        widget
+    }
 }
@@ -0,0 +1,627 @@
+//! Context menus and menu bars, with nested submenus.
+//!
+//! A `Menu` describes the items of a menu, the menu widgets are created from it
+//! every time the menu is opened, and shown as popups in the overlay.
+
+use std::rc::Rc;
+use std::cell::Cell;
+
+use glutin;
+use cassowary::strength::*;
+
+use layout::constraint::*;
+use layout::linear_layout::{LinearLayoutSettings, Orientation, ItemAlignment};
+use event::{EventHandler, EventArgs};
+use widget::{WidgetBuilder, WidgetRef};
+use widget::property::Property;
+use widget::property::states::*;
+use widgets::text::TextBuilder;
+use input::mouse::{ClickEvent, MouseMoved, MouseOverEvent, WidgetMouseButton};
+use input::keyboard::{KeyboardInputEvent, WidgetKeyboardInput};
+use overlay::PopupClosed;
+use draw::rect::{RectState, RectStyle};
+use draw::text::TextStyle;
+use geometry::{Point, Size};
+use color::*;
+use app::App;
+
+static COLOR_MENU_ITEM_HIGHLIGHT: Color = BLUE_HIGHLIGHT;
+static COLOR_MENU_TEXT_INACTIVE: Color = GRAY_70;
+
+lazy_static! {
+    pub static ref STYLE_MENU: Vec<RectStyle> = {
+        style!(
+            RectStyle::BackgroundColor: WHITE,
+            RectStyle::Border: Some((1.0, GRAY_40))
+        )
+    };
+    pub static ref STYLE_MENU_ITEM: Vec<RectStyle> = {
+        style!(RectStyle::BackgroundColor: selector!(TRANSPARENT,
+            SELECTED: COLOR_MENU_ITEM_HIGHLIGHT))
+    };
+    pub static ref STYLE_MENU_ITEM_CHECK: Vec<RectStyle> = {
+        style!(RectStyle::BackgroundColor: selector!(TRANSPARENT,
+            ACTIVATED_SELECTED: WHITE,
+            ACTIVATED: GRAY_20))
+    };
+    pub static ref STYLE_MENU_SEPARATOR: Vec<RectStyle> = {
+        style!(RectStyle::BackgroundColor: GRAY_80)
+    };
+    pub static ref STYLE_MENU_TEXT: Vec<TextStyle> = {
+        style!(
+            TextStyle::TextColor: selector!(BLACK,
+                INACTIVE: COLOR_MENU_TEXT_INACTIVE,
+                SELECTED: WHITE),
+            TextStyle::FontSize: 18.0
+        )
+    };
+    pub static ref STYLE_MENU_BAR: Vec<RectStyle> = {
+        style!(RectStyle::BackgroundColor: GRAY_90)
+    };
+    pub static ref STYLE_MENU_BAR_ITEM: Vec<RectStyle> = {
+        style!(RectStyle::BackgroundColor: selector!(TRANSPARENT,
+            ACTIVATED: GRAY_70,
+            MOUSEOVER: GRAY_80))
+    };
+}
+
+/// Passed to the callbacks of a menu item when it is activated
+#[derive(Debug, Copy, Clone)]
+pub struct MenuItemActivated {
+    /// For checkable items, whether the item is checked, after being toggled by the activation
+    pub checked: Option<bool>,
+}
+
+enum MenuItemKind {
+    Action,
+    Submenu(Rc<Menu>),
+    Separator,
+}
+
+/// An entry in a `Menu`
+pub struct MenuItem {
+    kind: MenuItemKind,
+    text: String,
+    accelerator: Option<String>,
+    enabled: bool,
+    checked: Option<Rc<Cell<bool>>>,
+    callbacks: Vec<Box<Fn(&MenuItemActivated, &mut EventArgs)>>,
+}
+
+impl MenuItem {
+    /// Creates a new menu item with a text label
+    pub fn new(text: &str) -> Self {
+        MenuItem {
+            kind: MenuItemKind::Action,
+            text: text.to_owned(),
+            accelerator: None,
+            enabled: true,
+            checked: None,
+            callbacks: Vec::new(),
+        }
+    }
+    fn separator() -> Self {
+        let mut item = MenuItem::new("");
+        item.kind = MenuItemKind::Separator;
+        item
+    }
+    /// Set a label showing the key combination for this item, eg. "Ctrl+S",
+    /// the key combination itself needs to be handled separately
+    pub fn set_accelerator(&mut self, accelerator: &str) -> &mut Self {
+        self.accelerator = Some(accelerator.to_owned());
+        self
+    }
+    /// Disabled items are grayed out, and can't be activated
+    pub fn set_enabled(&mut self, enabled: bool) -> &mut Self {
+        self.enabled = enabled;
+        self
+    }
+    /// Make the item show a check mark, that is toggled when the item is activated
+    pub fn set_checkable(&mut self, checked: bool) -> &mut Self {
+        self.checked = Some(Rc::new(Cell::new(checked)));
+        self
+    }
+    /// Callback function to be called when the item is activated
+    pub fn on_activate<F>(&mut self, callback: F) -> &mut Self
+        where F: Fn(&MenuItemActivated, &mut EventArgs) + 'static
+    {
+        self.callbacks.push(Box::new(callback));
+        self
+    }
+    /// Send an event to the root widget when the item is activated
+    pub fn set_event<E: Clone + 'static>(&mut self, event: E) -> &mut Self {
+        self.on_activate(move |_, args| {
+            args.ui.event(event.clone());
+        })
+    }
+    fn is_selectable(&self) -> bool {
+        match self.kind {
+            MenuItemKind::Separator => false,
+            _ => self.enabled,
+        }
+    }
+}
+
+/// The list of items in a menu, used to create context menus, menu bar menus and submenus
+#[derive(Default)]
+pub struct Menu {
+    items: Vec<MenuItem>,
+}
+
+impl Menu {
+    /// Creates a new, empty `Menu`
+    pub fn new() -> Self {
+        Self::default()
+    }
+    pub fn add_item(&mut self, item: MenuItem) -> &mut Self {
+        self.items.push(item);
+        self
+    }
+    /// Add a line separating groups of items
+    pub fn add_separator(&mut self) -> &mut Self {
+        self.items.push(MenuItem::separator());
+        self
+    }
+    /// Add an item that opens a nested menu when hovered over or activated
+    pub fn add_submenu(&mut self, text: &str, submenu: Menu) -> &mut Self {
+        let mut item = MenuItem::new(text);
+        item.kind = MenuItemKind::Submenu(Rc::new(submenu));
+        self.items.push(item);
+        self
+    }
+}
+
+#[derive(Debug, Copy, Clone)]
+enum MenuInputEvent {
+    Highlight(usize),
+    HighlightFirst,
+    Activate(usize),
+    Key(glutin::VirtualKeyCode),
+    SubmenuClosed,
+}
+
+/// Handles the input to a single open menu popup, and opens it's submenus
+struct MenuHandler {
+    menu: Rc<Menu>,
+    /// The outermost menu of a chain of submenus, closing it closes all it's submenus
+    root: WidgetRef,
+    /// The widgets for each item, or `None` for separators
+    items: Vec<Option<WidgetRef>>,
+    highlighted: Option<usize>,
+    submenu: Option<(usize, WidgetRef)>,
+}
+
+impl MenuHandler {
+    fn highlight(&mut self, index: Option<usize>) {
+        if index == self.highlighted {
+            return;
+        }
+        if let Some(mut item) = self.highlighted.and_then(|index| self.items[index].clone()) {
+            item.remove_prop(Property::Selected);
+        }
+        self.highlighted = index;
+        if let Some(mut item) = index.and_then(|index| self.items[index].clone()) {
+            item.add_prop(Property::Selected);
+        }
+    }
+    /// Find the next selectable item, forwards or backwards from the highlighted item, wrapping around
+    fn next_selectable(&self, forward: bool) -> Option<usize> {
+        let len = self.menu.items.len();
+        if len == 0 {
+            return None;
+        }
+        let start = self.highlighted.unwrap_or(if forward { len - 1 } else { 0 });
+        (1..len + 1)
+            .map(|offset| if forward { (start + offset) % len } else { (start + len - offset) % len })
+            .find(|&index| self.menu.items[index].is_selectable())
+    }
+    fn close_submenu(&mut self, args: &mut EventArgs) {
+        if let Some((_, submenu)) = self.submenu.take() {
+            args.ui.close_popup(&submenu);
+        }
+    }
+    /// Opens the submenu of an item, if it has one and it isn't already open,
+    /// closing any other open submenu. Returns the submenu.
+    fn open_submenu(&mut self, index: usize, args: &mut EventArgs) -> Option<WidgetRef> {
+        if let Some((open_index, ref submenu)) = self.submenu {
+            if open_index == index {
+                return Some(submenu.clone());
+            }
+        }
+        self.close_submenu(args);
+        if let MenuItemKind::Submenu(ref submenu) = self.menu.items[index].kind {
+            let item = self.items[index].clone().unwrap();
+            let mut popup = build_menu(submenu.clone(), Some(self.root.clone()));
+            popup.layout().add(constraints![
+                to_right_of(&item).strength(STRONG),
+                align_top(&item).strength(STRONG),
+            ]);
+            let parent = args.widget.clone();
+            popup.add_handler(move |_: &PopupClosed, _: EventArgs| {
+                parent.event(MenuInputEvent::SubmenuClosed);
+            });
+            let popup = args.ui.open_popup(popup, Some(item));
+            self.submenu = Some((index, popup.clone()));
+            Some(popup)
+        } else {
+            None
+        }
+    }
+    /// Open the submenu of the highlighted item, and move keyboard focus to it
+    fn enter_submenu(&mut self, index: usize, args: &mut EventArgs) {
+        if let Some(submenu) = self.open_submenu(index, args) {
+            args.ui.event(KeyboardInputEvent::FocusChange(Some(submenu.clone())));
+            submenu.event(MenuInputEvent::HighlightFirst);
+        }
+    }
+    fn activate(&mut self, index: usize, args: &mut EventArgs) {
+        let menu = self.menu.clone();
+        let item = &menu.items[index];
+        if !item.is_selectable() {
+            return;
+        }
+        match item.kind {
+            MenuItemKind::Submenu(_) => {
+                self.enter_submenu(index, args);
+            }
+            MenuItemKind::Action => {
+                let checked = item.checked.as_ref().map(|checked| {
+                    checked.set(!checked.get());
+                    checked.get()
+                });
+                let event = MenuItemActivated { checked: checked };
+                for callback in &item.callbacks {
+                    callback(&event, args);
+                }
+                args.ui.close_popup(&self.root);
+            }
+            MenuItemKind::Separator => (),
+        }
+    }
+}
+
+impl EventHandler<MenuInputEvent> for MenuHandler {
+    fn handle(&mut self, event: &MenuInputEvent, mut args: EventArgs) {
+        match *event {
+            MenuInputEvent::Highlight(index) => {
+                if self.menu.items[index].is_selectable() {
+                    self.highlight(Some(index));
+                    if self.open_submenu(index, &mut args).is_none() {
+                        self.close_submenu(&mut args);
+                    }
+                }
+            }
+            MenuInputEvent::HighlightFirst => {
+                self.highlighted = None;
+                let first = self.next_selectable(true);
+                self.highlight(first);
+            }
+            MenuInputEvent::Activate(index) => {
+                self.activate(index, &mut args);
+            }
+            MenuInputEvent::Key(key) => {
+                match key {
+                    glutin::VirtualKeyCode::Down => {
+                        let next = self.next_selectable(true);
+                        self.highlight(next);
+                    }
+                    glutin::VirtualKeyCode::Up => {
+                        let next = self.next_selectable(false);
+                        self.highlight(next);
+                    }
+                    glutin::VirtualKeyCode::Right => {
+                        if let Some(index) = self.highlighted {
+                            self.enter_submenu(index, &mut args);
+                        }
+                    }
+                    glutin::VirtualKeyCode::Left => {
+                        // closing a submenu restores the focus to the menu that opened it
+                        if args.widget != self.root {
+                            args.ui.close_popup(&args.widget);
+                        }
+                    }
+                    glutin::VirtualKeyCode::Return | glutin::VirtualKeyCode::Space => {
+                        if let Some(index) = self.highlighted {
+                            self.activate(index, &mut args);
+                        }
+                    }
+                    _ => (),
+                }
+            }
+            MenuInputEvent::SubmenuClosed => {
+                self.submenu = None;
+            }
+        }
+    }
+}
+
+fn build_menu_item(item: &MenuItem, index: usize, menu: &WidgetBuilder) -> WidgetBuilder {
+    let mut widget = WidgetBuilder::new("menu_item");
+    if let MenuItemKind::Separator = item.kind {
+        widget.set_draw_state_with_style(RectState::new(), STYLE_MENU_SEPARATOR.clone());
+        widget.layout().add(height(1.0));
+        return widget;
+    }
+    widget.set_draw_state_with_style(RectState::new(), STYLE_MENU_ITEM.clone());
+    if !item.enabled {
+        widget.add_prop(Property::Inactive);
+    }
+    if item.checked.as_ref().map_or(false, |checked| checked.get()) {
+        widget.add_prop(Property::Activated);
+    }
+    {
+        let menu = menu.widget_ref();
+        widget.add_handler(move |_: &ClickEvent, mut args: EventArgs| {
+            menu.event(MenuInputEvent::Activate(index));
+            *args.handled = true;
+        });
+    }
+    {
+        let menu = menu.widget_ref();
+        widget.add_handler(move |event: &MouseOverEvent, _: EventArgs| {
+            if let MouseOverEvent::Over = *event {
+                menu.event(MenuInputEvent::Highlight(index));
+            }
+        });
+    }
+
+    if item.checked.is_some() {
+        let mut check = WidgetBuilder::new("menu_item_check");
+        check.set_draw_state_with_style(RectState::new(), STYLE_MENU_ITEM_CHECK.clone());
+        check.layout().add(constraints![
+            size(Size::new(8.0, 8.0)),
+            align_left(&widget).padding(11.0),
+            center_vertical(&widget),
+        ]);
+        widget.add_child(check);
+    }
+
+    let style = style!(parent: STYLE_MENU_TEXT, TextStyle::Text: item.text.clone());
+    let mut text = TextBuilder::new_with_style(style);
+    text.set_name("menu_item_text");
+    text.layout().add(constraints![
+        align_left(&widget).padding(30.0),
+        align_top(&widget).padding(4.0),
+        align_bottom(&widget).padding(4.0),
+    ]);
+
+    let shortcut = match item.kind {
+        MenuItemKind::Submenu(_) => Some(">".to_owned()),
+        _ => item.accelerator.clone(),
+    };
+    if let Some(shortcut) = shortcut {
+        let style = style!(parent: STYLE_MENU_TEXT, TextStyle::Text: shortcut);
+        let mut shortcut = TextBuilder::new_with_style(style);
+        shortcut.set_name("menu_item_accelerator");
+        shortcut.layout().add(constraints![
+            to_right_of(&text).padding(30.0),
+            align_right(&widget).padding(10.0),
+            center_vertical(&widget),
+        ]);
+        widget.add_child(text);
+        widget.add_child(shortcut);
+    } else {
+        text.layout().add(bound_right(&widget).padding(30.0));
+        widget.add_child(text);
+    }
+    widget
+}
+
+/// Creates the popup widget for a menu, `root` is the outermost menu if this is a submenu
+fn build_menu(menu: Rc<Menu>, root: Option<WidgetRef>) -> WidgetBuilder {
+    let mut layout_settings = LinearLayoutSettings::new(Orientation::Vertical);
+    layout_settings.item_align = ItemAlignment::Fill;
+    layout_settings.padding = 4.0;
+    let mut widget = WidgetBuilder::new("menu");
+    widget
+        .set_draw_state_with_style(RectState::new(), STYLE_MENU.clone())
+        .make_focusable()
+        .linear_layout(layout_settings);
+    widget.layout().add(constraints![
+        min_width(120.0),
+        shrink(),
+    ]);
+    widget.add_handler(|event: &WidgetKeyboardInput, args: EventArgs| {
+        if event.0.state == glutin::ElementState::Pressed {
+            if let Some(key) = event.0.virtual_keycode {
+                args.widget.event(MenuInputEvent::Key(key));
+            }
+        }
+    });
+
+    let mut items = Vec::new();
+    for (index, item) in menu.items.iter().enumerate() {
+        let item_widget = build_menu_item(item, index, &widget);
+        items.push(match item.kind {
+            MenuItemKind::Separator => None,
+            _ => Some(item_widget.widget_ref()),
+        });
+        widget.add_child(item_widget);
+    }
+    let root = root.unwrap_or_else(|| widget.widget_ref());
+    widget.add_handler(MenuHandler {
+        menu: menu,
+        root: root,
+        items: items,
+        highlighted: None,
+        submenu: None,
+    });
+    widget
+}
+
+/// Open a menu as a popup, and give it keyboard focus
+fn open_menu(menu: WidgetBuilder, anchor: Option<WidgetRef>, args: &mut EventArgs) -> WidgetRef {
+    let menu = args.ui.open_popup(menu, anchor);
+    args.ui.event(KeyboardInputEvent::FocusChange(Some(menu.clone())));
+    menu
+}
+
+enum ContextMenuEvent {
+    MouseMoved(Point),
+    Open(Rc<Menu>),
+}
+
+/// Opens context menus at the position of the mouse
+struct ContextMenuHandler {
+    mouse: Point,
+}
+
+impl EventHandler<ContextMenuEvent> for ContextMenuHandler {
+    fn handle(&mut self, event: &ContextMenuEvent, mut args: EventArgs) {
+        match *event {
+            ContextMenuEvent::MouseMoved(point) => {
+                self.mouse = point;
+            }
+            ContextMenuEvent::Open(ref menu) => {
+                let mut menu = build_menu(menu.clone(), None);
+                menu.layout().edit_left().set(self.mouse.x).strength(STRONG);
+                menu.layout().edit_top().set(self.mouse.y).strength(STRONG);
+                open_menu(menu, None, &mut args);
+            }
+        }
+    }
+}
+
+impl WidgetBuilder {
+    /// Open a menu at the mouse position when this widget is right clicked
+    pub fn set_context_menu(&mut self, menu: Menu) -> &mut Self {
+        let menu = Rc::new(menu);
+        self.add_handler(move |event: &WidgetMouseButton, mut args: EventArgs| {
+            if let WidgetMouseButton(glutin::ElementState::Pressed, glutin::MouseButton::Right) = *event {
+                args.ui.event(ContextMenuEvent::Open(menu.clone()));
+                *args.handled = true;
+            }
+        })
+    }
+}
+
+impl App {
+    /// Add handlers that open the context menus set with `WidgetBuilder::set_context_menu`
+    pub fn add_context_menu_handlers(&mut self) {
+        self.add_handler(|event: &MouseMoved, args: EventArgs| {
+            args.widget.event(ContextMenuEvent::MouseMoved(event.0));
+        });
+        self.add_handler(ContextMenuHandler { mouse: Point::zero() });
+    }
+}
+
+enum MenuBarEvent {
+    Click(WidgetRef, Rc<Menu>),
+    Hover(WidgetRef, Rc<Menu>),
+    Closed(WidgetRef),
+}
+
+/// Tracks which of the menus in a menu bar is open
+#[derive(Default)]
+struct MenuBarHandler {
+    open: Option<(WidgetRef, WidgetRef)>,
+}
+
+impl MenuBarHandler {
+    fn close(&mut self, args: &mut EventArgs) {
+        if let Some((mut title, menu)) = self.open.take() {
+            title.remove_prop(Property::Activated);
+            args.ui.close_popup(&menu);
+        }
+    }
+    fn open(&mut self, mut title: WidgetRef, menu: Rc<Menu>, args: &mut EventArgs) {
+        let mut menu = build_menu(menu, None);
+        menu.layout().add(constraints![
+            align_below(&title).strength(STRONG),
+            align_left(&title).strength(STRONG),
+        ]);
+        let bar = args.widget.clone();
+        menu.add_handler(move |_: &PopupClosed, args: EventArgs| {
+            bar.event(MenuBarEvent::Closed(args.widget));
+        });
+        let menu = open_menu(menu, Some(title.clone()), args);
+        menu.event(MenuInputEvent::HighlightFirst);
+        title.add_prop(Property::Activated);
+        self.open = Some((title, menu));
+    }
+}
+
+impl EventHandler<MenuBarEvent> for MenuBarHandler {
+    fn handle(&mut self, event: &MenuBarEvent, mut args: EventArgs) {
+        match *event {
+            MenuBarEvent::Click(ref title, ref menu) => {
+                let was_open = self.open.as_ref().map_or(false, |&(ref open_title, _)| open_title == title);
+                self.close(&mut args);
+                if !was_open {
+                    self.open(title.clone(), menu.clone(), &mut args);
+                }
+            }
+            MenuBarEvent::Hover(ref title, ref menu) => {
+                // once a menu is open, hovering over other titles switches between menus
+                let switch = self.open.as_ref().map_or(false, |&(ref open_title, _)| open_title != title);
+                if switch {
+                    self.close(&mut args);
+                    self.open(title.clone(), menu.clone(), &mut args);
+                }
+            }
+            MenuBarEvent::Closed(ref menu) => {
+                let closed = self.open.as_ref().map_or(false, |&(_, ref open_menu)| open_menu == menu);
+                if closed {
+                    self.close(&mut args);
+                }
+            }
+        }
+    }
+}
+
+/// A horizontal bar of menu titles, each of which opens a menu when clicked
+pub struct MenuBarBuilder {
+    pub widget: WidgetBuilder,
+}
+
+widget_wrapper!(MenuBarBuilder);
+
+impl Default for MenuBarBuilder {
+    fn default() -> Self {
+        let mut widget = WidgetBuilder::new("menu_bar");
+        widget
+            .set_draw_state_with_style(RectState::new(), STYLE_MENU_BAR.clone())
+            .add_handler(MenuBarHandler::default())
+            .linear_layout(LinearLayoutSettings::new(Orientation::Horizontal));
+        widget.layout().add(shrink_vertical());
+        MenuBarBuilder { widget: widget }
+    }
+}
+
+impl MenuBarBuilder {
+    /// Creates a new, empty `MenuBarBuilder`
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a title to the menu bar, that opens `menu` when clicked
+    pub fn add_menu(&mut self, title: &str, menu: Menu) -> &mut Self {
+        let menu = Rc::new(menu);
+        let mut title_widget = WidgetBuilder::new("menu_bar_item");
+        title_widget
+            .set_draw_state_with_style(RectState::new(), STYLE_MENU_BAR_ITEM.clone())
+            .enable_hover();
+        {
+            let (bar, menu) = (self.widget.widget_ref(), menu.clone());
+            title_widget.add_handler(move |_: &ClickEvent, mut args: EventArgs| {
+                bar.event(MenuBarEvent::Click(args.widget.clone(), menu.clone()));
+                *args.handled = true;
+            });
+        }
+        {
+            let bar = self.widget.widget_ref();
+            title_widget.add_handler(move |event: &MouseOverEvent, args: EventArgs| {
+                if let MouseOverEvent::Over = *event {
+                    bar.event(MenuBarEvent::Hover(args.widget.clone(), menu.clone()));
+                }
+            });
+        }
+        let style = style!(parent: STYLE_MENU_TEXT, TextStyle::Text: title.to_owned());
+        let mut text = TextBuilder::new_with_style(style);
+        text.set_name("menu_bar_item_text");
+        text.layout().add(match_layout(&title_widget).padding(6.0));
+        title_widget.add_child(text);
+        self.widget.add_child(title_widget);
+        self
+    }
+}
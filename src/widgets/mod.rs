@@ -1,11 +1,26 @@
 //! Includes standard bundled widgets.
 
 pub mod button;
+pub mod checkbox;
+pub mod radio;
+pub mod switch;
+pub mod tabs;
+pub mod split;
 pub mod scroll;
 pub mod drag;
+pub mod drag_drop;
+pub mod dropdown;
+pub mod menu;
 pub mod list;
 pub mod slider;
+pub mod progress;
 pub mod edit_text;
+pub mod spin_box;
+pub mod validation;
+pub mod form;
 pub mod image;
+pub mod icon;
+pub mod pixel_buffer;
 pub mod glcanvas;
 pub mod text;
+pub mod tooltip;
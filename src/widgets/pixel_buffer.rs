@@ -0,0 +1,71 @@
+//! A widget showing an image from a buffer of pixels that can be replaced while the app runs,
+//! for content like video previews or generated visualizations.
+
+use cassowary::strength::*;
+
+use layout::constraint::*;
+use event::EventArgs;
+use ui::{WidgetAttachedEvent, WidgetDetachedEvent};
+use widget::WidgetBuilder;
+use draw::image::ImageState;
+use resources::resources;
+use geometry::Size;
+
+/// Send to a pixel buffer widget to replace its pixels, with straight, not premultiplied, RGBA pixels,
+/// row by row from the top left. The size of the buffer can change between frames.
+#[derive(Debug, Clone)]
+pub struct SetPixels {
+    pub width: u32,
+    pub height: u32,
+    pub pixels: Vec<u8>,
+}
+
+/// An image whose pixels are set with `SetPixels`. The pixels are stored as an image in the resources,
+/// named by `PixelBufferBuilder::image_name`, and updated in place, so new frames only upload the pixels.
+/// The image is stretched to fill the widget, which is the size of the buffer by default.
+#[derive(Debug, Copy, Clone)]
+pub struct PixelBufferBuilder;
+
+impl PixelBufferBuilder {
+    /// Create a pixel buffer with the given size, initially transparent
+    #[cfg_attr(feature = "cargo-clippy", allow(new_ret_no_self))]
+    pub fn new(width: u32, height: u32) -> WidgetBuilder {
+        let mut widget = WidgetBuilder::new("pixel_buffer");
+        let image_name = PixelBufferBuilder::image_name(&widget);
+        put_blank_image(&image_name, width, height);
+        widget.set_draw_state(ImageState::new(&image_name));
+        widget.layout().add(size(Size::new(width as f32, height as f32)).strength(WEAK));
+        // the image is deleted when the widget is removed, and added again if it's reattached
+        let attached_image = image_name.clone();
+        widget.add_handler(move |_: &WidgetAttachedEvent, _: EventArgs| {
+            if !resources().images.contains_key(&attached_image) {
+                put_blank_image(&attached_image, width, height);
+            }
+        });
+        let detached_image = image_name.clone();
+        widget.add_handler(move |_: &WidgetDetachedEvent, _: EventArgs| {
+            resources().delete_image(&detached_image);
+        });
+        widget.add_handler(move |event: &SetPixels, args: EventArgs| {
+            let result = resources().update_image_rgba(&image_name, event.width, event.height, &event.pixels).map(|_| ());
+            match result {
+                Ok(()) => args.ui.redraw(),
+                Err(err) => warn!("dropped a {}x{} frame with {} bytes of pixels: {}",
+                                  event.width, event.height, event.pixels.len(), err),
+            }
+        });
+        widget
+    }
+    /// The name of the image in the resources that holds the pixels of a pixel buffer widget
+    pub fn image_name(widget: &WidgetBuilder) -> String {
+        format!("pixel_buffer_{}", widget.id().0)
+    }
+}
+
+/// Add a transparent image
+fn put_blank_image(name: &str, width: u32, height: u32) {
+    let pixels = vec![0; width as usize * height as usize * 4];
+    if let Err(err) = resources().put_image_rgba(name, width, height, &pixels) {
+        warn!("failed to add a {}x{} pixel buffer: {}", width, height, err);
+    }
+}
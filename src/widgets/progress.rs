@@ -0,0 +1,321 @@
+//! Progress indicators, a progress bar for operations with known progress,
+//! and an animated spinner for operations without.
+
+use std::ops::Range;
+use std::time::Instant;
+
+use cassowary::strength::*;
+
+use layout::constraint::*;
+use layout::LayoutUpdated;
+use event::{EventHandler, EventArgs};
+use widget::{WidgetBuilder, WidgetRef};
+use draw::rect::{RectState, RectStyle};
+use draw::spinner::{SpinnerState, SpinnerStyle};
+use geometry::{Size, Point, Rect, RectExt};
+use ui::WidgetAttachedEvent;
+use overlay::is_descendant;
+use color::*;
+use app::{App, FrameEvent, seconds};
+
+/// Time taken for the spinner highlight to go around once, in seconds
+const SPINNER_PERIOD: f32 = 1.0;
+
+lazy_static! {
+    pub static ref STYLE_PROGRESS_BAR: Vec<RectStyle> = {
+        style!(
+            RectStyle::BackgroundColor: GRAY_80,
+            RectStyle::Border: Some((1.0, GRAY_60)),
+            RectStyle::CornerRadius: Some(3.0)
+        )
+    };
+    pub static ref STYLE_PROGRESS_BAR_FILL: Vec<RectStyle> = {
+        style!(
+            RectStyle::BackgroundColor: BLUE_HIGHLIGHT,
+            RectStyle::CornerRadius: Some(3.0)
+        )
+    };
+}
+
+#[derive(Debug, Copy, Clone)]
+pub struct SetProgressValue(pub f32);
+#[derive(Debug, Clone)]
+pub struct SetProgressRange(pub Range<f32>);
+
+#[derive(Debug, Clone)]
+enum ProgressBarEvent {
+    SetValue(f32),
+    SetRange(Range<f32>),
+    LayoutUpdated,
+}
+
+struct ProgressBarHandler {
+    bar: WidgetRef,
+    fill: WidgetRef,
+    range: Range<f32>,
+    value: f32,
+}
+
+impl ProgressBarHandler {
+    fn update_fill(&self) {
+        let range = self.range.end - self.range.start;
+        let fraction = if range > 0.0 {
+            ((self.value - self.range.start) / range).max(0.0).min(1.0)
+        } else {
+            0.0
+        };
+        let width = self.bar.bounds().width() * fraction;
+        self.fill.update_layout(|layout| {
+            layout.edit_width().set(width);
+        });
+    }
+}
+
+impl EventHandler<ProgressBarEvent> for ProgressBarHandler {
+    fn handle(&mut self, event: &ProgressBarEvent, _: EventArgs) {
+        match *event {
+            ProgressBarEvent::SetValue(value) => {
+                if value.is_finite() {
+                    self.value = value;
+                }
+            }
+            ProgressBarEvent::SetRange(ref range) => {
+                self.range = range.clone();
+            }
+            ProgressBarEvent::LayoutUpdated => (),
+        }
+        self.update_fill();
+    }
+}
+
+/// A horizontal bar, filled from the left in proportion to a value within a range.
+/// The value is set with `SetProgressValue`, which can be sent from a worker thread with `event_global`.
+pub struct ProgressBarBuilder {
+    pub widget: WidgetBuilder,
+    pub fill: WidgetBuilder,
+    range: Range<f32>,
+    init_value: f32,
+}
+
+widget_builder!(ProgressBarBuilder);
+
+impl Default for ProgressBarBuilder {
+    fn default() -> Self {
+        let mut widget = WidgetBuilder::new("progress_bar");
+        widget.set_draw_state_with_style(RectState::new(), STYLE_PROGRESS_BAR.clone());
+        widget.layout().add(constraints![
+            min_size(Size::new(100.0, 20.0)),
+            height(20.0).strength(STRONG),
+        ]);
+        let mut fill = WidgetBuilder::new("progress_bar_fill");
+        fill.set_draw_state_with_style(RectState::new(), STYLE_PROGRESS_BAR_FILL.clone());
+        fill.layout().add(constraints![
+            align_left(&widget),
+            align_top(&widget),
+            align_bottom(&widget),
+            bound_right(&widget),
+        ]);
+        ProgressBarBuilder {
+            widget: widget,
+            fill: fill,
+            range: 0.0..1.0,
+            init_value: 0.0,
+        }
+    }
+}
+
+impl ProgressBarBuilder {
+    /// Creates a new `ProgressBarBuilder`, with a range of 0 to 1
+    pub fn new() -> Self {
+        Self::default()
+    }
+    pub fn set_value(&mut self, value: f32) -> &mut Self {
+        self.init_value = value;
+        self
+    }
+    pub fn set_range(&mut self, range: Range<f32>) -> &mut Self {
+        self.range = range;
+        self
+    }
+}
+
+impl Into<WidgetBuilder> for ProgressBarBuilder {
+    fn into(self) -> WidgetBuilder {
+        let (mut widget, fill) = (self.widget, self.fill);
+        let handler = ProgressBarHandler {
+            bar: widget.widget_ref(),
+            fill: fill.widget_ref(),
+            range: self.range,
+            value: self.init_value,
+        };
+        handler.update_fill();
+        widget
+            .add_handler(handler)
+            .add_handler(|event: &SetProgressValue, args: EventArgs| {
+                args.widget.event(ProgressBarEvent::SetValue(event.0));
+            })
+            .add_handler(|event: &SetProgressRange, args: EventArgs| {
+                args.widget.event(ProgressBarEvent::SetRange(event.0.clone()));
+            })
+            .add_handler(|_: &LayoutUpdated, args: EventArgs| {
+                args.widget.event(ProgressBarEvent::LayoutUpdated);
+            });
+        widget.add_child(fill);
+        widget
+    }
+}
+
+/// Send to a spinner to start or stop its animation
+#[derive(Debug, Copy, Clone)]
+pub struct SetSpinnerRunning(pub bool);
+
+enum SpinnerEvent {
+    Start(WidgetRef),
+    Stop(WidgetRef),
+    Frame,
+}
+
+/// Whether any part of the spinner is drawn inside the window. Widgets clip their children to their bounds,
+/// so it's hidden if it's outside a widget containing it, including when it's scrolled out of a scroll frame.
+/// Widgets in a hidden layout have empty bounds.
+fn is_visible(spinner: &WidgetRef, window: Rect) -> bool {
+    let mut visible = Some(spinner.visible_bounds());
+    let mut maybe_widget = spinner.parent();
+    while let Some(widget) = maybe_widget {
+        visible = visible.and_then(|visible| visible.intersection(&widget.visible_bounds()));
+        maybe_widget = widget.parent();
+    }
+    match visible.and_then(|visible| visible.intersection(&window)) {
+        Some(visible) => visible.width() > 0.0 && visible.height() > 0.0,
+        None => false,
+    }
+}
+
+/// Animates the running spinners, only requesting new frames while a running spinner is visible
+struct SpinnerAnimationHandler {
+    spinners: Vec<WidgetRef>,
+    start: Instant,
+}
+
+impl EventHandler<SpinnerEvent> for SpinnerAnimationHandler {
+    fn handle(&mut self, event: &SpinnerEvent, args: EventArgs) {
+        match *event {
+            SpinnerEvent::Start(ref spinner) => {
+                if !self.spinners.contains(spinner) {
+                    self.spinners.push(spinner.clone());
+                }
+                args.ui.redraw();
+            }
+            SpinnerEvent::Stop(ref spinner) => {
+                self.spinners.retain(|other| other != spinner);
+            }
+            SpinnerEvent::Frame => {
+                // forget spinners that were removed from the widget tree without being stopped
+                let (root, overlay_root) = (args.ui.get_root(), args.ui.overlay.root.clone());
+                self.spinners.retain(|spinner| is_descendant(spinner, &root) || is_descendant(spinner, &overlay_root));
+
+                let phase = (seconds(self.start.elapsed()) / SPINNER_PERIOD) % 1.0;
+                let window = Rect::new(Point::zero(), args.ui.window.borrow().size_dp());
+                let mut visible = false;
+                for spinner in &mut self.spinners {
+                    if is_visible(spinner, window) {
+                        spinner.update(|state: &mut SpinnerState| state.phase = phase);
+                        visible = true;
+                    }
+                }
+                if visible {
+                    args.ui.redraw();
+                }
+            }
+        }
+    }
+}
+
+enum SpinnerInputEvent {
+    Attached,
+    SetRunning(bool),
+}
+
+struct SpinnerHandler {
+    running: bool,
+}
+
+impl EventHandler<SpinnerInputEvent> for SpinnerHandler {
+    fn handle(&mut self, event: &SpinnerInputEvent, args: EventArgs) {
+        match *event {
+            SpinnerInputEvent::Attached => (),
+            SpinnerInputEvent::SetRunning(running) => self.running = running,
+        }
+        if self.running {
+            args.ui.event(SpinnerEvent::Start(args.widget.clone()));
+        } else {
+            args.ui.event(SpinnerEvent::Stop(args.widget.clone()));
+        }
+    }
+}
+
+/// A ring of dots with an animated highlight, for operations without known progress.
+/// Running spinners are animated only while they are visible, so they don't keep an otherwise idle app awake.
+pub struct SpinnerBuilder {
+    pub widget: WidgetBuilder,
+    running: bool,
+}
+
+widget_builder!(SpinnerBuilder);
+
+impl Default for SpinnerBuilder {
+    fn default() -> Self {
+        let mut widget = WidgetBuilder::new("spinner");
+        widget.set_draw_state(SpinnerState::new());
+        widget.layout().add(size(Size::new(32.0, 32.0)).strength(STRONG));
+        SpinnerBuilder {
+            widget: widget,
+            running: true,
+        }
+    }
+}
+
+impl SpinnerBuilder {
+    /// Creates a new `SpinnerBuilder`, the spinner is running by default
+    pub fn new() -> Self {
+        Self::default()
+    }
+    /// Set whether the spinner is initially running, it can be started or stopped later with `SetSpinnerRunning`
+    pub fn set_running(&mut self, running: bool) -> &mut Self {
+        self.running = running;
+        self
+    }
+    /// Set the color of the spinner's dots
+    pub fn set_color(&mut self, color: Color) -> &mut Self {
+        self.widget.set_draw_state_with_style(SpinnerState::new(), style!(SpinnerStyle::Color: color));
+        self
+    }
+}
+
+impl Into<WidgetBuilder> for SpinnerBuilder {
+    fn into(self) -> WidgetBuilder {
+        let mut widget = self.widget;
+        widget
+            .add_handler(SpinnerHandler { running: self.running })
+            .add_handler(|_: &WidgetAttachedEvent, args: EventArgs| {
+                args.widget.event(SpinnerInputEvent::Attached);
+            })
+            .add_handler(|event: &SetSpinnerRunning, args: EventArgs| {
+                args.widget.event(SpinnerInputEvent::SetRunning(event.0));
+            });
+        widget
+    }
+}
+
+impl App {
+    /// Add the handler that animates spinners
+    pub fn add_spinner_handlers(&mut self) {
+        self.add_handler(|_: &FrameEvent, args: EventArgs| {
+            args.widget.event(SpinnerEvent::Frame);
+        });
+        self.add_handler(SpinnerAnimationHandler {
+            spinners: Vec::new(),
+            start: Instant::now(),
+        });
+    }
+}
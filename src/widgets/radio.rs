@@ -0,0 +1,239 @@
+use cassowary::strength::*;
+
+use layout::constraint::*;
+use layout::linear_layout::{LinearLayoutSettings, Orientation};
+use event::{EventHandler, EventArgs};
+use widget::{WidgetBuilder, WidgetRef};
+use widget::property::Property;
+use widget::property::states::*;
+use widgets::text::TextBuilder;
+use input::mouse::ClickEvent;
+use draw::ellipse::{EllipseState, EllipseStyle};
+use draw::text::TextStyle;
+use geometry::Size;
+use color::*;
+
+static COLOR_RADIO_DEFAULT: Color = WHITE;
+static COLOR_RADIO_MOUSEOVER: Color = GRAY_90;
+static COLOR_RADIO_INACTIVE: Color = GRAY_90;
+static COLOR_RADIO_DOT: Color = BLUE_HIGHLIGHT;
+static COLOR_RADIO_TEXT_INACTIVE: Color = GRAY_70;
+
+static RADIO_BORDER: (f32, Color) = (1.0, GRAY_40);
+static RADIO_BORDER_FOCUSED: (f32, Color) = (1.0, BLUE);
+static RADIO_BORDER_INACTIVE: (f32, Color) = (1.0, GRAY_70);
+
+const RADIO_SIZE: f32 = 20.0;
+
+lazy_static! {
+    pub static ref STYLE_RADIO: Vec<EllipseStyle> = {
+        style!(
+            EllipseStyle::BackgroundColor: selector!(COLOR_RADIO_DEFAULT,
+                INACTIVE: COLOR_RADIO_INACTIVE,
+                MOUSEOVER: COLOR_RADIO_MOUSEOVER),
+            EllipseStyle::Border: selector!(Some(RADIO_BORDER),
+                INACTIVE: Some(RADIO_BORDER_INACTIVE),
+                FOCUSED: Some(RADIO_BORDER_FOCUSED))
+        )
+    };
+    pub static ref STYLE_RADIO_DOT: Vec<EllipseStyle> = {
+        style!(EllipseStyle::BackgroundColor: selector!(TRANSPARENT,
+            ACTIVATED: COLOR_RADIO_DOT))
+    };
+    pub static ref STYLE_RADIO_TEXT: Vec<TextStyle> = {
+        style!(TextStyle::TextColor: selector!(BLACK, INACTIVE: COLOR_RADIO_TEXT_INACTIVE))
+    };
+}
+
+/// Emitted by a radio group when a different radio button is selected
+#[derive(Debug, Clone)]
+pub struct RadioSelected(pub WidgetRef);
+
+/// Sent from a radio button to it's group when it becomes selected
+struct RadioButtonSelected(WidgetRef);
+
+/// Send to a radio button to select it
+#[derive(Debug, Copy, Clone)]
+pub struct SelectRadioButton;
+
+#[derive(Default)]
+struct RadioGroupHandler {
+    selected: Option<WidgetRef>,
+}
+
+impl EventHandler<RadioButtonSelected> for RadioGroupHandler {
+    fn handle(&mut self, event: &RadioButtonSelected, args: EventArgs) {
+        let selected = Some(event.0.clone());
+        if selected != self.selected {
+            if let Some(ref mut old_selected) = self.selected {
+                old_selected.remove_prop(Property::Activated);
+            }
+            self.selected = selected;
+            args.widget.event(RadioSelected(event.0.clone()));
+        }
+    }
+}
+
+struct RadioButtonHandler {
+    group: Option<WidgetRef>,
+}
+
+impl EventHandler<SelectRadioButton> for RadioButtonHandler {
+    fn handle(&mut self, _: &SelectRadioButton, mut args: EventArgs) {
+        if !args.widget.props().contains(&Property::Activated) {
+            args.widget.add_prop(Property::Activated);
+            if let Some(ref group) = self.group {
+                group.event(RadioButtonSelected(args.widget.clone()));
+            }
+        }
+    }
+}
+
+/// A set of mutually exclusive radio buttons, only one of which can be selected at a time.
+/// The group is a container widget, that arranges it's radio buttons vertically,
+/// but radio buttons can also be added to a group from anywhere in the widget tree
+/// using `RadioButtonBuilder::set_group`.
+pub struct RadioGroupBuilder {
+    pub widget: WidgetBuilder,
+}
+
+widget_wrapper!(RadioGroupBuilder);
+
+impl Default for RadioGroupBuilder {
+    fn default() -> Self {
+        let mut layout_settings = LinearLayoutSettings::new(Orientation::Vertical);
+        layout_settings.padding = 5.0;
+        let mut widget = WidgetBuilder::new("radio_group");
+        widget
+            .add_handler(RadioGroupHandler::default())
+            .linear_layout(layout_settings);
+        RadioGroupBuilder { widget: widget }
+    }
+}
+
+impl RadioGroupBuilder {
+    /// Creates a new, empty `RadioGroupBuilder`
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a radio button to the group, and as a child of the group widget
+    pub fn add_button(&mut self, mut button: RadioButtonBuilder) -> &mut Self {
+        button.set_group(&self.widget.widget_ref());
+        self.widget.add_child(button);
+        self
+    }
+
+    /// Add a radio button with a text label to the group
+    pub fn add_option(&mut self, text: &str) -> &mut Self {
+        let mut button = RadioButtonBuilder::new();
+        button.set_text(text);
+        self.add_button(button)
+    }
+
+    /// Callback function to be called when the selected radio button changes
+    pub fn on_selected<F>(&mut self, callback: F) -> &mut Self
+        where F: Fn(WidgetRef, EventArgs) + 'static
+    {
+        self.widget.add_handler(move |event: &RadioSelected, args: EventArgs| {
+            callback(event.0.clone(), args);
+        });
+        self
+    }
+}
+
+pub struct RadioButtonBuilder {
+    pub widget: WidgetBuilder,
+    radio: WidgetBuilder,
+    group: Option<WidgetRef>,
+    selected: bool,
+}
+
+widget_builder!(RadioButtonBuilder);
+
+impl Default for RadioButtonBuilder {
+    fn default() -> Self {
+        let mut widget = WidgetBuilder::new("radio_button");
+        widget
+            .enable_hover()
+            .make_focusable()
+            .click_on_activate_key();
+        widget.layout().add(shrink());
+
+        let mut radio = WidgetBuilder::new("radio_button_ring");
+        radio.set_draw_state_with_style(EllipseState::new(), STYLE_RADIO.clone());
+        radio.layout().add(constraints![
+            size(Size::new(RADIO_SIZE, RADIO_SIZE)),
+            align_left(&widget),
+            align_top(&widget).strength(WEAK),
+            bound_by(&widget),
+            center_vertical(&widget),
+        ]);
+
+        let mut dot = WidgetBuilder::new("radio_button_dot");
+        dot.set_draw_state_with_style(EllipseState::new(), STYLE_RADIO_DOT.clone());
+        dot.layout().add(match_layout(&radio).padding(5.0));
+        radio.add_child(dot);
+
+        RadioButtonBuilder {
+            widget: widget,
+            radio: radio,
+            group: None,
+            selected: false,
+        }
+    }
+}
+
+impl RadioButtonBuilder {
+    /// Creates a new `RadioButtonBuilder`, that doesn't belong to any group
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the group this radio button belongs to, selecting this radio button
+    /// will deselect the previously selected button in the group
+    pub fn set_group(&mut self, group: &WidgetRef) -> &mut Self {
+        self.group = Some(group.clone());
+        self
+    }
+
+    /// Make this radio button initially selected
+    pub fn set_selected(&mut self) -> &mut Self {
+        self.selected = true;
+        self
+    }
+
+    /// Add a text label to the right of the radio button
+    pub fn set_text(&mut self, text: &str) -> &mut Self {
+        let style = style!(parent: STYLE_RADIO_TEXT,
+            TextStyle::Text: text.to_owned());
+        let mut text_widget = TextBuilder::new_with_style(style);
+        text_widget.set_name("radio_button_text");
+        text_widget.layout().add(constraints![
+            to_right_of(&self.radio).padding(8.0),
+            center_vertical(&self.widget),
+            bound_by(&self.widget),
+        ]);
+        self.widget.add_child(text_widget);
+        self
+    }
+}
+
+impl Into<WidgetBuilder> for RadioButtonBuilder {
+    fn into(self) -> WidgetBuilder {
+        let (mut widget, radio) = (self.widget, self.radio);
+        widget
+            .add_handler(RadioButtonHandler { group: self.group })
+            .add_handler(|_: &ClickEvent, mut args: EventArgs| {
+                if !args.widget.props().contains(&Property::Inactive) {
+                    args.widget.event(SelectRadioButton);
+                }
+                *args.handled = true;
+            });
+        widget.add_child(radio);
+        if self.selected {
+            widget.widget_ref().event(SelectRadioButton);
+        }
+        widget
+    }
+}
@@ -1,3 +1,5 @@
+use std::time::Instant;
+
 use glutin;
 use cassowary::strength::*;
 use cassowary::WeightedRelation::*;
@@ -6,19 +8,41 @@ use layout::constraint::*;
 use event::{EventArgs, EventHandler};
 use widget::{WidgetBuilder, WidgetRef};
 use widgets::slider::{SliderBuilder, SetSliderValue};
-use geometry::{Size, Vector, Rect, RectExt};
+use geometry::{Size, Point, Vector, Rect, RectExt};
 use layout::{LayoutUpdated, LAYOUT};
 use input::mouse::WidgetMouseWheel;
 use draw::rect::{RectState, RectStyle};
+use app::{FrameEvent, seconds};
 use color::*;
 
 const FLOATING_POINT_ERROR: f32 = 0.0001;
+/// Fraction of the remaining distance to the target offset left after one second of smooth scrolling
+const SMOOTHING: f32 = 0.000_001;
+/// Fraction of the velocity left after one second of kinetic scrolling
+const FRICTION: f32 = 0.05;
+/// Velocity in pixels per second, below which kinetic scrolling stops
+const MIN_VELOCITY: f32 = 20.0;
+/// Time after the last trackpad scroll before kinetic scrolling starts, in seconds
+const MOMENTUM_DELAY: f32 = 0.05;
+/// Longest time step used for scroll animation, in seconds, so a stalled frame doesn't cause a jump
+const MAX_FRAME_TIME: f32 = 0.1;
+
+/// Send to a scroll widget to scroll to a position, the distance of the visible area from the top left of the content
+#[derive(Debug, Copy, Clone)]
+pub struct ScrollTo(pub Point);
+
+/// Send to a scroll widget to scroll the least distance that makes a widget within its content visible.
+/// Can also be sent with `event_bubble_up` from the widget itself, to scroll the innermost scroll widget containing it.
+#[derive(Debug, Clone)]
+pub struct ScrollToWidget(pub WidgetRef);
 
 pub struct ScrollBuilder {
     widget: WidgetBuilder,
     content_holder: WidgetBuilder,
     content: Option<WidgetBuilder>,
     scrollbars: Option<(WidgetBuilder, SliderBuilder, SliderBuilder)>,
+    smooth: bool,
+    kinetic: bool,
 }
 
 impl Default for ScrollBuilder {
@@ -27,12 +51,15 @@ impl Default for ScrollBuilder {
 
         let mut content_holder = WidgetBuilder::new("content_holder");
         content_holder.layout().no_container();
+        content_holder.make_scroll_frame();
 
         ScrollBuilder {
             widget: widget,
             content_holder: content_holder,
             content: None,
             scrollbars: None,
+            smooth: true,
+            kinetic: true,
         }
     }
 }
@@ -50,6 +77,19 @@ impl ScrollBuilder {
         self
     }
 
+    /// Animate scrolling with the mouse wheel and `ScrollTo` events, instead of jumping
+    /// straight to the new position. Enabled by default.
+    pub fn set_smooth_scrolling(&mut self, smooth: bool) -> &mut Self {
+        self.smooth = smooth;
+        self
+    }
+
+    /// Keep scrolling with decreasing speed after a trackpad scroll gesture ends. Enabled by default.
+    pub fn set_kinetic_scrolling(&mut self, kinetic: bool) -> &mut Self {
+        self.kinetic = kinetic;
+        self
+    }
+
     /// Add a scrollbar. Scrolling can be done independently,
     /// even if no scroll bar is shown.
     pub fn add_scrollbar(&mut self) -> &mut Self {
@@ -120,7 +160,9 @@ impl Into<WidgetBuilder> for ScrollBuilder {
             ]);
         }
 
-        let mut scroll_parent_handler = ScrollParent::new(&mut content.widget_ref());
+        let mut scroll_parent_handler = ScrollParent::new();
+        scroll_parent_handler.smooth = self.smooth;
+        scroll_parent_handler.kinetic = self.kinetic;
         if let Some((ref mut corner, ref mut scrollbar_h, ref mut scrollbar_v)) = self.scrollbars {
             scroll_parent_handler.scrollbars = Some(ScrollBars::new(scrollbar_h, scrollbar_v, corner.widget_ref()));
         }
@@ -128,6 +170,25 @@ impl Into<WidgetBuilder> for ScrollBuilder {
         self.content_holder.add_handler(|event: &WidgetMouseWheel, args: EventArgs| {
             args.widget.event(ScrollParentEvent::WidgetMouseWheel(*event));
         });
+        self.content_holder.add_handler(|_: &FrameEvent, args: EventArgs| {
+            args.widget.event(ScrollParentEvent::Frame);
+        });
+        self.content_holder.add_handler(|event: &ScrollTo, args: EventArgs| {
+            args.widget.event(ScrollParentEvent::ScrollTo(event.0));
+        });
+        self.content_holder.add_handler(|event: &ScrollToWidget, mut args: EventArgs| {
+            args.widget.event(ScrollParentEvent::ScrollToWidget(event.0.clone()));
+            *args.handled = true;
+        });
+        let content_holder = self.content_holder.widget_ref();
+        self.widget.add_handler(move |event: &ScrollTo, _: EventArgs| {
+            content_holder.event(*event);
+        });
+        let content_holder = self.content_holder.widget_ref();
+        self.widget.add_handler(move |event: &ScrollToWidget, mut args: EventArgs| {
+            content_holder.event(event.clone());
+            *args.handled = true;
+        });
         self.content_holder.add_child(content);
         if self.scrollbars.is_some() {
             self.content_holder.layout().add(constraints![
@@ -176,49 +237,153 @@ enum ScrollParentEvent {
     WidgetMouseWheel(WidgetMouseWheel),
     ScrollBarMovedX(f32),
     ScrollBarMovedY(f32),
+    ScrollTo(Point),
+    ScrollToWidget(WidgetRef),
+    Frame,
 }
+/// Scrolls the content holder, a scroll frame, so scrolling moves the content without a relayout
 struct ScrollParent {
-    scrollable: WidgetRef,
     content_rect: Rect,
     container_rect: Rect,
     width_ratio: f32,
     height_ratio: f32,
     scrollable_area: Size,
     offset: Vector,
+    /// Offset the content is animating towards, the same as `offset` when not animating
+    target: Vector,
+    /// Velocity of kinetic scrolling, in pixels per second
+    velocity: Vector,
+    smooth: bool,
+    kinetic: bool,
+    animating: bool,
+    last_frame: Instant,
+    last_wheel: Instant,
     pub scrollbars: Option<ScrollBars>,
 }
 impl ScrollParent {
-    fn new(scrollable: &mut WidgetRef) -> Self {
+    fn new() -> Self {
         ScrollParent {
-            scrollable: scrollable.clone(),
             content_rect: Rect::zero(),
             container_rect: Rect::zero(),
             width_ratio: 0.0,
             height_ratio: 0.0,
             scrollable_area: Size::zero(),
             offset: Vector::zero(),
+            target: Vector::zero(),
+            velocity: Vector::zero(),
+            smooth: true,
+            kinetic: true,
+            animating: false,
+            last_frame: Instant::now(),
+            last_wheel: Instant::now(),
             scrollbars: None,
         }
     }
-    fn move_content_x(&mut self) {
-        let scroll_to = self.container_rect.left() + self.offset.x;
-        self.scrollable.update_layout(|layout| {
-            layout.edit_left().set(scroll_to);
-        });
+    /// Limit an offset so the content covers the container, preventing overscroll
+    fn clamp(&self, offset: Vector) -> Vector {
+        Vector::new(
+            f32::min(0.0, f32::max(-self.scrollable_area.width, offset.x)),
+            f32::min(0.0, f32::max(-self.scrollable_area.height, offset.y)))
     }
-    fn move_content_y(&mut self) {
-        let scroll_to = self.container_rect.top() + self.offset.y;
-        self.scrollable.update_layout(|layout| {
-            layout.edit_top().set(scroll_to);
-        });
+    /// Move the content to an offset immediately
+    fn set_offset(&mut self, offset: Vector, args: &mut EventArgs) {
+        let offset = self.clamp(offset);
+        let mut moved = false;
+        if self.scrollable_area.width > 0.0 && offset.x != self.offset.x {
+            self.offset.x = offset.x;
+            moved = true;
+            self.move_slider_x();
+        }
+        if self.scrollable_area.height > 0.0 && offset.y != self.offset.y {
+            self.offset.y = offset.y;
+            moved = true;
+            self.move_slider_y();
+        }
+        if moved {
+            self.move_content(args);
+        }
+    }
+    /// Scroll to an offset, animated if smooth scrolling is enabled
+    fn scroll_to(&mut self, target: Vector, args: &mut EventArgs) {
+        self.target = self.clamp(target);
+        self.velocity = Vector::zero();
+        if self.smooth {
+            self.start_animation(&args.widget);
+        } else {
+            let target = self.target;
+            self.set_offset(target, args);
+        }
+    }
+    fn start_animation(&mut self, widget: &WidgetRef) {
+        if !self.animating {
+            self.animating = true;
+            self.last_frame = Instant::now();
+            widget.request_frame();
+        }
+    }
+    fn animate(&mut self, args: &mut EventArgs) {
+        let now = Instant::now();
+        let dt = f32::min(seconds(now - self.last_frame), MAX_FRAME_TIME);
+        self.last_frame = now;
+        // kinetic scrolling only starts once the trackpad stops sending scroll events
+        if self.velocity != Vector::zero() && seconds(now - self.last_wheel) > MOMENTUM_DELAY {
+            let target = self.target + self.velocity * dt;
+            self.target = self.clamp(target);
+            self.velocity = self.velocity * FRICTION.powf(dt);
+            if self.target.x != target.x || self.velocity.x.abs() < MIN_VELOCITY {
+                self.velocity.x = 0.0;
+            }
+            if self.target.y != target.y || self.velocity.y.abs() < MIN_VELOCITY {
+                self.velocity.y = 0.0;
+            }
+        }
+        let remaining = self.target - self.offset;
+        let offset = if remaining.x.abs() < 0.5 && remaining.y.abs() < 0.5 {
+            self.target
+        } else {
+            self.offset + remaining * (1.0 - SMOOTHING.powf(dt))
+        };
+        self.set_offset(offset, args);
+        if self.clamp(self.target) == self.offset && self.velocity == Vector::zero() {
+            self.animating = false;
+        } else {
+            args.widget.request_frame();
+        }
+    }
+    /// The target offset that scrolls the least distance that makes `rect` visible,
+    /// or aligns it to the top left of the container if it's larger than the container.
+    /// Both rects are where they're drawn in the window.
+    fn reveal(&self, rect: Rect, container: Rect) -> Vector {
+        let rect = rect.translate(&(self.target - self.offset));
+        let mut target = self.target;
+        if rect.width() > container.width() || rect.left() < container.left() {
+            target.x += container.left() - rect.left();
+        } else if rect.right() > container.right() {
+            target.x -= rect.right() - container.right();
+        }
+        if rect.height() > container.height() || rect.top() < container.top() {
+            target.y += container.top() - rect.top();
+        } else if rect.bottom() > container.bottom() {
+            target.y -= rect.bottom() - container.bottom();
+        }
+        target
+    }
+    fn move_content(&mut self, args: &mut EventArgs) {
+        args.ui.scroll_widget(&args.widget, self.offset);
     }
     fn move_slider_x(&mut self) {
+        if self.scrollable_area.width <= 0.0 {
+            return;
+        }
         if let Some(ref mut scrollbars) = self.scrollbars {
             let offset_x = -self.offset.x / self.scrollable_area.width;
             scrollbars.scrollbar_h.event(SetSliderValue(offset_x));
         }
     }
     fn move_slider_y(&mut self) {
+        if self.scrollable_area.height <= 0.0 {
+            return;
+        }
         if let Some(ref mut scrollbars) = self.scrollbars {
             let offset_y = -self.offset.y / self.scrollable_area.height;
             scrollbars.scrollbar_v.event(SetSliderValue(offset_y));
@@ -226,7 +391,7 @@ impl ScrollParent {
     }
 }
 impl EventHandler<ScrollParentEvent> for ScrollParent {
-    fn handle(&mut self, event: &ScrollParentEvent, args: EventArgs) {
+    fn handle(&mut self, event: &ScrollParentEvent, mut args: EventArgs) {
         match *event {
             ScrollParentEvent::ContainerLayoutUpdated | ScrollParentEvent::ContentLayoutUpdated(_) => {
 
@@ -238,16 +403,17 @@ impl EventHandler<ScrollParentEvent> for ScrollParent {
                 }
 
                 let scrollable_area = self.content_rect.size - self.container_rect.size;
-                let content_offset = self.content_rect.origin - self.container_rect.origin;
-                if content_offset != self.offset || scrollable_area != self.scrollable_area {
-                    self.offset = content_offset;
+                if scrollable_area != self.scrollable_area {
                     self.scrollable_area = scrollable_area;
-                    if self.scrollable_area.width > 0.0 {
-                        self.move_slider_x();
-                    }
-                    if self.scrollable_area.height > 0.0 {
-                        self.move_slider_y();
+                    // keep the content covering the container if it shrinks
+                    self.target = self.clamp(self.target);
+                    let offset = self.clamp(self.offset);
+                    if offset != self.offset {
+                        self.offset = offset;
+                        self.move_content(&mut args);
                     }
+                    self.move_slider_x();
+                    self.move_slider_y();
                 }
 
 
@@ -313,34 +479,63 @@ impl EventHandler<ScrollParentEvent> for ScrollParent {
             }
             ScrollParentEvent::WidgetMouseWheel(ref mouse_wheel) => {
                 let scroll = get_scroll(mouse_wheel.0);
+                if let glutin::MouseScrollDelta::PixelDelta(..) = mouse_wheel.0 {
+                    // trackpads scroll in small steps, follow them immediately and
+                    // track their velocity to continue scrolling once they stop
+                    let now = Instant::now();
+                    let dt = seconds(now - self.last_wheel);
+                    self.last_wheel = now;
+                    let velocity = if dt > 0.0 && dt < MAX_FRAME_TIME { scroll / dt } else { Vector::zero() };
+                    self.velocity = self.velocity * 0.2 + velocity * 0.8;
+                    let offset = self.offset + scroll;
+                    self.set_offset(offset, &mut args);
+                    self.target = self.offset;
+                    if self.kinetic {
+                        self.start_animation(&args.widget);
+                    } else {
+                        self.velocity = Vector::zero();
+                    }
+                } else {
+                    let target = self.target + scroll;
+                    self.scroll_to(target, &mut args);
+                }
+            }
+            ScrollParentEvent::ScrollBarMovedX(ref offset) => {
+                // the scrollbar is hidden when the content fits, but may still send a last value
                 if self.scrollable_area.width > 0.0 {
-                    self.offset.x = f32::min(0.0, f32::max(-self.scrollable_area.width, self.offset.x + scroll.x));
-                    self.move_content_x();
-                    self.move_slider_x();
+                    self.offset.x = -offset.max(0.0).min(1.0) * self.scrollable_area.width;
+                    self.target.x = self.offset.x;
+                    self.velocity.x = 0.0;
+                    self.move_content(&mut args);
                 }
+            }
+            ScrollParentEvent::ScrollBarMovedY(ref offset) => {
+                // the scrollbar is hidden when the content fits, but may still send a last value
                 if self.scrollable_area.height > 0.0 {
-                    self.offset.y = f32::min(0.0, f32::max(-self.scrollable_area.height, self.offset.y + scroll.y));
-                    self.move_content_y();
-                    self.move_slider_y();
+                    self.offset.y = -offset.max(0.0).min(1.0) * self.scrollable_area.height;
+                    self.target.y = self.offset.y;
+                    self.velocity.y = 0.0;
+                    self.move_content(&mut args);
                 }
             }
-            ScrollParentEvent::ScrollBarMovedX(ref offset) => {
-                self.offset.x = -offset * self.scrollable_area.width;
-                self.move_content_x();
+            ScrollParentEvent::ScrollTo(position) => {
+                self.scroll_to(Vector::new(-position.x, -position.y), &mut args);
             }
-            ScrollParentEvent::ScrollBarMovedY(ref offset) => {
-                self.offset.y = -offset * self.scrollable_area.height;
-                self.move_content_y();
+            ScrollParentEvent::ScrollToWidget(ref widget) => {
+                let target = self.reveal(widget.visible_bounds(), args.widget.visible_bounds());
+                self.scroll_to(target, &mut args);
+            }
+            ScrollParentEvent::Frame => {
+                if self.animating {
+                    self.animate(&mut args);
+                }
             }
         }
     }
 }
 fn get_scroll(event: glutin::MouseScrollDelta) -> Vector {
-    let vec = match event {
-        glutin::MouseScrollDelta::LineDelta(x, y) |
-        glutin::MouseScrollDelta::PixelDelta(x, y) => {
-            Vector::new(-x, y)
-        }
-    };
-    vec * 13.0
+    match event {
+        glutin::MouseScrollDelta::LineDelta(x, y) => Vector::new(-x, y) * 13.0,
+        glutin::MouseScrollDelta::PixelDelta(x, y) => Vector::new(-x, y),
+    }
 }
@@ -391,6 +391,8 @@ impl EventHandler<SliderInputEvent> for SliderHandler {
                 if args.widget.props().contains(&Property::Inactive) {
                     return;
                 }
+                // the click is in the window, the slider's bounds may be in a scroll frame
+                let point = self.slider_ref.window_to_local(point).unwrap_or(point);
                 let position = if let Orientation::Horizontal = self.orientation {
                     point.x
                 } else {
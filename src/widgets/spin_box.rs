@@ -0,0 +1,400 @@
+//! A numeric input field, with buttons and keys to step the value up or down.
+
+use std::ops::Range;
+
+use glutin;
+
+use layout::constraint::*;
+use event::{EventHandler, EventArgs};
+use widget::{WidgetBuilder, WidgetRef};
+use widget::property::Property;
+use widget::property::states::*;
+use widget::style::StyleUpdated;
+use widgets::edit_text::{EditTextBuilder, TextUpdated};
+use widgets::slider::{SliderBuilder, SetSliderValue, SetSliderRange};
+use widgets::text::TextBuilder;
+use input::mouse::{ClickEvent, WidgetMouseWheel};
+use input::keyboard::WidgetKeyboardInput;
+use draw::rect::{RectState, RectStyle};
+use draw::text::{TextState, TextStyle};
+use color::*;
+
+/// Width of the increment and decrement buttons
+const SPIN_BUTTON_WIDTH: f32 = 24.0;
+/// Distance scrolled on a trackpad for each step, the same as a line of mouse wheel scrolling in a scroll widget
+const PIXELS_PER_STEP: f32 = 13.0;
+
+lazy_static! {
+    pub static ref STYLE_SPIN_BOX_EDIT: Vec<RectStyle> = {
+        style!(
+            RectStyle::Border: selector!(Some((1.0, GRAY_70)),
+                INVALID: Some((2.0, RED)),
+                FOCUSED: Some((1.0, BLUE))),
+            RectStyle::CornerRadius: Some(3.0)
+        )
+    };
+    pub static ref STYLE_SPIN_BUTTON: Vec<RectStyle> = {
+        style!(
+            RectStyle::BackgroundColor: selector!(GRAY_80,
+                PRESSED: GRAY_60,
+                MOUSEOVER: GRAY_90,
+                INACTIVE: GRAY_90),
+            RectStyle::Border: Some((1.0, GRAY_70))
+        )
+    };
+    pub static ref STYLE_SPIN_BUTTON_TEXT: Vec<TextStyle> = {
+        style!(TextStyle::FontSize: 16.0)
+    };
+}
+
+/// Emitted by a spin box when its value is changed by the user
+#[derive(Debug, Copy, Clone)]
+pub struct SpinBoxEvent {
+    pub value: f32,
+    pub offset: f32,
+}
+
+#[derive(Debug, Copy, Clone)]
+pub struct SetSpinBoxValue(pub f32);
+pub struct SetSpinBoxRange(pub Range<f32>);
+
+#[derive(Debug, Clone)]
+enum SpinBoxInputEvent {
+    /// Step the value by a number of steps
+    Step(f32),
+    MouseWheel(glutin::MouseScrollDelta),
+    Text(String),
+    Commit,
+    FocusChanged(bool),
+    SetValue(f32),
+    SetRange(Range<f32>),
+}
+
+struct SpinBoxHandler {
+    edit: WidgetRef,
+    text: WidgetRef,
+    value: f32,
+    range: Range<f32>,
+    step: f32,
+    precision: usize,
+    focused: bool,
+    /// Trackpad scrolling that hasn't added up to a step yet
+    wheel_pixels: f32,
+}
+
+impl SpinBoxHandler {
+    fn round(&self, value: f32) -> f32 {
+        let scale = 10.0f32.powi(self.precision as i32);
+        let value = (value * scale).round() / scale;
+        value.max(self.range.start).min(self.range.end)
+    }
+    fn format(&self, value: f32) -> String {
+        format!("{:.*}", self.precision, value)
+    }
+    /// Parse the text, if it's a number within range
+    fn parse(&self, text: &str) -> Option<f32> {
+        text.trim().parse::<f32>().ok().and_then(|value| {
+            if value.is_finite() && value >= self.range.start && value <= self.range.end {
+                Some(value)
+            } else {
+                None
+            }
+        })
+    }
+    fn set_valid(&mut self, valid: bool) {
+        let invalid = self.edit.props().contains(&Property::Invalid);
+        if valid && invalid {
+            self.edit.remove_prop(Property::Invalid);
+        } else if !valid && !invalid {
+            self.edit.add_prop(Property::Invalid);
+        }
+    }
+    /// The text in the text field, which may not have been committed yet
+    fn current_text(&mut self) -> String {
+        let draw_state = self.text.draw_state();
+        draw_state.downcast_ref::<TextState>().map(|state| state.text.clone()).unwrap_or_default()
+    }
+    /// Show the current value in the text field, replacing any text being edited
+    fn update_text(&mut self) {
+        self.text.event(TextUpdated(self.format(self.value)));
+        self.set_valid(true);
+    }
+    fn set_value(&mut self, value: f32, args: &mut EventArgs) {
+        let value = self.round(value);
+        if value != self.value {
+            let offset = value - self.value;
+            self.value = value;
+            args.widget.event(SpinBoxEvent { value: value, offset: offset });
+        }
+    }
+}
+
+impl EventHandler<SpinBoxInputEvent> for SpinBoxHandler {
+    fn handle(&mut self, event: &SpinBoxInputEvent, mut args: EventArgs) {
+        match *event {
+            SpinBoxInputEvent::Step(steps) => {
+                if args.widget.props().contains(&Property::Inactive) {
+                    return;
+                }
+                // step from the value being typed, if it's valid
+                let text = self.current_text();
+                let value = self.parse(&text).unwrap_or(self.value);
+                self.set_value(value + steps * self.step, &mut args);
+                self.update_text();
+            }
+            SpinBoxInputEvent::MouseWheel(delta) => {
+                let steps = match delta {
+                    glutin::MouseScrollDelta::LineDelta(_, y) => y.signum(),
+                    glutin::MouseScrollDelta::PixelDelta(_, y) => {
+                        self.wheel_pixels += y;
+                        let steps = (self.wheel_pixels / PIXELS_PER_STEP).trunc();
+                        self.wheel_pixels -= steps * PIXELS_PER_STEP;
+                        steps
+                    }
+                };
+                if steps != 0.0 {
+                    args.widget.event(SpinBoxInputEvent::Step(steps));
+                }
+            }
+            SpinBoxInputEvent::Text(ref text) => {
+                if text == &self.format(self.value) {
+                    self.set_valid(true);
+                    return;
+                }
+                match self.parse(text) {
+                    Some(value) => {
+                        self.set_value(value, &mut args);
+                        self.set_valid(true);
+                    }
+                    None => self.set_valid(false),
+                }
+            }
+            SpinBoxInputEvent::Commit => {
+                // clamp numbers out of range, and revert text that isn't a number
+                let text = self.current_text();
+                if let Ok(value) = text.trim().parse::<f32>() {
+                    if value.is_finite() {
+                        self.set_value(value, &mut args);
+                    }
+                }
+                self.update_text();
+            }
+            SpinBoxInputEvent::FocusChanged(focused) => {
+                if self.focused && !focused {
+                    args.widget.event(SpinBoxInputEvent::Commit);
+                }
+                self.focused = focused;
+            }
+            SpinBoxInputEvent::SetValue(value) => {
+                if value.is_finite() {
+                    self.value = self.round(value);
+                    self.update_text();
+                }
+            }
+            SpinBoxInputEvent::SetRange(ref range) => {
+                self.range = range.clone();
+                let value = self.round(self.value);
+                self.set_value(value, &mut args);
+                self.update_text();
+            }
+        }
+    }
+}
+
+/// A text field for numbers, with buttons to increment and decrement the value.
+/// The value can also be stepped with the up and down arrow keys, page up and page down
+/// for ten steps at a time, or the mouse wheel. Text that isn't a number within range is
+/// shown with the `Invalid` property, and reverted when the field loses focus or return is pressed.
+pub struct SpinBoxBuilder {
+    pub widget: WidgetBuilder,
+    pub edit: EditTextBuilder,
+    range: Range<f32>,
+    init_value: f32,
+    step: f32,
+    precision: usize,
+}
+
+widget_builder!(SpinBoxBuilder);
+
+impl Default for SpinBoxBuilder {
+    fn default() -> Self {
+        let mut edit = EditTextBuilder::new();
+        edit.widget.set_draw_state_with_style(RectState::new(), STYLE_SPIN_BOX_EDIT.clone());
+        SpinBoxBuilder {
+            widget: WidgetBuilder::new("spin_box"),
+            edit: edit,
+            range: 0.0..100.0,
+            init_value: 0.0,
+            step: 1.0,
+            precision: 0,
+        }
+    }
+}
+
+impl SpinBoxBuilder {
+    /// Creates a new `SpinBoxBuilder`, with a range of 0 to 100 and a step of 1
+    pub fn new() -> Self {
+        Self::default()
+    }
+    pub fn set_value(&mut self, value: f32) -> &mut Self {
+        self.init_value = value;
+        self
+    }
+    /// Set the minimum and maximum value, values outside the range are clamped
+    pub fn set_range(&mut self, range: Range<f32>) -> &mut Self {
+        self.range = range;
+        self
+    }
+    /// Set how much the value changes with each step
+    pub fn set_step(&mut self, step: f32) -> &mut Self {
+        self.step = step;
+        self
+    }
+    /// Set the number of decimal places the value is rounded to and shown with
+    pub fn set_precision(&mut self, precision: usize) -> &mut Self {
+        self.precision = precision;
+        self
+    }
+    pub fn on_value_changed<F>(&mut self, on_value_changed: F) -> &mut Self
+        where F: Fn(f32, &mut EventArgs) + 'static
+    {
+        self.widget.add_handler(move |event: &SpinBoxEvent, mut args: EventArgs| {
+            on_value_changed(event.value, &mut args);
+        });
+        self
+    }
+    /// Link the value of this spin box and a slider, so that changing either one updates the other.
+    /// The slider is given the range and value of the spin box, so set those before binding.
+    pub fn bind_slider(&mut self, slider: &mut SliderBuilder) -> &mut Self {
+        slider.set_range(self.range.clone()).set_value(self.init_value);
+        let slider_ref = slider.widget_ref();
+        self.widget.add_handler(move |event: &SetSpinBoxRange, _: EventArgs| {
+            slider_ref.event(SetSliderRange(event.0.clone()));
+        });
+        let slider_ref = slider.widget_ref();
+        self.on_value_changed(move |value, _| {
+            slider_ref.event(SetSliderValue(value));
+        });
+        let spin_box_ref = self.widget.widget_ref();
+        slider.on_value_changed(move |value, _| {
+            spin_box_ref.event(SetSpinBoxValue(value));
+        });
+        self
+    }
+}
+
+fn build_spin_button(text: &str, steps: f32, spin_box: &WidgetRef) -> WidgetBuilder {
+    let mut button = WidgetBuilder::new("spin_button");
+    button
+        .set_draw_state_with_style(RectState::new(), STYLE_SPIN_BUTTON.clone())
+        .enable_hover();
+    let spin_box = spin_box.clone();
+    button.add_handler(move |_: &ClickEvent, mut args: EventArgs| {
+        spin_box.event(SpinBoxInputEvent::Step(steps));
+        *args.handled = true;
+    });
+    let style = style!(parent: STYLE_SPIN_BUTTON_TEXT, TextStyle::Text: text.to_owned());
+    let mut text = TextBuilder::new_with_style(style);
+    text.set_name("spin_button_text");
+    text.layout().add(center(&button));
+    button.add_child(text);
+    button
+}
+
+impl Into<WidgetBuilder> for SpinBoxBuilder {
+    fn into(self) -> WidgetBuilder {
+        let (mut widget, mut edit) = (self.widget, self.edit);
+        let spin_box = widget.widget_ref();
+
+        let mut handler = SpinBoxHandler {
+            edit: edit.widget.widget_ref(),
+            text: edit.text_widget.widget_ref(),
+            value: 0.0,
+            range: self.range,
+            step: self.step,
+            precision: self.precision,
+            focused: false,
+            wheel_pixels: 0.0,
+        };
+        handler.value = handler.round(self.init_value);
+        edit.text_widget.set_draw_state(TextState::new(&handler.format(handler.value)));
+
+        {
+            let spin_box = spin_box.clone();
+            edit.on_text_changed(move |event, _| {
+                spin_box.event(SpinBoxInputEvent::Text(event.0.clone()));
+            });
+        }
+        {
+            let spin_box = spin_box.clone();
+            edit.widget.add_handler(move |event: &WidgetKeyboardInput, _: EventArgs| {
+                let input = event.0;
+                if input.state != glutin::ElementState::Pressed {
+                    return;
+                }
+                let event = match input.virtual_keycode {
+                    Some(glutin::VirtualKeyCode::Up) => SpinBoxInputEvent::Step(1.0),
+                    Some(glutin::VirtualKeyCode::Down) => SpinBoxInputEvent::Step(-1.0),
+                    Some(glutin::VirtualKeyCode::PageUp) => SpinBoxInputEvent::Step(10.0),
+                    Some(glutin::VirtualKeyCode::PageDown) => SpinBoxInputEvent::Step(-10.0),
+                    Some(glutin::VirtualKeyCode::Return) => SpinBoxInputEvent::Commit,
+                    _ => return,
+                };
+                spin_box.event(event);
+            });
+        }
+        {
+            let spin_box = spin_box.clone();
+            edit.widget.add_handler(move |_: &StyleUpdated, args: EventArgs| {
+                let focused = args.widget.props().contains(&Property::Focused);
+                spin_box.event(SpinBoxInputEvent::FocusChanged(focused));
+            });
+        }
+        edit.layout().add(constraints![
+            align_left(&widget),
+            align_top(&widget),
+            align_bottom(&widget),
+        ]);
+
+        let mut increment = build_spin_button("+", 1.0, &spin_box);
+        increment.set_name("spin_button_increment");
+        let mut decrement = build_spin_button("-", -1.0, &spin_box);
+        decrement.set_name("spin_button_decrement");
+        increment.layout().add(constraints![
+            width(SPIN_BUTTON_WIDTH),
+            to_right_of(&edit),
+            align_right(&widget),
+            align_top(&widget),
+        ]);
+        decrement.layout().add(constraints![
+            match_width(&increment),
+            align_left(&increment),
+            below(&increment),
+            match_height(&increment),
+            align_bottom(&widget),
+        ]);
+
+        widget
+            .add_handler(handler)
+            .add_handler(|event: &WidgetMouseWheel, mut args: EventArgs| {
+                let y = match event.0 {
+                    glutin::MouseScrollDelta::LineDelta(_, y) |
+                    glutin::MouseScrollDelta::PixelDelta(_, y) => y,
+                };
+                if y != 0.0 {
+                    args.widget.event(SpinBoxInputEvent::MouseWheel(event.0));
+                    *args.handled = true;
+                }
+            })
+            .add_handler(|event: &SetSpinBoxValue, args: EventArgs| {
+                args.widget.event(SpinBoxInputEvent::SetValue(event.0));
+            })
+            .add_handler(|event: &SetSpinBoxRange, args: EventArgs| {
+                args.widget.event(SpinBoxInputEvent::SetRange(event.0.clone()));
+            });
+        widget.add_child(edit);
+        widget.add_child(increment);
+        widget.add_child(decrement);
+        widget
+    }
+}
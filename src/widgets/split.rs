@@ -0,0 +1,410 @@
+//! A split pane, dividing its space between two or more panes separated by draggable dividers.
+
+use std::time::{Duration, Instant};
+
+use cassowary::Constraint;
+use glutin;
+use cassowary::strength::*;
+use cassowary::WeightedRelation::*;
+
+use layout::constraint::*;
+use layout::LayoutUpdated;
+use layout::linear_layout::Orientation;
+use event::{EventHandler, EventArgs};
+use widget::{WidgetBuilder, WidgetRef};
+use widget::property::states::*;
+use widgets::drag::{DragEvent, DragState};
+use input::mouse::ClickEvent;
+use draw::rect::{RectState, RectStyle};
+use color::*;
+
+/// Thickness of the dividers between panes
+const DIVIDER_SIZE: f32 = 6.0;
+/// Maximum time between two clicks on a divider for them to count as a double click
+const DOUBLE_CLICK_TIME_MS: u64 = 400;
+
+lazy_static! {
+    pub static ref STYLE_SPLIT_DIVIDER: Vec<RectStyle> = {
+        style!(RectStyle::BackgroundColor: selector!(GRAY_70, MOUSEOVER: GRAY_60))
+    };
+}
+
+/// Emitted by a split pane when its panes are resized by the user, with the new size of
+/// each pane as a fraction of the space available to the panes.
+/// The ratios can be stored and restored later with `SplitPaneBuilder::set_ratios` or `SetSplitRatios`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SplitRatiosChanged(pub Vec<f32>);
+
+/// Send to a split pane to resize its panes, see `SplitPaneBuilder::set_ratios`
+#[derive(Debug, Clone)]
+pub struct SetSplitRatios(pub Vec<f32>);
+
+enum SplitInputEvent {
+    SetRatios(Vec<f32>),
+    Drag(usize, DragEvent),
+    Click(usize),
+    Resized,
+}
+
+struct Pane {
+    widget: WidgetRef,
+    min_size: f32,
+    max_size: Option<f32>,
+    /// Constraints enforcing `min_size` and `max_size`, removed while the pane is collapsed
+    limits: Vec<Constraint>,
+    /// The ratio the pane had before it was collapsed
+    collapsed: Option<f32>,
+}
+
+impl Pane {
+    fn size(&self, orientation: Orientation) -> f32 {
+        let bounds = self.widget.bounds();
+        match orientation {
+            Orientation::Horizontal => bounds.size.width,
+            Orientation::Vertical => bounds.size.height,
+        }
+    }
+    fn expand(&mut self) {
+        if self.collapsed.take().is_some() {
+            let limits = self.limits.clone();
+            self.widget.update_layout(|layout| layout.add(limits));
+        }
+    }
+    fn collapse(&mut self, ratio: f32) {
+        if self.collapsed.is_none() {
+            self.collapsed = Some(ratio);
+            let limits = self.limits.clone();
+            self.widget.update_layout(|layout| layout.remove_constraints(limits));
+        }
+    }
+}
+
+struct SplitPaneHandler {
+    widget: WidgetRef,
+    orientation: Orientation,
+    panes: Vec<Pane>,
+    /// Size of each pane, as a fraction of the space available to the panes
+    ratios: Vec<f32>,
+    /// Sizes of the two panes around the divider being dragged, when the drag started
+    drag_start: (f32, f32),
+    last_click: Option<(usize, Instant)>,
+}
+
+impl SplitPaneHandler {
+    /// Space along the split axis available to the panes, excluding the dividers
+    fn available(&self) -> f32 {
+        let bounds = self.widget.bounds();
+        let size = match self.orientation {
+            Orientation::Horizontal => bounds.size.width,
+            Orientation::Vertical => bounds.size.height,
+        };
+        let dividers = (self.panes.len() - 1) as f32 * DIVIDER_SIZE;
+        (size - dividers).max(0.0)
+    }
+    fn set_ratios(&mut self, ratios: &[f32]) {
+        if ratios.len() != self.panes.len() {
+            return;
+        }
+        let total: f32 = ratios.iter().sum();
+        if total <= 0.0 {
+            return;
+        }
+        self.ratios = ratios.iter().map(|ratio| ratio / total).collect();
+    }
+    /// Resize every pane but the last to match `ratios`, the last pane takes the remaining space
+    fn update_sizes(&self) {
+        let available = self.available();
+        let orientation = self.orientation;
+        for (pane, ratio) in self.panes.iter().zip(self.ratios.iter()).take(self.panes.len() - 1) {
+            let size = ratio * available;
+            pane.widget.update_layout(|layout| {
+                match orientation {
+                    Orientation::Horizontal => layout.edit_width().set(size),
+                    Orientation::Vertical => layout.edit_height().set(size),
+                };
+            });
+        }
+    }
+    fn drag(&mut self, divider: usize, event: &DragEvent, args: &mut EventArgs) {
+        let (before, after) = (divider, divider + 1);
+        match event.state {
+            DragState::Start => {
+                self.panes[before].expand();
+                self.panes[after].expand();
+                self.drag_start = (self.panes[before].size(self.orientation), self.panes[after].size(self.orientation));
+            }
+            DragState::Moved => {
+                let offset = match self.orientation {
+                    Orientation::Horizontal => event.offset.x,
+                    Orientation::Vertical => event.offset.y,
+                };
+                let (start_before, start_after) = self.drag_start;
+                let total = start_before + start_after;
+                let (min_before, max_before) = (self.panes[before].min_size, self.panes[before].max_size);
+                let (min_after, max_after) = (self.panes[after].min_size, self.panes[after].max_size);
+                // respect the limits of both panes, the pane before the divider gets priority
+                let mut size = start_before + offset;
+                if let Some(max_after) = max_after {
+                    size = size.max(total - max_after);
+                }
+                size = size.min(total - min_after);
+                if let Some(max_before) = max_before {
+                    size = size.min(max_before);
+                }
+                size = size.max(min_before).max(0.0).min(total);
+
+                let available = self.available();
+                if available > 0.0 {
+                    let ratio_total = self.ratios[before] + self.ratios[after];
+                    self.ratios[before] = ratio_total * size / total.max(1.0);
+                    self.ratios[after] = ratio_total - self.ratios[before];
+                    self.update_sizes();
+                }
+            }
+            DragState::End => {
+                args.widget.event(SplitRatiosChanged(self.ratios.clone()));
+            }
+        }
+    }
+    /// Collapse the smaller of the panes around the divider, or restore a pane collapsed previously
+    fn toggle_collapse(&mut self, divider: usize, args: &mut EventArgs) {
+        let (before, after) = (divider, divider + 1);
+        let collapsed = if self.panes[before].collapsed.is_some() {
+            Some((before, after))
+        } else if self.panes[after].collapsed.is_some() {
+            Some((after, before))
+        } else {
+            None
+        };
+        if let Some((pane, neighbour)) = collapsed {
+            let ratio = self.panes[pane].collapsed.unwrap_or(0.0).min(self.ratios[neighbour]);
+            self.panes[pane].expand();
+            self.ratios[pane] += ratio;
+            self.ratios[neighbour] -= ratio;
+        } else {
+            let (pane, neighbour) = if self.ratios[before] <= self.ratios[after] {
+                (before, after)
+            } else {
+                (after, before)
+            };
+            let ratio = self.ratios[pane];
+            self.panes[pane].collapse(ratio);
+            self.ratios[pane] = 0.0;
+            self.ratios[neighbour] += ratio;
+        }
+        self.update_sizes();
+        args.widget.event(SplitRatiosChanged(self.ratios.clone()));
+    }
+}
+
+impl EventHandler<SplitInputEvent> for SplitPaneHandler {
+    fn handle(&mut self, event: &SplitInputEvent, mut args: EventArgs) {
+        match *event {
+            SplitInputEvent::SetRatios(ref ratios) => {
+                for pane in &mut self.panes {
+                    pane.expand();
+                }
+                self.set_ratios(ratios);
+                self.update_sizes();
+            }
+            SplitInputEvent::Drag(divider, ref event) => {
+                self.drag(divider, event, &mut args);
+            }
+            SplitInputEvent::Click(divider) => {
+                let now = Instant::now();
+                let double_click = match self.last_click {
+                    Some((last_divider, time)) => {
+                        last_divider == divider && now - time < Duration::from_millis(DOUBLE_CLICK_TIME_MS)
+                    }
+                    None => false,
+                };
+                if double_click {
+                    self.last_click = None;
+                    self.toggle_collapse(divider, &mut args);
+                } else {
+                    self.last_click = Some((divider, now));
+                }
+            }
+            SplitInputEvent::Resized => {
+                self.update_sizes();
+            }
+        }
+    }
+}
+
+/// Divides its space between two or more panes, horizontally or vertically,
+/// with a divider between each pair of panes that can be dragged to resize them.
+/// Double clicking a divider collapses the smaller of its panes, or restores a collapsed pane.
+pub struct SplitPaneBuilder {
+    pub widget: WidgetBuilder,
+    orientation: Orientation,
+    panes: Vec<(WidgetBuilder, f32, Option<f32>)>,
+    ratios: Option<Vec<f32>>,
+}
+
+widget_builder!(SplitPaneBuilder);
+
+impl SplitPaneBuilder {
+    /// Creates a new, empty `SplitPaneBuilder`, horizontal panes are placed left to right,
+    /// vertical panes top to bottom
+    pub fn new(orientation: Orientation) -> Self {
+        SplitPaneBuilder {
+            widget: WidgetBuilder::new("split_pane"),
+            orientation: orientation,
+            panes: Vec::new(),
+            ratios: None,
+        }
+    }
+
+    /// Add a pane after the existing panes
+    pub fn add_pane<U: Into<WidgetBuilder>>(&mut self, pane: U) -> &mut Self {
+        self.add_pane_with_limits(pane, 0.0, None)
+    }
+
+    /// Add a pane after the existing panes, which can't be made smaller than `min_size` or,
+    /// if set, larger than `max_size`, along the split axis
+    pub fn add_pane_with_limits<U>(&mut self, pane: U, min_size: f32, max_size: Option<f32>) -> &mut Self
+        where U: Into<WidgetBuilder>
+    {
+        self.panes.push((pane.into(), min_size, max_size));
+        self
+    }
+
+    /// Set the initial size of each pane, relative to the other panes, eg. the ratios
+    /// emitted by `SplitRatiosChanged`. Panes are the same size by default.
+    pub fn set_ratios(&mut self, ratios: Vec<f32>) -> &mut Self {
+        self.ratios = Some(ratios);
+        self
+    }
+
+    /// Callback function to be called when the panes are resized by the user,
+    /// with the new ratios of the panes
+    pub fn on_ratios_changed<F>(&mut self, callback: F) -> &mut Self
+        where F: Fn(&[f32], &mut EventArgs) + 'static
+    {
+        self.widget.add_handler(move |event: &SplitRatiosChanged, mut args: EventArgs| {
+            callback(&event.0, &mut args);
+        });
+        self
+    }
+}
+
+impl Into<WidgetBuilder> for SplitPaneBuilder {
+    fn into(self) -> WidgetBuilder {
+        let mut widget = self.widget;
+        let orientation = self.orientation;
+        let pane_count = self.panes.len();
+
+        let mut panes = Vec::new();
+        let mut prev: Option<WidgetRef> = None;
+        for (index, (mut pane, min_size, max_size)) in self.panes.into_iter().enumerate() {
+            if let Some(ref prev) = prev {
+                let mut divider = WidgetBuilder::new("split_divider");
+                divider
+                    .set_draw_state_with_style(RectState::new(), STYLE_SPLIT_DIVIDER.clone())
+                    .enable_hover()
+                    .set_cursor(match orientation {
+                        Orientation::Horizontal => glutin::MouseCursor::ColResize,
+                        Orientation::Vertical => glutin::MouseCursor::RowResize,
+                    })
+                    .make_draggable();
+                let divider_index = index - 1;
+                let split = widget.widget_ref();
+                divider.add_handler(move |event: &DragEvent, _: EventArgs| {
+                    split.event(SplitInputEvent::Drag(divider_index, *event));
+                });
+                let split = widget.widget_ref();
+                divider.add_handler(move |_: &ClickEvent, mut args: EventArgs| {
+                    split.event(SplitInputEvent::Click(divider_index));
+                    *args.handled = true;
+                });
+                match orientation {
+                    Orientation::Horizontal => divider.layout().add(constraints![
+                        width(DIVIDER_SIZE),
+                        align_to_right_of(prev),
+                        align_top(&widget),
+                        align_bottom(&widget),
+                    ]),
+                    Orientation::Vertical => divider.layout().add(constraints![
+                        height(DIVIDER_SIZE),
+                        align_below(prev),
+                        align_left(&widget),
+                        align_right(&widget),
+                    ]),
+                }
+                match orientation {
+                    Orientation::Horizontal => pane.layout().add(align_to_right_of(&divider)),
+                    Orientation::Vertical => pane.layout().add(align_below(&divider)),
+                }
+                widget.add_child(divider);
+            } else {
+                match orientation {
+                    Orientation::Horizontal => pane.layout().add(align_left(&widget)),
+                    Orientation::Vertical => pane.layout().add(align_top(&widget)),
+                }
+            }
+            if index == pane_count - 1 {
+                match orientation {
+                    Orientation::Horizontal => pane.layout().add(align_right(&widget)),
+                    Orientation::Vertical => pane.layout().add(align_bottom(&widget)),
+                }
+            }
+            match orientation {
+                Orientation::Horizontal => pane.layout().add(constraints![
+                    align_top(&widget),
+                    align_bottom(&widget),
+                ]),
+                Orientation::Vertical => pane.layout().add(constraints![
+                    align_left(&widget),
+                    align_right(&widget),
+                ]),
+            }
+
+            let limits = {
+                let vars = pane.widget_ref().layout_vars();
+                let size = match orientation {
+                    Orientation::Horizontal => vars.width,
+                    Orientation::Vertical => vars.height,
+                };
+                let mut limits = vec![ size | GE(REQUIRED) | min_size as f64 ];
+                if let Some(max_size) = max_size {
+                    limits.push(size | LE(REQUIRED) | max_size as f64);
+                }
+                limits
+            };
+            pane.layout().add(limits.clone());
+            prev = Some(pane.widget_ref());
+            panes.push(Pane {
+                widget: pane.widget_ref(),
+                min_size: min_size,
+                max_size: max_size,
+                limits: limits,
+                collapsed: None,
+            });
+            widget.add_child(pane);
+        }
+
+        if pane_count > 0 {
+            let mut handler = SplitPaneHandler {
+                widget: widget.widget_ref(),
+                orientation: orientation,
+                panes: panes,
+                ratios: vec![1.0 / pane_count as f32; pane_count],
+                drag_start: (0.0, 0.0),
+                last_click: None,
+            };
+            if let Some(ratios) = self.ratios {
+                handler.set_ratios(&ratios);
+            }
+            widget
+                .add_handler(handler)
+                .add_handler(|_: &LayoutUpdated, args: EventArgs| {
+                    args.widget.event(SplitInputEvent::Resized);
+                })
+                .add_handler(|event: &SetSplitRatios, args: EventArgs| {
+                    args.widget.event(SplitInputEvent::SetRatios(event.0.clone()));
+                });
+        }
+        widget
+    }
+}
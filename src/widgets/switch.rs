@@ -0,0 +1,196 @@
+use cassowary::Constraint;
+
+use layout::constraint::*;
+use event::{EventHandler, EventArgs};
+use widget::{WidgetBuilder, WidgetRef};
+use widget::property::Property;
+use widget::property::states::*;
+use widgets::button::ToggleEvent;
+use input::mouse::ClickEvent;
+use draw::rect::{RectState, RectStyle};
+use draw::ellipse::{EllipseState, EllipseStyle};
+use geometry::Size;
+use color::*;
+
+static COLOR_SWITCH_TRACK: Color = GRAY_70;
+static COLOR_SWITCH_TRACK_ACTIVATED: Color = BLUE_HIGHLIGHT;
+static COLOR_SWITCH_TRACK_INACTIVE: Color = GRAY_90;
+static COLOR_SWITCH_KNOB: Color = WHITE;
+static COLOR_SWITCH_KNOB_MOUSEOVER: Color = GRAY_90;
+
+static SWITCH_BORDER_FOCUSED: (f32, Color) = (1.0, BLUE);
+static SWITCH_KNOB_BORDER: (f32, Color) = (1.0, GRAY_40);
+static SWITCH_KNOB_BORDER_INACTIVE: (f32, Color) = (1.0, GRAY_70);
+
+const SWITCH_WIDTH: f32 = 44.0;
+const SWITCH_HEIGHT: f32 = 24.0;
+const SWITCH_KNOB_PADDING: f32 = 2.0;
+
+lazy_static! {
+    pub static ref STYLE_SWITCH_TRACK: Vec<RectStyle> = {
+        style!(
+            RectStyle::BackgroundColor: selector!(COLOR_SWITCH_TRACK,
+                INACTIVE: COLOR_SWITCH_TRACK_INACTIVE,
+                ACTIVATED: COLOR_SWITCH_TRACK_ACTIVATED),
+            RectStyle::CornerRadius: Some(SWITCH_HEIGHT / 2.0),
+            RectStyle::Border: selector!(None, FOCUSED: Some(SWITCH_BORDER_FOCUSED))
+        )
+    };
+    pub static ref STYLE_SWITCH_KNOB: Vec<EllipseStyle> = {
+        style!(
+            EllipseStyle::BackgroundColor: selector!(COLOR_SWITCH_KNOB,
+                MOUSEOVER: COLOR_SWITCH_KNOB_MOUSEOVER),
+            EllipseStyle::Border: selector!(Some(SWITCH_KNOB_BORDER),
+                INACTIVE: Some(SWITCH_KNOB_BORDER_INACTIVE))
+        )
+    };
+}
+
+/// Send to a switch to turn it on or off, without emitting a `ToggleEvent`
+#[derive(Debug, Copy, Clone)]
+pub struct SetSwitchState(pub bool);
+
+#[derive(Debug, Copy, Clone)]
+enum SwitchInputEvent {
+    Click,
+    SetState(bool),
+}
+
+struct SwitchHandler {
+    knob: WidgetRef,
+    off_constraints: Vec<Constraint>,
+    on_constraints: Vec<Constraint>,
+    on: bool,
+}
+
+impl SwitchHandler {
+    fn new(knob: WidgetRef, track: WidgetRef, on: bool) -> Self {
+        let knob_vars = knob.layout_vars();
+        SwitchHandler {
+            knob: knob,
+            off_constraints: align_left(&track).padding(SWITCH_KNOB_PADDING).build(&knob_vars),
+            on_constraints: align_right(&track).padding(SWITCH_KNOB_PADDING).build(&knob_vars),
+            on: on,
+        }
+    }
+    fn current_constraints(&self) -> Vec<Constraint> {
+        if self.on {
+            self.on_constraints.clone()
+        } else {
+            self.off_constraints.clone()
+        }
+    }
+    fn set_state(&mut self, args: &mut EventArgs, on: bool) {
+        if on == self.on {
+            return;
+        }
+        let old_constraints = self.current_constraints();
+        self.on = on;
+        let new_constraints = self.current_constraints();
+        self.knob.update_layout(|layout| {
+            layout.remove_constraints(old_constraints);
+            layout.add(new_constraints);
+        });
+        if on {
+            args.widget.add_prop(Property::Activated);
+        } else {
+            args.widget.remove_prop(Property::Activated);
+        }
+    }
+}
+
+impl EventHandler<SwitchInputEvent> for SwitchHandler {
+    fn handle(&mut self, event: &SwitchInputEvent, mut args: EventArgs) {
+        match *event {
+            SwitchInputEvent::Click => {
+                if args.widget.props().contains(&Property::Inactive) {
+                    return;
+                }
+                let on = !self.on;
+                self.set_state(&mut args, on);
+                args.widget.event(if on { ToggleEvent::On } else { ToggleEvent::Off });
+            }
+            SwitchInputEvent::SetState(on) => {
+                self.set_state(&mut args, on);
+            }
+        }
+    }
+}
+
+/// An on / off toggle switch, with a knob that slides along a track
+pub struct SwitchBuilder {
+    pub widget: WidgetBuilder,
+    knob: WidgetBuilder,
+    init_on: bool,
+}
+
+widget_builder!(SwitchBuilder);
+
+impl Default for SwitchBuilder {
+    fn default() -> Self {
+        let mut widget = WidgetBuilder::new("switch");
+        widget
+            .set_draw_state_with_style(RectState::new(), STYLE_SWITCH_TRACK.clone())
+            .enable_hover()
+            .make_focusable()
+            .click_on_activate_key();
+        widget.layout().add(size(Size::new(SWITCH_WIDTH, SWITCH_HEIGHT)));
+
+        let mut knob = WidgetBuilder::new("switch_knob");
+        knob.set_draw_state_with_style(EllipseState::new(), STYLE_SWITCH_KNOB.clone());
+        let knob_size = SWITCH_HEIGHT - SWITCH_KNOB_PADDING * 2.0;
+        knob.layout().add(constraints![
+            size(Size::new(knob_size, knob_size)),
+            center_vertical(&widget),
+        ]);
+
+        SwitchBuilder {
+            widget: widget,
+            knob: knob,
+            init_on: false,
+        }
+    }
+}
+
+impl SwitchBuilder {
+    /// Creates a new `SwitchBuilder`, initially off
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set whether the switch is initially on
+    pub fn set_on(&mut self, on: bool) -> &mut Self {
+        self.init_on = on;
+        self
+    }
+
+    /// Callback function to be called when the switch is toggled
+    pub fn on_toggle<F>(&mut self, callback: F) -> &mut Self
+        where F: Fn(&ToggleEvent, EventArgs) + 'static
+    {
+        self.widget.add_handler(callback);
+        self
+    }
+}
+
+impl Into<WidgetBuilder> for SwitchBuilder {
+    fn into(self) -> WidgetBuilder {
+        let (mut widget, mut knob) = (self.widget, self.knob);
+        let handler = SwitchHandler::new(knob.widget_ref(), widget.widget_ref(), self.init_on);
+        knob.layout().add(handler.current_constraints());
+        widget
+            .add_handler(handler)
+            .add_handler(|_: &ClickEvent, mut args: EventArgs| {
+                args.widget.event(SwitchInputEvent::Click);
+                *args.handled = true;
+            })
+            .add_handler(|event: &SetSwitchState, args: EventArgs| {
+                args.widget.event(SwitchInputEvent::SetState(event.0));
+            });
+        widget.add_child(knob);
+        if self.init_on {
+            widget.add_prop(Property::Activated);
+        }
+        widget
+    }
+}
@@ -0,0 +1,400 @@
+//! A tab view, a strip of tab headers that switch between child panels.
+
+use glutin;
+use cassowary::Constraint;
+
+use layout::constraint::*;
+use event::{EventHandler, EventArgs};
+use widget::{WidgetBuilder, WidgetRef};
+use widget::property::Property;
+use widget::property::states::*;
+use widgets::text::TextBuilder;
+use widgets::drag::{DragEvent, DragState};
+use input::mouse::ClickEvent;
+use input::keyboard::WidgetKeyboardInput;
+use draw::rect::{RectState, RectStyle};
+use draw::text::TextStyle;
+use geometry::Size;
+use color::*;
+
+static COLOR_TAB: Color = GRAY_80;
+static COLOR_TAB_MOUSEOVER: Color = GRAY_90;
+static COLOR_TAB_ACTIVATED: Color = WHITE;
+
+lazy_static! {
+    pub static ref STYLE_TAB_STRIP: Vec<RectStyle> = {
+        style!(
+            RectStyle::BackgroundColor: GRAY_70,
+            RectStyle::Border: selector!(None, FOCUSED: Some((1.0, BLUE)))
+        )
+    };
+    pub static ref STYLE_TAB: Vec<RectStyle> = {
+        style!(RectStyle::BackgroundColor: selector!(COLOR_TAB,
+            ACTIVATED: COLOR_TAB_ACTIVATED,
+            MOUSEOVER: COLOR_TAB_MOUSEOVER))
+    };
+    pub static ref STYLE_TAB_TEXT: Vec<TextStyle> = {
+        style!(TextStyle::FontSize: 18.0)
+    };
+    pub static ref STYLE_TAB_CLOSE: Vec<RectStyle> = {
+        style!(
+            RectStyle::BackgroundColor: selector!(TRANSPARENT, MOUSEOVER: GRAY_70),
+            RectStyle::CornerRadius: Some(3.0)
+        )
+    };
+}
+
+/// Emitted by a tab view when the tabs change, with the current index of the affected tab
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum TabEvent {
+    /// A tab became the active tab
+    Selected(usize),
+    /// A tab was closed, the index is the index it had before it was closed
+    Closed(usize),
+    /// A tab was dragged from the first index to the second
+    Moved(usize, usize),
+}
+
+/// Send to a tab view to make the tab at the given index active
+#[derive(Debug, Copy, Clone)]
+pub struct SelectTab(pub usize);
+
+enum TabInputEvent {
+    SelectIndex(usize),
+    Select(WidgetRef),
+    Close(WidgetRef),
+    Drag(WidgetRef, DragEvent),
+    Key(glutin::KeyboardInput),
+}
+
+struct Tab {
+    header: WidgetRef,
+    panel: WidgetRef,
+    /// Constraints positioning the header after the previous header in the strip
+    position: Vec<Constraint>,
+}
+
+struct TabViewHandler {
+    strip: WidgetRef,
+    content: WidgetRef,
+    tabs: Vec<Tab>,
+    selected: Option<usize>,
+}
+
+impl TabViewHandler {
+    fn index_of(&self, header: &WidgetRef) -> Option<usize> {
+        self.tabs.iter().position(|tab| tab.header == *header)
+    }
+    /// Position each header to the right of the previous one, in the order of `tabs`
+    fn update_positions(&mut self) {
+        let mut prev: Option<WidgetRef> = None;
+        for tab in &mut self.tabs {
+            let position = {
+                let vars = tab.header.layout_vars();
+                match prev {
+                    Some(ref prev) => to_right_of(prev).build(&vars),
+                    None => align_left(&self.strip).build(&vars),
+                }
+            };
+            let old_position = ::std::mem::replace(&mut tab.position, position.clone());
+            tab.header.update_layout(|layout| {
+                layout.remove_constraints(old_position);
+                layout.add(position);
+            });
+            prev = Some(tab.header.clone());
+        }
+    }
+    fn select(&mut self, index: usize, args: &mut EventArgs) {
+        if self.selected == Some(index) || index >= self.tabs.len() {
+            return;
+        }
+        if let Some(old) = self.selected.and_then(|old| self.tabs.get_mut(old)) {
+            old.header.remove_prop(Property::Activated);
+            old.panel.update_layout(|layout| layout.hide());
+        }
+        {
+            let tab = &mut self.tabs[index];
+            tab.header.add_prop(Property::Activated);
+            tab.panel.update_layout(|layout| layout.show());
+        }
+        self.selected = Some(index);
+        args.widget.event(TabEvent::Selected(index));
+    }
+    fn close(&mut self, index: usize, args: &mut EventArgs) {
+        let tab = self.tabs.remove(index);
+        self.update_positions();
+        self.strip.remove_child(tab.header);
+        self.content.remove_child(tab.panel);
+        args.widget.event(TabEvent::Closed(index));
+        if let Some(selected) = self.selected {
+            if selected == index {
+                self.selected = None;
+                if !self.tabs.is_empty() {
+                    let new_selected = if index < self.tabs.len() { index } else { index - 1 };
+                    self.select(new_selected, args);
+                }
+            } else if selected > index {
+                self.selected = Some(selected - 1);
+            }
+        }
+    }
+    fn move_tab(&mut self, from: usize, to: usize, args: &mut EventArgs) {
+        let tab = self.tabs.remove(from);
+        self.tabs.insert(to, tab);
+        if let Some(selected) = self.selected {
+            self.selected = Some(if selected == from {
+                to
+            } else if from < selected && selected <= to {
+                selected - 1
+            } else if to <= selected && selected < from {
+                selected + 1
+            } else {
+                selected
+            });
+        }
+        self.update_positions();
+        args.widget.event(TabEvent::Moved(from, to));
+    }
+}
+
+impl EventHandler<TabInputEvent> for TabViewHandler {
+    fn handle(&mut self, event: &TabInputEvent, mut args: EventArgs) {
+        match *event {
+            TabInputEvent::SelectIndex(index) => {
+                self.select(index, &mut args);
+            }
+            TabInputEvent::Select(ref header) => {
+                if let Some(index) = self.index_of(header) {
+                    self.select(index, &mut args);
+                }
+            }
+            TabInputEvent::Close(ref header) => {
+                if let Some(index) = self.index_of(header) {
+                    self.close(index, &mut args);
+                }
+            }
+            TabInputEvent::Drag(ref header, ref event) => {
+                if event.state != DragState::Moved {
+                    return;
+                }
+                // swap places with the tab under the mouse, as the header is dragged over it
+                let from = self.index_of(header);
+                let to = self.tabs.iter().position(|tab| {
+                    let bounds = tab.header.visible_bounds();
+                    event.position.x >= bounds.origin.x && event.position.x < bounds.origin.x + bounds.size.width
+                });
+                if let (Some(from), Some(to)) = (from, to) {
+                    if from != to {
+                        self.move_tab(from, to, &mut args);
+                    }
+                }
+            }
+            TabInputEvent::Key(input) => {
+                if input.state != glutin::ElementState::Pressed || self.tabs.is_empty() {
+                    return;
+                }
+                let last = self.tabs.len() - 1;
+                let selected = self.selected.unwrap_or(0);
+                let ctrl = input.modifiers.ctrl;
+                let index = match input.virtual_keycode {
+                    Some(glutin::VirtualKeyCode::Left) => selected.saturating_sub(1),
+                    Some(glutin::VirtualKeyCode::PageUp) if ctrl => selected.saturating_sub(1),
+                    Some(glutin::VirtualKeyCode::Right) => ::std::cmp::min(selected + 1, last),
+                    Some(glutin::VirtualKeyCode::PageDown) if ctrl => ::std::cmp::min(selected + 1, last),
+                    Some(glutin::VirtualKeyCode::Home) => 0,
+                    Some(glutin::VirtualKeyCode::End) => last,
+                    _ => return,
+                };
+                self.select(index, &mut args);
+            }
+        }
+    }
+}
+
+/// A strip of tab headers above a content area, showing the panel of the active tab.
+/// The tab strip can be focused to switch tabs with the arrow keys.
+pub struct TabViewBuilder {
+    pub widget: WidgetBuilder,
+    tabs: Vec<(String, WidgetBuilder)>,
+    selected: usize,
+    closable: bool,
+}
+
+widget_builder!(TabViewBuilder);
+
+impl Default for TabViewBuilder {
+    fn default() -> Self {
+        TabViewBuilder {
+            widget: WidgetBuilder::new("tab_view"),
+            tabs: Vec::new(),
+            selected: 0,
+            closable: false,
+        }
+    }
+}
+
+impl TabViewBuilder {
+    /// Creates a new, empty `TabViewBuilder`
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a tab with a header showing `title`, that shows `panel` when active
+    pub fn add_tab<U: Into<WidgetBuilder>>(&mut self, title: &str, panel: U) -> &mut Self {
+        self.tabs.push((title.to_owned(), panel.into()));
+        self
+    }
+
+    /// Set the index of the initially active tab
+    pub fn set_selected(&mut self, index: usize) -> &mut Self {
+        self.selected = index;
+        self
+    }
+
+    /// Show a close button on each tab header
+    pub fn set_closable(&mut self, closable: bool) -> &mut Self {
+        self.closable = closable;
+        self
+    }
+
+    /// Callback function to be called when a tab is selected, closed, or moved
+    pub fn on_tab_event<F>(&mut self, callback: F) -> &mut Self
+        where F: Fn(&TabEvent, EventArgs) + 'static
+    {
+        self.widget.add_handler(callback);
+        self
+    }
+}
+
+fn build_header(title: &str, strip: &WidgetBuilder, view: &WidgetRef, closable: bool) -> WidgetBuilder {
+    let mut header = WidgetBuilder::new("tab_header");
+    header
+        .set_draw_state_with_style(RectState::new(), STYLE_TAB.clone())
+        .enable_hover()
+        .make_draggable();
+    header.layout().add(constraints![
+        align_top(strip).padding(4.0),
+        align_bottom(strip),
+        shrink_horizontal(),
+    ]);
+    {
+        let view = view.clone();
+        header.add_handler(move |_: &ClickEvent, mut args: EventArgs| {
+            view.event(TabInputEvent::Select(args.widget.clone()));
+            *args.handled = true;
+        });
+    }
+    {
+        let view = view.clone();
+        header.add_handler(move |event: &DragEvent, args: EventArgs| {
+            view.event(TabInputEvent::Drag(args.widget.clone(), *event));
+        });
+    }
+
+    let style = style!(parent: STYLE_TAB_TEXT, TextStyle::Text: title.to_owned());
+    let mut text = TextBuilder::new_with_style(style);
+    text.set_name("tab_header_text");
+    text.layout().add(constraints![
+        align_left(&header).padding(12.0),
+        center_vertical(&header),
+        bound_top(&header).padding(4.0),
+        bound_bottom(&header).padding(4.0),
+    ]);
+
+    if closable {
+        let mut close = WidgetBuilder::new("tab_close");
+        close
+            .set_draw_state_with_style(RectState::new(), STYLE_TAB_CLOSE.clone())
+            .enable_hover();
+        close.layout().add(constraints![
+            size(Size::new(18.0, 18.0)),
+            to_right_of(&text).padding(8.0),
+            align_right(&header).padding(6.0),
+            center_vertical(&header),
+        ]);
+        let mut close_text = TextBuilder::new_with_style(style!(parent: STYLE_TAB_TEXT,
+            TextStyle::Text: "x".to_owned()));
+        close_text.set_name("tab_close_text");
+        close_text.layout().add(center(&close));
+        close.add_child(close_text);
+
+        let (view, header_ref) = (view.clone(), header.widget_ref());
+        close.add_handler(move |_: &ClickEvent, mut args: EventArgs| {
+            view.event(TabInputEvent::Close(header_ref.clone()));
+            *args.handled = true;
+        });
+        header.add_child(text);
+        header.add_child(close);
+    } else {
+        text.layout().add(align_right(&header).padding(12.0));
+        header.add_child(text);
+    }
+    header
+}
+
+impl Into<WidgetBuilder> for TabViewBuilder {
+    fn into(self) -> WidgetBuilder {
+        let mut widget = self.widget;
+        let view = widget.widget_ref();
+
+        let mut strip = WidgetBuilder::new("tab_strip");
+        strip
+            .set_draw_state_with_style(RectState::new(), STYLE_TAB_STRIP.clone())
+            .make_focusable();
+        strip.layout().add(constraints![
+            align_top(&widget),
+            align_left(&widget),
+            align_right(&widget),
+            shrink_vertical(),
+        ]);
+        {
+            let view = view.clone();
+            strip.add_handler(move |event: &WidgetKeyboardInput, _: EventArgs| {
+                view.event(TabInputEvent::Key(event.0));
+            });
+        }
+
+        let mut content = WidgetBuilder::new("tab_content");
+        content.layout().add(constraints![
+            below(&strip),
+            align_left(&widget),
+            align_right(&widget),
+            align_bottom(&widget),
+        ]);
+
+        let mut tabs = Vec::new();
+        for (index, (title, mut panel)) in self.tabs.into_iter().enumerate() {
+            let mut header = build_header(&title, &strip, &view, self.closable);
+            panel.layout().add(match_layout(&content));
+            if index == self.selected {
+                header.add_prop(Property::Activated);
+            } else {
+                panel.layout().hide();
+            }
+            tabs.push(Tab {
+                header: header.widget_ref(),
+                panel: panel.widget_ref(),
+                position: Vec::new(),
+            });
+            strip.add_child(header);
+            content.add_child(panel);
+        }
+
+        let selected = if self.selected < tabs.len() { Some(self.selected) } else { None };
+        let mut handler = TabViewHandler {
+            strip: strip.widget_ref(),
+            content: content.widget_ref(),
+            tabs: tabs,
+            selected: selected,
+        };
+        handler.update_positions();
+        widget
+            .add_handler(handler)
+            .add_handler(|event: &SelectTab, args: EventArgs| {
+                args.widget.event(TabInputEvent::SelectIndex(event.0));
+            });
+        widget.add_child(strip);
+        widget.add_child(content);
+        widget
+    }
+}
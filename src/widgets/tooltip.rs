@@ -0,0 +1,210 @@
+//! Tooltips, floating labels shown after the mouse has hovered over a widget for some time.
+
+use std::rc::Rc;
+use std::time::Duration;
+
+use glutin;
+use cassowary::strength::*;
+
+use layout::constraint::*;
+use event::{EventHandler, EventArgs};
+use widget::{WidgetBuilder, WidgetRef};
+use widgets::text::TextBuilder;
+use input::mouse::{MouseMoved, MouseButton, MouseWheel, MouseOverEvent};
+use draw::rect::{RectState, RectStyle};
+use draw::text::TextStyle;
+use geometry::{Point, Vector};
+use color::*;
+use overlay::is_descendant;
+use app::App;
+
+/// Offset of the tooltip from the cursor, so the tooltip doesn't cover the cursor
+const TOOLTIP_OFFSET_X: f32 = 12.0;
+const TOOLTIP_OFFSET_Y: f32 = 18.0;
+
+lazy_static! {
+    pub static ref STYLE_TOOLTIP: Vec<RectStyle> = {
+        style!(
+            RectStyle::BackgroundColor: WHITE,
+            RectStyle::Border: Some((1.0, GRAY_40))
+        )
+    };
+    pub static ref STYLE_TOOLTIP_TEXT: Vec<TextStyle> = {
+        style!(
+            TextStyle::TextColor: GRAY_20,
+            TextStyle::FontSize: 16.0
+        )
+    };
+}
+
+enum TooltipContent {
+    Text(String),
+    Widget(Box<Fn() -> WidgetBuilder>),
+}
+
+/// The content of a tooltip, and how long the mouse has to hover over the widget before it is shown.
+pub struct Tooltip {
+    content: TooltipContent,
+    delay: Duration,
+}
+
+impl Tooltip {
+    /// A tooltip containing a text label
+    pub fn text(text: &str) -> Self {
+        Tooltip {
+            content: TooltipContent::Text(text.to_owned()),
+            delay: Duration::from_millis(500),
+        }
+    }
+    /// A tooltip containing an arbitrary widget, the function is called
+    /// to create the widget every time the tooltip is shown
+    pub fn widget<F>(build: F) -> Self
+        where F: Fn() -> WidgetBuilder + 'static
+    {
+        Tooltip {
+            content: TooltipContent::Widget(Box::new(build)),
+            delay: Duration::from_millis(500),
+        }
+    }
+    /// Set how long the mouse has to hover over the widget before the tooltip is shown
+    pub fn set_delay(mut self, delay: Duration) -> Self {
+        self.delay = delay;
+        self
+    }
+    fn build(&self) -> WidgetBuilder {
+        match self.content {
+            TooltipContent::Text(ref text) => {
+                let style = style!(parent: STYLE_TOOLTIP_TEXT, TextStyle::Text: text.clone());
+                let mut text_widget = TextBuilder::new_with_style(style);
+                text_widget.set_name("tooltip_text");
+                let mut widget = WidgetBuilder::new("tooltip");
+                widget.set_draw_state_with_style(RectState::new(), STYLE_TOOLTIP.clone());
+                text_widget.layout().add(match_layout(&widget).padding(5.0));
+                widget.add_child(text_widget);
+                widget
+            }
+            TooltipContent::Widget(ref build) => build(),
+        }
+    }
+}
+
+impl<'a> From<&'a str> for Tooltip {
+    fn from(text: &'a str) -> Self {
+        Tooltip::text(text)
+    }
+}
+
+impl From<String> for Tooltip {
+    fn from(text: String) -> Self {
+        Tooltip::text(&text)
+    }
+}
+
+enum TooltipEvent {
+    Hover(WidgetRef, Rc<Tooltip>),
+    Unhover(WidgetRef),
+    /// Sent after the hover delay, ignored if the generation has changed since
+    Show(usize),
+    MouseMoved(Point),
+    Hide,
+}
+
+/// Tracks the widget being hovered over and the mouse position,
+/// and shows a tooltip once the hover delay has passed.
+struct TooltipHandler {
+    mouse: Point,
+    hovered: Option<(WidgetRef, Rc<Tooltip>)>,
+    tooltip: Option<WidgetRef>,
+    /// Incremented whenever a pending tooltip should be cancelled
+    generation: usize,
+}
+
+impl TooltipHandler {
+    fn new() -> Self {
+        TooltipHandler {
+            mouse: Point::zero(),
+            hovered: None,
+            tooltip: None,
+            generation: 0,
+        }
+    }
+    fn hide(&mut self, args: &mut EventArgs) {
+        self.generation += 1;
+        if let Some(tooltip) = self.tooltip.take() {
+            args.ui.close_popup(&tooltip);
+        }
+    }
+}
+
+impl EventHandler<TooltipEvent> for TooltipHandler {
+    fn handle(&mut self, event: &TooltipEvent, mut args: EventArgs) {
+        match *event {
+            TooltipEvent::Hover(ref widget, ref tooltip) => {
+                // hover events bubble up, so prefer the innermost widget with a tooltip
+                if self.hovered.as_ref().map_or(false, |&(ref hovered, _)| is_descendant(hovered, widget)) {
+                    return;
+                }
+                self.hide(&mut args);
+                self.hovered = Some((widget.clone(), tooltip.clone()));
+                args.ui.event_delayed(TooltipEvent::Show(self.generation), tooltip.delay);
+            }
+            TooltipEvent::Unhover(ref widget) => {
+                if self.hovered.as_ref().map_or(false, |&(ref hovered, _)| hovered == widget) {
+                    self.hovered = None;
+                    self.hide(&mut args);
+                }
+            }
+            TooltipEvent::Show(generation) => {
+                if generation != self.generation {
+                    return;
+                }
+                if let Some((_, ref tooltip)) = self.hovered {
+                    let mut widget = tooltip.build();
+                    let position = self.mouse + Vector::new(TOOLTIP_OFFSET_X, TOOLTIP_OFFSET_Y);
+                    widget.layout().add(shrink());
+                    widget.layout().edit_left().set(position.x).strength(STRONG);
+                    widget.layout().edit_top().set(position.y).strength(STRONG);
+                    self.tooltip = Some(args.ui.open_popup(widget, None));
+                }
+            }
+            TooltipEvent::MouseMoved(point) => {
+                self.mouse = point;
+            }
+            TooltipEvent::Hide => {
+                self.hide(&mut args);
+            }
+        }
+    }
+}
+
+impl WidgetBuilder {
+    /// Show a tooltip when the mouse hovers over this widget, either some text, or a `Tooltip`.
+    /// The tooltip is hidden when the mouse leaves the widget, is pressed or scrolled.
+    pub fn set_tooltip<T: Into<Tooltip>>(&mut self, tooltip: T) -> &mut Self {
+        let tooltip = Rc::new(tooltip.into());
+        self.add_handler(move |event: &MouseOverEvent, args: EventArgs| {
+            match *event {
+                MouseOverEvent::Over => args.ui.event(TooltipEvent::Hover(args.widget.clone(), tooltip.clone())),
+                MouseOverEvent::Out => args.ui.event(TooltipEvent::Unhover(args.widget.clone())),
+            }
+        })
+    }
+}
+
+impl App {
+    /// Add handlers that show the tooltips set with `WidgetBuilder::set_tooltip`
+    pub fn add_tooltip_handlers(&mut self) {
+        self.add_handler(|event: &MouseMoved, args: EventArgs| {
+            args.widget.event(TooltipEvent::MouseMoved(event.0));
+        });
+        self.add_handler(|event: &MouseButton, args: EventArgs| {
+            if let MouseButton(glutin::ElementState::Pressed, _) = *event {
+                args.widget.event(TooltipEvent::Hide);
+            }
+        });
+        self.add_handler(|_: &MouseWheel, args: EventArgs| {
+            args.widget.event(TooltipEvent::Hide);
+        });
+        self.add_handler(TooltipHandler::new());
+    }
+}
@@ -0,0 +1,258 @@
+//! Validators and input filters for editable text, see `EditTextBuilder::add_validator`
+//! and `EditTextBuilder::add_filter`.
+//!
+//! Filters reject edits as they are typed, validators check the complete text
+//! and mark the field with the `Invalid` property when it fails.
+
+use std::rc::Rc;
+use std::cell::RefCell;
+use std::ops::Range;
+
+use regex::{self, Regex};
+
+use event::{EventHandler, EventArgs};
+use widget::{WidgetBuilder, WidgetRef};
+use widget::property::Property;
+use widgets::edit_text::TextUpdated;
+use widgets::tooltip::{STYLE_TOOLTIP, STYLE_TOOLTIP_TEXT};
+use widgets::text::TextBuilder;
+use layout::constraint::*;
+use draw::rect::RectState;
+use draw::text::{TextState, TextStyle};
+use color::*;
+
+lazy_static! {
+    pub static ref STYLE_ERROR_TEXT: Vec<TextStyle> = {
+        style!(
+            TextStyle::TextColor: RED,
+            TextStyle::FontSize: 16.0
+        )
+    };
+}
+
+/// Checks the text of a field, returning an error message if the text is invalid
+#[derive(Clone)]
+pub struct Validator(Rc<Fn(&str) -> Result<(), String>>);
+
+impl Validator {
+    /// A validator that calls a closure with the text of the field
+    pub fn new<F>(validate: F) -> Self
+        where F: Fn(&str) -> Result<(), String> + 'static
+    {
+        Validator(Rc::new(validate))
+    }
+    /// Requires the field to not be empty
+    pub fn required() -> Self {
+        Validator::new(|text| {
+            if text.trim().is_empty() { Err("Required".to_owned()) } else { Ok(()) }
+        })
+    }
+    /// Requires the whole text to match a regular expression.
+    /// Panics if the pattern is not a valid regular expression, use `try_regex` for
+    /// patterns that aren't known to be valid.
+    pub fn regex(pattern: &str) -> Self {
+        Validator::try_regex(pattern).unwrap()
+    }
+    /// Requires the whole text to match a regular expression,
+    /// or returns an error if the pattern is not a valid regular expression.
+    pub fn try_regex(pattern: &str) -> Result<Self, regex::Error> {
+        let regex = full_match_regex(pattern)?;
+        Ok(Validator::new(move |text| {
+            if regex.is_match(text) { Ok(()) } else { Err("Invalid format".to_owned()) }
+        }))
+    }
+    /// Requires the text to be a number
+    pub fn numeric() -> Self {
+        Validator::new(|text| {
+            match text.trim().parse::<f64>() {
+                Ok(value) if value.is_finite() => Ok(()),
+                _ => Err("Must be a number".to_owned()),
+            }
+        })
+    }
+    /// Requires the text to be a number within a range, inclusive of the end of the range
+    pub fn range(range: Range<f64>) -> Self {
+        Validator::new(move |text| {
+            match text.trim().parse::<f64>() {
+                Ok(value) if value >= range.start && value <= range.end => Ok(()),
+                Ok(_) => Err(format!("Must be between {} and {}", range.start, range.end)),
+                Err(_) => Err("Must be a number".to_owned()),
+            }
+        })
+    }
+    /// Limits the number of characters in the text
+    pub fn max_length(max_length: usize) -> Self {
+        Validator::new(move |text| {
+            if text.chars().count() <= max_length {
+                Ok(())
+            } else {
+                Err(format!("Must be at most {} characters", max_length))
+            }
+        })
+    }
+    /// Replace the error message of this validator
+    pub fn with_message(self, message: &str) -> Self {
+        let (validate, message) = (self.0, message.to_owned());
+        Validator::new(move |text| validate(text).map_err(|_| message.clone()))
+    }
+    pub fn validate(&self, text: &str) -> Result<(), String> {
+        (self.0)(text)
+    }
+}
+
+/// Compiles a pattern that has to match the whole text
+fn full_match_regex(pattern: &str) -> Result<Regex, regex::Error> {
+    Regex::new(&format!("^(?:{})$", pattern))
+}
+
+/// Decides whether an edit is allowed as it is typed, given the text the edit would produce
+#[derive(Clone)]
+pub struct InputFilter(Rc<Fn(&str) -> bool>);
+
+impl InputFilter {
+    /// A filter that calls a closure with the text the edit would produce
+    pub fn new<F>(filter: F) -> Self
+        where F: Fn(&str) -> bool + 'static
+    {
+        InputFilter(Rc::new(filter))
+    }
+    /// Only allows text that is a number, or could become one as more is typed
+    pub fn numeric() -> Self {
+        InputFilter::new(|text| {
+            let digits = text.trim_left_matches('-');
+            text.len() - digits.len() <= 1 &&
+                digits.matches('.').count() <= 1 &&
+                digits.chars().all(|c| c.is_digit(10) || c == '.')
+        })
+    }
+    /// Only allows whole numbers, or text that could become one as more is typed
+    pub fn integer() -> Self {
+        InputFilter::new(|text| {
+            let digits = text.trim_left_matches('-');
+            text.len() - digits.len() <= 1 && digits.chars().all(|c| c.is_digit(10))
+        })
+    }
+    /// Only allows text matching a regular expression, which should also match any incomplete
+    /// text that is allowed while typing. Panics if the pattern is not a valid regular expression,
+    /// use `try_regex` for patterns that aren't known to be valid.
+    pub fn regex(pattern: &str) -> Self {
+        InputFilter::try_regex(pattern).unwrap()
+    }
+    /// Like `regex`, but returns an error if the pattern is not a valid regular expression.
+    pub fn try_regex(pattern: &str) -> Result<Self, regex::Error> {
+        let regex = full_match_regex(pattern)?;
+        Ok(InputFilter::new(move |text| regex.is_match(text)))
+    }
+    /// Prevents typing more than `max_length` characters
+    pub fn max_length(max_length: usize) -> Self {
+        InputFilter::new(move |text| text.chars().count() <= max_length)
+    }
+    pub fn accepts(&self, text: &str) -> bool {
+        (self.0)(text)
+    }
+}
+
+/// Emitted by an edit text widget when its text becomes valid or invalid,
+/// with the error message of the first validator that failed
+#[derive(Debug, Clone)]
+pub struct ValidityChanged(pub Result<(), String>);
+
+/// Sent up the widget tree from a field whenever it's validated, so forms can track its validity
+#[derive(Debug, Clone)]
+pub(crate) struct FieldValidity(pub WidgetRef, pub bool);
+
+/// Sent up the widget tree from a field when it's removed, so forms stop tracking it.
+/// A removed widget keeps its parent, so this still reaches the form.
+#[derive(Debug, Clone)]
+pub(crate) struct FieldDetached(pub WidgetRef);
+
+pub(crate) enum ValidationEvent {
+    /// The field was added to the widget tree, it's validated so forms know whether it's valid,
+    /// but isn't marked invalid until it's edited
+    Attached,
+    Detached,
+    Text(String),
+}
+
+/// Validates the text of an edit text widget whenever it changes
+pub(crate) struct ValidationHandler {
+    pub field: WidgetRef,
+    pub validators: Vec<Validator>,
+    pub error_labels: Vec<WidgetRef>,
+    pub message: Rc<RefCell<Option<String>>>,
+    pub validity: Option<Result<(), String>>,
+}
+
+impl ValidationHandler {
+    fn validate(&self, text: &str) -> Result<(), String> {
+        for validator in &self.validators {
+            validator.validate(text)?;
+        }
+        Ok(())
+    }
+    fn show_validity(&mut self, validity: &Result<(), String>) {
+        let message = validity.clone().err();
+        *self.message.borrow_mut() = message.clone();
+        for label in &self.error_labels {
+            label.event(TextUpdated(message.clone().unwrap_or_default()));
+        }
+        let invalid = self.field.props().contains(&Property::Invalid);
+        if validity.is_ok() && invalid {
+            self.field.remove_prop(Property::Invalid);
+        } else if validity.is_err() && !invalid {
+            self.field.add_prop(Property::Invalid);
+        }
+    }
+}
+
+impl EventHandler<ValidationEvent> for ValidationHandler {
+    fn handle(&mut self, event: &ValidationEvent, mut args: EventArgs) {
+        let validity = match *event {
+            ValidationEvent::Attached => {
+                let text = {
+                    let draw_state = args.widget.draw_state();
+                    draw_state.downcast_ref::<TextState>().map(|state| state.text.clone()).unwrap_or_default()
+                };
+                self.validate(&text)
+            }
+            ValidationEvent::Detached => {
+                self.field.event_bubble_up(FieldDetached(self.field.clone()));
+                return;
+            }
+            ValidationEvent::Text(ref text) => {
+                let validity = self.validate(text);
+                self.show_validity(&validity);
+                validity
+            }
+        };
+        self.field.event_bubble_up(FieldValidity(self.field.clone(), validity.is_ok()));
+        if self.validity.as_ref() != Some(&validity) {
+            self.field.event(ValidityChanged(validity.clone()));
+            self.validity = Some(validity);
+        }
+    }
+}
+
+/// Builds a label that shows the error message of a field
+pub(crate) fn build_error_label() -> WidgetBuilder {
+    let mut label = TextBuilder::new_with_style(STYLE_ERROR_TEXT.clone());
+    label.set_name("error_label");
+    label.add_handler(::widgets::edit_text::text_change_handle);
+    label
+}
+
+/// Builds a tooltip showing the error message of a field, or nothing if it's valid
+pub(crate) fn build_error_tooltip(message: &Rc<RefCell<Option<String>>>) -> WidgetBuilder {
+    let mut widget = WidgetBuilder::new("error_tooltip");
+    if let Some(ref message) = *message.borrow() {
+        let style = style!(parent: STYLE_TOOLTIP_TEXT,
+            TextStyle::TextColor: RED,
+            TextStyle::Text: message.clone());
+        let mut text = TextBuilder::new_with_style(style);
+        text.set_name("error_tooltip_text");
+        widget.set_draw_state_with_style(RectState::new(), STYLE_TOOLTIP.clone());
+        text.layout().add(match_layout(&widget).padding(5.0));
+        widget.add_child(text);
+    }
+    widget
+}
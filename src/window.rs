@@ -2,11 +2,17 @@ use gleam::gl;
 use glutin;
 use glutin::GlContext;
 use webrender::api::DeviceUintSize;
-use geometry::Size;
+use geometry::{Point, Size};
 
 /// A simple wrapper around a `glutin::GlWindow`.
+///
+/// There are no methods to set the window icon or keep the window on top of others,
+/// since the version of winit used by glutin 0.10 supports neither.
 pub struct Window {
-    pub window: glutin::GlWindow
+    pub window: glutin::GlWindow,
+    min_size: Option<Size>,
+    max_size: Option<Size>,
+    cursor: glutin::MouseCursor,
 }
 impl Window {
     pub fn new(window_builder: glutin::WindowBuilder, events_loop: &glutin::EventsLoop) -> Self {
@@ -17,10 +23,17 @@ impl Window {
                 opengles_version: (3, 0)
             });
 
+        // keep the limits given to the window manager, so they're also enforced if it ignores them
+        let (min_dimensions, max_dimensions) = (window_builder.window.min_dimensions, window_builder.window.max_dimensions);
         let window = glutin::GlWindow::new(window_builder, context, events_loop).unwrap();
         unsafe { window.make_current().ok() };
+        let hidpi_factor = window.hidpi_factor();
+        let to_size = |(width, height): (u32, u32)| Size::new(width as f32 / hidpi_factor, height as f32 / hidpi_factor);
         Window {
-            window: window
+            window: window,
+            min_size: min_dimensions.map(&to_size),
+            max_size: max_dimensions.map(&to_size),
+            cursor: glutin::MouseCursor::Default,
         }
     }
     pub fn gl(&self) -> ::std::rc::Rc<gl::Gl> {
@@ -36,7 +49,80 @@ impl Window {
         self.window.hidpi_factor()
     }
     pub fn resize(&mut self, width: u32, height: u32) {
-        self.window.set_inner_size(width, height);
+        let size = self.clamp_size(Size::new(width as f32, height as f32));
+        self.window.set_inner_size(size.width as u32, size.height as u32);
+    }
+    pub fn set_title(&mut self, title: &str) {
+        self.window.set_title(title);
+    }
+    pub fn set_visible(&mut self, visible: bool) {
+        if visible {
+            self.window.show();
+        } else {
+            self.window.hide();
+        }
+    }
+    /// Get the position of the top left of the window on screen
+    pub fn position(&self) -> Option<Point> {
+        self.window.get_position().map(|(x, y)| Point::new(x as f32, y as f32))
+    }
+    pub fn set_position(&mut self, position: Point) {
+        self.window.set_position(position.x as i32, position.y as i32);
+    }
+    /// Make the window fullscreen on its current monitor, or restore it
+    pub fn set_fullscreen(&mut self, fullscreen: bool) {
+        let monitor = if fullscreen {
+            Some(self.window.get_current_monitor())
+        } else {
+            None
+        };
+        self.window.set_fullscreen(monitor);
+    }
+    pub fn set_maximized(&mut self, maximized: bool) {
+        self.window.set_maximized(maximized);
+    }
+    /// Set the minimum size of the window, the window is resized if it's smaller.
+    ///
+    /// The window manager can't be given new limits once the window is created, so the window
+    /// is resized back after the user resizes it past them, which can briefly show it at the wrong size.
+    /// Use `glutin::WindowBuilder::with_min_dimensions` to set limits the window manager enforces.
+    pub fn set_min_size(&mut self, min_size: Option<Size>) {
+        self.min_size = min_size;
+        self.enforce_size_limits();
+    }
+    /// Set the maximum size of the window, the window is resized if it's larger.
+    ///
+    /// Like `set_min_size`, the limit is enforced by resizing the window after it's resized past it,
+    /// use `glutin::WindowBuilder::with_max_dimensions` to set limits the window manager enforces.
+    pub fn set_max_size(&mut self, max_size: Option<Size>) {
+        self.max_size = max_size;
+        self.enforce_size_limits();
+    }
+    /// Limit a size to the minimum and maximum size of the window
+    pub fn clamp_size(&self, mut size: Size) -> Size {
+        if let Some(max_size) = self.max_size {
+            size = Size::new(size.width.min(max_size.width), size.height.min(max_size.height));
+        }
+        if let Some(min_size) = self.min_size {
+            size = Size::new(size.width.max(min_size.width), size.height.max(min_size.height));
+        }
+        size
+    }
+    /// Resize the window if it's outside of its size limits, returning the new size,
+    /// used for window managers that don't respect the limits
+    pub(crate) fn enforce_size_limits(&mut self) -> Size {
+        let size = self.size_dp();
+        let clamped_size = self.clamp_size(size);
+        if clamped_size != size {
+            self.window.set_inner_size(clamped_size.width as u32, clamped_size.height as u32);
+        }
+        clamped_size
+    }
+    pub fn set_cursor(&mut self, cursor: glutin::MouseCursor) {
+        if cursor != self.cursor {
+            self.cursor = cursor;
+            self.window.set_cursor(cursor);
+        }
     }
     /// Get the size of the client area of the window in actual pixels.
     /// This is the size of the framebuffer
@@ -0,0 +1,67 @@
+extern crate limn;
+
+use limn::prelude::*;
+use limn::golden::{render_draw_state, check_golden};
+use limn::draw::rect::RectState;
+use limn::draw::ellipse::EllipseState;
+use limn::draw::text::TextState;
+
+/// Per channel difference allowed between the rendered and golden images
+const TOLERANCE: u8 = 2;
+
+fn golden_path(name: &str) -> String {
+    format!("{}/tests/golden/{}.png", env!("CARGO_MANIFEST_DIR"), name)
+}
+
+fn assert_golden<T: Draw>(state: &mut T, size: Size, name: &str) {
+    let frame = render_draw_state(state, size);
+    if let Err(err) = check_golden(&frame, golden_path(name), TOLERANCE) {
+        panic!("{}: {}", name, err);
+    }
+}
+
+#[test]
+fn rect() {
+    let mut rect = RectState::new();
+    rect.background_color = BLUE;
+    assert_golden(&mut rect, Size::new(40.0, 30.0), "rect");
+}
+
+#[test]
+fn rect_rounded_border() {
+    let mut rect = RectState::new();
+    rect.background_color = GRAY_80;
+    rect.corner_radii = Some(CornerRadii {
+        top_left: Size::new(8.0, 8.0),
+        top_right: Size::new(4.0, 4.0),
+        bottom_left: Size::new(12.0, 6.0),
+        bottom_right: Size::zero(),
+    });
+    rect.border = Some((3.0, BLACK));
+    assert_golden(&mut rect, Size::new(60.0, 40.0), "rect_rounded_border");
+}
+
+#[test]
+fn rect_dashed_border() {
+    let mut rect = RectState::new();
+    rect.border = Some((2.0, RED));
+    rect.border_style = BorderStyle::Dashed;
+    assert_golden(&mut rect, Size::new(60.0, 40.0), "rect_dashed_border");
+}
+
+#[test]
+fn ellipse() {
+    let mut ellipse = EllipseState::new();
+    ellipse.background_color = GREEN;
+    ellipse.border = Some((2.0, BLACK));
+    assert_golden(&mut ellipse, Size::new(50.0, 30.0), "ellipse");
+}
+
+#[test]
+fn text() {
+    let mut text = TextState::new("Hello, golden world");
+    text.text_color = BLACK;
+    text.font_size = 16.0;
+    let size = text.measure();
+    assert_golden(&mut text, size, "text");
+}